@@ -0,0 +1,4 @@
+-- Add down migration script here
+
+DROP TABLE IF EXISTS note_share_links;
+ALTER TABLE notes DROP COLUMN IF EXISTS slug;
@@ -0,0 +1,25 @@
+-- Add migration script here
+
+ALTER TABLE notes ADD COLUMN IF NOT EXISTS slug VARCHAR(320) NULL UNIQUE;
+
+-- Give already published notes a stable slug, the same shape the service generates
+UPDATE notes
+SET slug = concat_ws(
+        '-',
+        NULLIF(trim(BOTH '-' FROM left(regexp_replace(lower(title), '[^a-z0-9]+', '-', 'g'), 80)), ''),
+        left(replace(id::text, '-', ''), 12)
+    )
+WHERE published = TRUE AND slug IS NULL;
+
+CREATE TABLE
+    IF NOT EXISTS note_share_links (
+        id UUID PRIMARY KEY NOT NULL DEFAULT (uuid_generate_v4()),
+        note_id UUID NOT NULL REFERENCES notes (id) ON DELETE CASCADE,
+        token VARCHAR(64) NOT NULL UNIQUE,
+        expires_at TIMESTAMP WITH TIME ZONE NULL,
+        created_by UUID NULL,
+        created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+        deleted_at TIMESTAMP WITH TIME ZONE NULL
+    );
+
+CREATE INDEX IF NOT EXISTS note_share_links_note_id_idx ON note_share_links (note_id);
@@ -0,0 +1,18 @@
+-- Add migration script here
+
+CREATE TABLE
+    IF NOT EXISTS attachments (
+        id UUID PRIMARY KEY NOT NULL DEFAULT (uuid_generate_v4()),
+        note_id UUID NOT NULL REFERENCES notes (id) ON DELETE CASCADE,
+        owner_id UUID NOT NULL,
+        file_name VARCHAR(255) NOT NULL,
+        content_type VARCHAR(100) NOT NULL,
+        size_bytes BIGINT NOT NULL CHECK( size_bytes >= 0 ),
+        checksum VARCHAR(64) NOT NULL,
+        storage_key VARCHAR(512) NOT NULL,
+        created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+        updated_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+        deleted_at TIMESTAMP WITH TIME ZONE NULL
+    );
+
+CREATE INDEX IF NOT EXISTS attachments_note_id_idx ON attachments (note_id);
@@ -0,0 +1,17 @@
+-- Add migration script here
+
+CREATE TABLE
+    IF NOT EXISTS note_comments (
+        id UUID PRIMARY KEY NOT NULL DEFAULT (uuid_generate_v4()),
+        note_id UUID NOT NULL REFERENCES notes (id) ON DELETE CASCADE,
+        parent_id UUID NULL REFERENCES note_comments (id) ON DELETE CASCADE,
+        content TEXT NOT NULL,
+        created_by UUID NOT NULL,
+        updated_by UUID NULL,
+        created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+        updated_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+        deleted_at TIMESTAMP WITH TIME ZONE NULL
+    );
+
+CREATE INDEX IF NOT EXISTS note_comments_note_id_idx ON note_comments (note_id);
+CREATE INDEX IF NOT EXISTS note_comments_parent_id_idx ON note_comments (parent_id);
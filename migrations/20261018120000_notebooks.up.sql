@@ -0,0 +1,20 @@
+-- Add migration script here
+
+CREATE TABLE
+    IF NOT EXISTS notebooks (
+        id UUID PRIMARY KEY NOT NULL DEFAULT (uuid_generate_v4()),
+        name VARCHAR(255) NOT NULL,
+        parent_id UUID NULL REFERENCES notebooks (id) ON DELETE SET NULL,
+        created_by UUID NOT NULL,
+        updated_by UUID NULL,
+        created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+        updated_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+        deleted_at TIMESTAMP WITH TIME ZONE NULL
+    );
+
+CREATE INDEX IF NOT EXISTS notebooks_parent_id_idx ON notebooks (parent_id);
+CREATE INDEX IF NOT EXISTS notebooks_created_by_idx ON notebooks (created_by);
+
+ALTER TABLE notes ADD COLUMN IF NOT EXISTS notebook_id UUID NULL REFERENCES notebooks (id) ON DELETE SET NULL;
+
+CREATE INDEX IF NOT EXISTS notes_notebook_id_idx ON notes (notebook_id);
@@ -0,0 +1,5 @@
+-- Add down migration script here
+
+DROP TABLE IF EXISTS note_favorites;
+ALTER TABLE notes DROP COLUMN IF EXISTS archived_at;
+ALTER TABLE notes DROP COLUMN IF EXISTS pinned;
@@ -0,0 +1,14 @@
+-- Add migration script here
+
+ALTER TABLE notes ADD COLUMN IF NOT EXISTS pinned BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE notes ADD COLUMN IF NOT EXISTS archived_at TIMESTAMP WITH TIME ZONE NULL;
+
+CREATE TABLE
+    IF NOT EXISTS note_favorites (
+        note_id UUID NOT NULL REFERENCES notes (id) ON DELETE CASCADE,
+        user_id UUID NOT NULL,
+        created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+        PRIMARY KEY (note_id, user_id)
+    );
+
+CREATE INDEX IF NOT EXISTS note_favorites_user_id_idx ON note_favorites (user_id);
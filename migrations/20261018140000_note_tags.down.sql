@@ -0,0 +1,4 @@
+-- Add down migration script here
+
+DROP INDEX IF EXISTS notes_tags_idx;
+ALTER TABLE notes DROP COLUMN IF EXISTS tags;
@@ -0,0 +1,5 @@
+-- Add migration script here
+
+ALTER TABLE notes ADD COLUMN IF NOT EXISTS tags TEXT[] NOT NULL DEFAULT '{}';
+
+CREATE INDEX IF NOT EXISTS notes_tags_idx ON notes USING GIN (tags);
@@ -0,0 +1,3 @@
+-- Add migration script here
+
+ALTER TABLE notes ADD COLUMN IF NOT EXISTS version INTEGER NOT NULL DEFAULT 1;
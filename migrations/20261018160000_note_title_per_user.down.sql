@@ -0,0 +1,4 @@
+-- Add down migration script here
+
+DROP INDEX IF EXISTS notes_created_by_title_key;
+ALTER TABLE notes ADD CONSTRAINT notes_title_key UNIQUE (title);
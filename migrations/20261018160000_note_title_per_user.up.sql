@@ -0,0 +1,5 @@
+-- Add migration script here
+
+-- Titles only have to be unique among one user's live notes, a trashed title can be reused
+ALTER TABLE notes DROP CONSTRAINT IF EXISTS notes_title_key;
+CREATE UNIQUE INDEX IF NOT EXISTS notes_created_by_title_key ON notes (created_by, title) WHERE deleted_at IS NULL;
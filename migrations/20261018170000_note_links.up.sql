@@ -0,0 +1,19 @@
+-- Add migration script here
+
+-- Wiki links keep the title they point at, they are resolved against the author's live notes
+-- when read so a link to a note that does not exist yet starts working once it is created
+CREATE TABLE
+    IF NOT EXISTS note_links (
+        source_id UUID NOT NULL REFERENCES notes (id) ON DELETE CASCADE,
+        target_title VARCHAR(255) NOT NULL,
+        PRIMARY KEY (source_id, target_title)
+    );
+
+CREATE INDEX IF NOT EXISTS note_links_target_title_idx ON note_links (target_title);
+
+-- Pick up the links already written in existing notes, the same way the service parses them
+INSERT INTO note_links (source_id, target_title)
+SELECT DISTINCT n.id, trim(m[1])
+FROM notes n, regexp_matches(n.content, '\[\[([^\[\]\n]+)\]\]', 'g') AS m
+WHERE trim(m[1]) <> '' AND char_length(trim(m[1])) <= 255
+ON CONFLICT DO NOTHING;
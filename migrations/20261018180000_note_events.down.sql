@@ -0,0 +1,8 @@
+-- Add down migration script here
+
+DROP TRIGGER IF EXISTS note_share_links_record_event ON note_share_links;
+DROP TRIGGER IF EXISTS notes_record_event ON notes;
+DROP FUNCTION IF EXISTS note_share_links_record_event();
+DROP FUNCTION IF EXISTS notes_record_event();
+DROP FUNCTION IF EXISTS record_note_event(notes, VARCHAR);
+DROP TABLE IF EXISTS note_events;
@@ -0,0 +1,71 @@
+-- Add migration script here
+
+-- Every change to a note is written here and announced on the note_events channel, so each
+-- server instance can push it to the clients connected to it. user_id is the owner of the note
+-- and public tells whether every user can see it, only the latest events are kept for resuming
+CREATE TABLE
+    IF NOT EXISTS note_events (
+        id BIGSERIAL PRIMARY KEY,
+        note_id UUID NOT NULL,
+        user_id UUID NULL,
+        public BOOLEAN NOT NULL DEFAULT FALSE,
+        event VARCHAR(20) NOT NULL,
+        created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW()
+    );
+
+CREATE OR REPLACE FUNCTION record_note_event(note notes, kind VARCHAR) RETURNS VOID AS $$
+DECLARE
+    recorded note_events;
+BEGIN
+    INSERT INTO note_events (note_id, user_id, public, event)
+    VALUES (note.id, note.created_by, COALESCE(note.published, FALSE), kind)
+    RETURNING * INTO recorded;
+
+    DELETE FROM note_events WHERE id <= recorded.id - 10000;
+    PERFORM pg_notify('note_events', row_to_json(recorded)::text);
+END;
+$$ LANGUAGE plpgsql;
+
+-- a note coming back from the trash is announced as created again, a note getting published
+-- as shared. changes made while a note is in the trash are not announced at all, and neither is
+-- the slug being filled in right after the note got published
+CREATE OR REPLACE FUNCTION notes_record_event() RETURNS TRIGGER AS $$
+BEGIN
+    IF TG_OP = 'INSERT' THEN
+        PERFORM record_note_event(NEW, 'created');
+    ELSIF NEW.deleted_at IS NOT NULL AND OLD.deleted_at IS NULL THEN
+        PERFORM record_note_event(NEW, 'deleted');
+    ELSIF NEW.deleted_at IS NULL AND OLD.deleted_at IS NOT NULL THEN
+        PERFORM record_note_event(NEW, 'created');
+    ELSIF NEW.deleted_at IS NULL AND to_jsonb(NEW) - 'slug' IS DISTINCT FROM to_jsonb(OLD) - 'slug' THEN
+        IF COALESCE(NEW.published, FALSE) AND NOT COALESCE(OLD.published, FALSE) THEN
+            PERFORM record_note_event(NEW, 'shared');
+        ELSE
+            PERFORM record_note_event(NEW, 'updated');
+        END IF;
+    END IF;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE FUNCTION note_share_links_record_event() RETURNS TRIGGER AS $$
+DECLARE
+    note notes;
+BEGIN
+    SELECT * INTO note FROM notes WHERE id = NEW.note_id AND deleted_at IS NULL;
+    IF FOUND THEN
+        PERFORM record_note_event(note, 'shared');
+    END IF;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS notes_record_event ON notes;
+CREATE TRIGGER notes_record_event
+    AFTER INSERT OR UPDATE ON notes
+    FOR EACH ROW EXECUTE FUNCTION notes_record_event();
+
+DROP TRIGGER IF EXISTS note_share_links_record_event ON note_share_links;
+CREATE TRIGGER note_share_links_record_event
+    AFTER INSERT ON note_share_links
+    FOR EACH ROW EXECUTE FUNCTION note_share_links_record_event();
@@ -0,0 +1,27 @@
+-- Add down migration script here
+
+CREATE OR REPLACE FUNCTION notes_record_event() RETURNS TRIGGER AS $$
+BEGIN
+    IF TG_OP = 'INSERT' THEN
+        PERFORM record_note_event(NEW, 'created');
+    ELSIF NEW.deleted_at IS NOT NULL AND OLD.deleted_at IS NULL THEN
+        PERFORM record_note_event(NEW, 'deleted');
+    ELSIF NEW.deleted_at IS NULL AND OLD.deleted_at IS NOT NULL THEN
+        PERFORM record_note_event(NEW, 'created');
+    ELSIF NEW.deleted_at IS NULL AND to_jsonb(NEW) - 'slug' IS DISTINCT FROM to_jsonb(OLD) - 'slug' THEN
+        IF COALESCE(NEW.published, FALSE) AND NOT COALESCE(OLD.published, FALSE) THEN
+            PERFORM record_note_event(NEW, 'shared');
+        ELSE
+            PERFORM record_note_event(NEW, 'updated');
+        END IF;
+    END IF;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP INDEX IF EXISTS notes_pending_reminder_idx;
+DROP INDEX IF EXISTS notes_due_at_idx;
+ALTER TABLE notes
+    DROP COLUMN IF EXISTS reminded_at,
+    DROP COLUMN IF EXISTS remind_at,
+    DROP COLUMN IF EXISTS due_at;
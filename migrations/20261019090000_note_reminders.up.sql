@@ -0,0 +1,33 @@
+-- Add migration script here
+
+-- due_at is only informative, remind_at is when the owner gets notified. reminded_at is set
+-- once the reminder went out and cleared again whenever remind_at changes
+ALTER TABLE notes
+    ADD COLUMN IF NOT EXISTS due_at TIMESTAMP WITH TIME ZONE NULL,
+    ADD COLUMN IF NOT EXISTS remind_at TIMESTAMP WITH TIME ZONE NULL,
+    ADD COLUMN IF NOT EXISTS reminded_at TIMESTAMP WITH TIME ZONE NULL;
+
+CREATE INDEX IF NOT EXISTS notes_due_at_idx ON notes (created_by, due_at) WHERE deleted_at IS NULL;
+CREATE INDEX IF NOT EXISTS notes_pending_reminder_idx ON notes (remind_at)
+    WHERE reminded_at IS NULL AND deleted_at IS NULL;
+
+-- a reminder going out is not a change of the note, so it is not announced either
+CREATE OR REPLACE FUNCTION notes_record_event() RETURNS TRIGGER AS $$
+BEGIN
+    IF TG_OP = 'INSERT' THEN
+        PERFORM record_note_event(NEW, 'created');
+    ELSIF NEW.deleted_at IS NOT NULL AND OLD.deleted_at IS NULL THEN
+        PERFORM record_note_event(NEW, 'deleted');
+    ELSIF NEW.deleted_at IS NULL AND OLD.deleted_at IS NOT NULL THEN
+        PERFORM record_note_event(NEW, 'created');
+    ELSIF NEW.deleted_at IS NULL
+        AND to_jsonb(NEW) - 'slug' - 'reminded_at' IS DISTINCT FROM to_jsonb(OLD) - 'slug' - 'reminded_at' THEN
+        IF COALESCE(NEW.published, FALSE) AND NOT COALESCE(OLD.published, FALSE) THEN
+            PERFORM record_note_event(NEW, 'shared');
+        ELSE
+            PERFORM record_note_event(NEW, 'updated');
+        END IF;
+    END IF;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
@@ -0,0 +1,16 @@
+-- Add migration script here
+
+CREATE TABLE
+    IF NOT EXISTS note_checklist_items (
+        id UUID PRIMARY KEY NOT NULL DEFAULT (uuid_generate_v4()),
+        note_id UUID NOT NULL REFERENCES notes (id) ON DELETE CASCADE,
+        position INTEGER NOT NULL DEFAULT 0,
+        text VARCHAR(1000) NOT NULL,
+        checked BOOLEAN NOT NULL DEFAULT FALSE,
+        created_by UUID NOT NULL,
+        updated_by UUID NULL,
+        created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+        updated_at TIMESTAMP WITH TIME ZONE DEFAULT NOW()
+    );
+
+CREATE INDEX IF NOT EXISTS note_checklist_items_note_id_idx ON note_checklist_items (note_id, position);
@@ -0,0 +1,20 @@
+-- Add migration script here
+
+-- title and content may carry {{placeholders}} that are filled in when a note is created
+-- from the template
+CREATE TABLE
+    IF NOT EXISTS note_templates (
+        id UUID PRIMARY KEY NOT NULL DEFAULT (uuid_generate_v4()),
+        name VARCHAR(255) NOT NULL,
+        title VARCHAR(255) NOT NULL,
+        content TEXT NOT NULL,
+        category VARCHAR(100) NULL,
+        tags TEXT[] NOT NULL DEFAULT '{}',
+        created_by UUID NOT NULL,
+        updated_by UUID NULL,
+        created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+        updated_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+        deleted_at TIMESTAMP WITH TIME ZONE NULL
+    );
+
+CREATE INDEX IF NOT EXISTS note_templates_created_by_idx ON note_templates (created_by);
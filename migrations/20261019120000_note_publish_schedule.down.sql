@@ -0,0 +1,8 @@
+-- Add down migration script here
+
+DROP INDEX IF EXISTS notes_unpublish_at_idx;
+DROP INDEX IF EXISTS notes_publish_at_idx;
+ALTER TABLE notes
+    DROP COLUMN IF EXISTS scheduled_by,
+    DROP COLUMN IF EXISTS unpublish_at,
+    DROP COLUMN IF EXISTS publish_at;
@@ -0,0 +1,13 @@
+-- Add migration script here
+
+-- the scheduler publishes or unpublishes the note once the time is reached and clears the
+-- timestamp again. scheduled_by is the user who last set one of them
+ALTER TABLE notes
+    ADD COLUMN IF NOT EXISTS publish_at TIMESTAMP WITH TIME ZONE NULL,
+    ADD COLUMN IF NOT EXISTS unpublish_at TIMESTAMP WITH TIME ZONE NULL,
+    ADD COLUMN IF NOT EXISTS scheduled_by UUID NULL;
+
+CREATE INDEX IF NOT EXISTS notes_publish_at_idx ON notes (publish_at)
+    WHERE publish_at IS NOT NULL AND deleted_at IS NULL;
+CREATE INDEX IF NOT EXISTS notes_unpublish_at_idx ON notes (unpublish_at)
+    WHERE unpublish_at IS NOT NULL AND deleted_at IS NULL;
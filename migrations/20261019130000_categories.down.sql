@@ -0,0 +1,7 @@
+-- Add down migration script here
+
+DROP TRIGGER IF EXISTS notes_resolve_category ON notes;
+DROP FUNCTION IF EXISTS notes_resolve_category();
+DROP INDEX IF EXISTS notes_category_id_idx;
+ALTER TABLE notes DROP COLUMN IF EXISTS category_id;
+DROP TABLE IF EXISTS categories;
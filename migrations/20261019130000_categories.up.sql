@@ -0,0 +1,69 @@
+-- Add migration script here
+
+-- every user keeps their own list of categories, names are unique regardless of case
+CREATE TABLE
+    IF NOT EXISTS categories (
+        id UUID PRIMARY KEY NOT NULL DEFAULT (uuid_generate_v4()),
+        name VARCHAR(100) NOT NULL,
+        color VARCHAR(7) NOT NULL DEFAULT '#9e9e9e',
+        sort_order INTEGER NOT NULL DEFAULT 0,
+        created_by UUID NOT NULL,
+        updated_by UUID NULL,
+        created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+        updated_at TIMESTAMP WITH TIME ZONE DEFAULT NOW()
+    );
+
+CREATE UNIQUE INDEX IF NOT EXISTS categories_created_by_name_idx ON categories (created_by, lower(name));
+
+ALTER TABLE notes ADD COLUMN IF NOT EXISTS category_id UUID NULL REFERENCES categories (id) ON DELETE SET NULL;
+
+CREATE INDEX IF NOT EXISTS notes_category_id_idx ON notes (category_id);
+
+-- notes.category stays as the denormalized name of category_id. whatever writes a category
+-- name gets it matched to the owner's category without regard to case, a name the owner has
+-- no category for yet creates one, and the note takes over the spelling of the category
+CREATE OR REPLACE FUNCTION notes_resolve_category() RETURNS TRIGGER AS $$
+DECLARE
+    resolved categories;
+BEGIN
+    IF NEW.created_by IS NULL OR btrim(COALESCE(NEW.category, '')) = '' THEN
+        NEW.category_id := NULL;
+        RETURN NEW;
+    END IF;
+
+    INSERT INTO categories (name, sort_order, created_by)
+    SELECT btrim(NEW.category), COALESCE(max(sort_order), -1) + 1, NEW.created_by
+    FROM categories WHERE created_by = NEW.created_by
+    ON CONFLICT (created_by, lower(name)) DO NOTHING;
+    SELECT * INTO resolved FROM categories
+    WHERE created_by = NEW.created_by AND lower(name) = lower(btrim(NEW.category));
+
+    NEW.category_id := resolved.id;
+    NEW.category := resolved.name;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+-- the existing names are taken over in the spelling used most often and sorted by name,
+-- without announcing every note as changed
+INSERT INTO categories (name, sort_order, created_by)
+SELECT name, row_number() OVER (PARTITION BY created_by ORDER BY lower(name)) - 1, created_by
+FROM (
+    SELECT DISTINCT ON (created_by, lower(btrim(category))) btrim(category) AS name, created_by
+    FROM notes
+    WHERE created_by IS NOT NULL AND btrim(COALESCE(category, '')) <> ''
+    GROUP BY created_by, btrim(category)
+    ORDER BY created_by, lower(btrim(category)), count(*) DESC, btrim(category)
+) existing
+ON CONFLICT (created_by, lower(name)) DO NOTHING;
+
+ALTER TABLE notes DISABLE TRIGGER notes_record_event;
+UPDATE notes n SET category_id = c.id, category = c.name
+FROM categories c
+WHERE c.created_by = n.created_by AND lower(c.name) = lower(btrim(n.category));
+ALTER TABLE notes ENABLE TRIGGER notes_record_event;
+
+DROP TRIGGER IF EXISTS notes_resolve_category ON notes;
+CREATE TRIGGER notes_resolve_category
+    BEFORE INSERT OR UPDATE OF category, created_by ON notes
+    FOR EACH ROW EXECUTE FUNCTION notes_resolve_category();
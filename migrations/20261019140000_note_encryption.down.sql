@@ -0,0 +1,23 @@
+-- Add down migration script here
+
+CREATE OR REPLACE FUNCTION notes_record_event() RETURNS TRIGGER AS $$
+BEGIN
+    IF TG_OP = 'INSERT' THEN
+        PERFORM record_note_event(NEW, 'created');
+    ELSIF NEW.deleted_at IS NOT NULL AND OLD.deleted_at IS NULL THEN
+        PERFORM record_note_event(NEW, 'deleted');
+    ELSIF NEW.deleted_at IS NULL AND OLD.deleted_at IS NOT NULL THEN
+        PERFORM record_note_event(NEW, 'created');
+    ELSIF NEW.deleted_at IS NULL
+        AND to_jsonb(NEW) - 'slug' - 'reminded_at' IS DISTINCT FROM to_jsonb(OLD) - 'slug' - 'reminded_at' THEN
+        IF COALESCE(NEW.published, FALSE) AND NOT COALESCE(OLD.published, FALSE) THEN
+            PERFORM record_note_event(NEW, 'shared');
+        ELSE
+            PERFORM record_note_event(NEW, 'updated');
+        END IF;
+    END IF;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+ALTER TABLE notes DROP COLUMN IF EXISTS word_count;
@@ -0,0 +1,35 @@
+-- Add migration script here
+
+-- content may be stored encrypted, so the word count is kept next to it and written by the
+-- application from the plain text on every change
+ALTER TABLE notes ADD COLUMN IF NOT EXISTS word_count INTEGER NOT NULL DEFAULT 0;
+
+ALTER TABLE notes DISABLE TRIGGER notes_record_event;
+UPDATE notes SET word_count = (SELECT count(*) FROM regexp_matches(content, '\S+', 'g'))
+    WHERE content NOT LIKE 'enc:%';
+ALTER TABLE notes ENABLE TRIGGER notes_record_event;
+
+-- re-encrypting notes under a new key sets notes.skip_events for its transaction, the
+-- notes did not change for anyone reading them
+CREATE OR REPLACE FUNCTION notes_record_event() RETURNS TRIGGER AS $$
+BEGIN
+    IF current_setting('notes.skip_events', true) = 'on' THEN
+        RETURN NULL;
+    END IF;
+    IF TG_OP = 'INSERT' THEN
+        PERFORM record_note_event(NEW, 'created');
+    ELSIF NEW.deleted_at IS NOT NULL AND OLD.deleted_at IS NULL THEN
+        PERFORM record_note_event(NEW, 'deleted');
+    ELSIF NEW.deleted_at IS NULL AND OLD.deleted_at IS NOT NULL THEN
+        PERFORM record_note_event(NEW, 'created');
+    ELSIF NEW.deleted_at IS NULL
+        AND to_jsonb(NEW) - 'slug' - 'reminded_at' IS DISTINCT FROM to_jsonb(OLD) - 'slug' - 'reminded_at' THEN
+        IF COALESCE(NEW.published, FALSE) AND NOT COALESCE(OLD.published, FALSE) THEN
+            PERFORM record_note_event(NEW, 'shared');
+        ELSE
+            PERFORM record_note_event(NEW, 'updated');
+        END IF;
+    END IF;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
@@ -0,0 +1,36 @@
+-- Add migration script here
+
+-- a reminder is claimed before it is sent and the claim is released afterwards, so nothing
+-- stays locked while the channel is slow. a claim older than the sending of a whole batch
+-- belongs to an instance that went away and can be taken over. a failed reminder is tried
+-- again after reminder_retry_at until it ran out of attempts
+ALTER TABLE notes
+    ADD COLUMN IF NOT EXISTS reminder_claimed_at TIMESTAMP WITH TIME ZONE NULL,
+    ADD COLUMN IF NOT EXISTS reminder_attempts INTEGER NOT NULL DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS reminder_retry_at TIMESTAMP WITH TIME ZONE NULL;
+
+-- sending a reminder is not a change of the note, neither is claiming it or failing to send it
+CREATE OR REPLACE FUNCTION notes_record_event() RETURNS TRIGGER AS $$
+BEGIN
+    IF current_setting('notes.skip_events', true) = 'on' THEN
+        RETURN NULL;
+    END IF;
+    IF TG_OP = 'INSERT' THEN
+        PERFORM record_note_event(NEW, 'created');
+    ELSIF NEW.deleted_at IS NOT NULL AND OLD.deleted_at IS NULL THEN
+        PERFORM record_note_event(NEW, 'deleted');
+    ELSIF NEW.deleted_at IS NULL AND OLD.deleted_at IS NOT NULL THEN
+        PERFORM record_note_event(NEW, 'created');
+    ELSIF NEW.deleted_at IS NULL
+        AND to_jsonb(NEW) - 'slug' - 'reminded_at' - 'reminder_claimed_at' - 'reminder_attempts' - 'reminder_retry_at'
+            IS DISTINCT FROM
+            to_jsonb(OLD) - 'slug' - 'reminded_at' - 'reminder_claimed_at' - 'reminder_attempts' - 'reminder_retry_at' THEN
+        IF COALESCE(NEW.published, FALSE) AND NOT COALESCE(OLD.published, FALSE) THEN
+            PERFORM record_note_event(NEW, 'shared');
+        ELSE
+            PERFORM record_note_event(NEW, 'updated');
+        END IF;
+    END IF;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
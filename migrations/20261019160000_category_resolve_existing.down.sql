@@ -0,0 +1,23 @@
+-- Add down migration script here
+
+CREATE OR REPLACE FUNCTION notes_resolve_category() RETURNS TRIGGER AS $$
+DECLARE
+    resolved categories;
+BEGIN
+    IF NEW.created_by IS NULL OR btrim(COALESCE(NEW.category, '')) = '' THEN
+        NEW.category_id := NULL;
+        RETURN NEW;
+    END IF;
+
+    INSERT INTO categories (name, sort_order, created_by)
+    SELECT btrim(NEW.category), COALESCE(max(sort_order), -1) + 1, NEW.created_by
+    FROM categories WHERE created_by = NEW.created_by
+    ON CONFLICT (created_by, lower(name)) DO NOTHING;
+    SELECT * INTO resolved FROM categories
+    WHERE created_by = NEW.created_by AND lower(name) = lower(btrim(NEW.category));
+
+    NEW.category_id := resolved.id;
+    NEW.category := resolved.name;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
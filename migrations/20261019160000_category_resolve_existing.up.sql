@@ -0,0 +1,26 @@
+-- Add migration script here
+
+-- a category name is only matched to one of the owner's existing categories, a name the owner
+-- has no category for is refused instead of creating one. categories are made over /categories
+-- and by importing an archive
+CREATE OR REPLACE FUNCTION notes_resolve_category() RETURNS TRIGGER AS $$
+DECLARE
+    resolved categories;
+BEGIN
+    IF NEW.created_by IS NULL OR btrim(COALESCE(NEW.category, '')) = '' THEN
+        NEW.category_id := NULL;
+        RETURN NEW;
+    END IF;
+
+    SELECT * INTO resolved FROM categories
+    WHERE created_by = NEW.created_by AND lower(name) = lower(btrim(NEW.category));
+    IF NOT FOUND THEN
+        RAISE EXCEPTION 'category % not found', btrim(NEW.category)
+            USING ERRCODE = 'foreign_key_violation';
+    END IF;
+
+    NEW.category_id := resolved.id;
+    NEW.category := resolved.name;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
@@ -0,0 +1,3 @@
+-- Add down migration script here
+
+-- favorites dropped by the up migration can not be restored
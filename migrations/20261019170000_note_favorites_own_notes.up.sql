@@ -0,0 +1,6 @@
+-- Add migration script here
+
+-- favorites are only listed on the owner's notes, drop the ones left on notes of other users
+DELETE FROM note_favorites f
+USING notes n
+WHERE n.id = f.note_id AND n.created_by <> f.user_id;
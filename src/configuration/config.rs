@@ -16,6 +16,27 @@ pub struct Config {
     pub jwt_secret_key: Option<String>,
     pub access_token_expiry: Option<i32>,
     pub access_token_expiry_unit: Option<String>,
+    pub attachment_max_size: Option<i64>,
+    pub attachment_allowed_types: Option<String>,
+    pub storage_backend: Option<String>,
+    pub storage_local_path: Option<String>,
+    pub s3_endpoint: Option<String>,
+    pub s3_region: Option<String>,
+    pub s3_bucket: Option<String>,
+    pub s3_access_key: Option<String>,
+    pub s3_secret_key: Option<String>,
+    pub reminder_channel: Option<String>,
+    pub reminder_webhook_url: Option<String>,
+    pub reminder_webhook_secret: Option<String>,
+    pub smtp_host: Option<String>,
+    pub smtp_port: Option<i32>,
+    pub smtp_tls: Option<String>,
+    pub smtp_username: Option<String>,
+    pub smtp_password: Option<String>,
+    pub smtp_from: Option<String>,
+    pub encryption_keys: Option<String>,
+    pub encryption_key_file: Option<String>,
+    pub encryption_active_key: Option<u32>,
 }
 
 impl Config {
@@ -46,6 +67,33 @@ impl Config {
             .map(|p| p.parse::<i32>().ok())
             .flatten();
         let access_token_expiry_unit = env::var("ACCESS_TOKEN_EXPIRY_UNIT").ok();
+        let attachment_max_size = env::var("ATTACHMENT_MAX_SIZE")
+            .ok()
+            .and_then(|p| p.parse::<i64>().ok());
+        let attachment_allowed_types = env::var("ATTACHMENT_ALLOWED_TYPES").ok();
+        let storage_backend = env::var("STORAGE_BACKEND").ok();
+        let storage_local_path = env::var("STORAGE_LOCAL_PATH").ok();
+        let s3_endpoint = env::var("S3_ENDPOINT").ok();
+        let s3_region = env::var("S3_REGION").ok();
+        let s3_bucket = env::var("S3_BUCKET").ok();
+        let s3_access_key = env::var("S3_ACCESS_KEY").ok();
+        let s3_secret_key = env::var("S3_SECRET_KEY").ok();
+        let reminder_channel = env::var("REMINDER_CHANNEL").ok();
+        let reminder_webhook_url = env::var("REMINDER_WEBHOOK_URL").ok();
+        let reminder_webhook_secret = env::var("REMINDER_WEBHOOK_SECRET").ok();
+        let smtp_host = env::var("SMTP_HOST").ok();
+        let smtp_port = env::var("SMTP_PORT")
+            .ok()
+            .and_then(|p| p.parse::<i32>().ok());
+        let smtp_tls = env::var("SMTP_TLS").ok();
+        let smtp_username = env::var("SMTP_USERNAME").ok();
+        let smtp_password = env::var("SMTP_PASSWORD").ok();
+        let smtp_from = env::var("SMTP_FROM").ok();
+        let encryption_keys = env::var("ENCRYPTION_KEYS").ok();
+        let encryption_key_file = env::var("ENCRYPTION_KEY_FILE").ok();
+        let encryption_active_key = env::var("ENCRYPTION_ACTIVE_KEY")
+            .ok()
+            .and_then(|p| p.parse::<u32>().ok());
 
         Ok(Config {
             enable_log,
@@ -61,6 +109,27 @@ impl Config {
             jwt_secret_key,
             access_token_expiry,
             access_token_expiry_unit,
+            attachment_max_size,
+            attachment_allowed_types,
+            storage_backend,
+            storage_local_path,
+            s3_endpoint,
+            s3_region,
+            s3_bucket,
+            s3_access_key,
+            s3_secret_key,
+            reminder_channel,
+            reminder_webhook_url,
+            reminder_webhook_secret,
+            smtp_host,
+            smtp_port,
+            smtp_tls,
+            smtp_username,
+            smtp_password,
+            smtp_from,
+            encryption_keys,
+            encryption_key_file,
+            encryption_active_key,
         })
     }
 }
@@ -0,0 +1,108 @@
+use actix_web::web::Bytes;
+use std::cell::RefCell;
+use std::io::{self, Read, Write};
+use std::rc::Rc;
+use zip::write::{SimpleFileOptions, StreamWriter};
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+// everything the zip writer produces lands here until it is taken as the next chunk
+#[derive(Clone, Default)]
+struct ChunkBuffer(Rc<RefCell<Vec<u8>>>);
+
+impl Write for ChunkBuffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.borrow_mut().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+// builds a zip without seeking, so it can be sent to the client while it is being written
+pub struct ZipStream {
+    writer: ZipWriter<StreamWriter<ChunkBuffer>>,
+    buffer: ChunkBuffer,
+}
+
+impl Default for ZipStream {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ZipStream {
+    pub fn new() -> ZipStream {
+        let buffer = ChunkBuffer::default();
+        ZipStream {
+            writer: ZipWriter::new_stream(buffer.clone()),
+            buffer,
+        }
+    }
+
+    pub fn start_file(&mut self, name: &str) -> io::Result<()> {
+        let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+        self.writer
+            .start_file(name, options)
+            .map_err(io::Error::other)
+    }
+
+    pub fn write_all(&mut self, bytes: &[u8]) -> io::Result<()> {
+        self.writer.write_all(bytes)
+    }
+
+    pub fn take_chunk(&mut self) -> Bytes {
+        let chunk = std::mem::take(&mut *self.buffer.0.borrow_mut());
+        Bytes::from(chunk)
+    }
+
+    pub fn finish(self) -> io::Result<Bytes> {
+        let buffer = self.writer.finish().map_err(io::Error::other)?.into_inner();
+        let chunk = std::mem::take(&mut *buffer.0.borrow_mut());
+        Ok(Bytes::from(chunk))
+    }
+}
+
+// one file read out of an uploaded zip, `truncated` is set when it was bigger than the limit
+pub struct ArchiveFile {
+    pub name: String,
+    pub bytes: Vec<u8>,
+    pub truncated: bool,
+}
+
+// reads every regular file of a zip, never decompressing more of an entry than the
+// `max_file_size` given for its name so a small upload cannot expand into an unbounded
+// amount of memory
+pub fn read_zip(
+    bytes: &[u8],
+    max_files: usize,
+    max_file_size: impl Fn(&str) -> u64,
+) -> io::Result<Vec<ArchiveFile>> {
+    let mut archive = ZipArchive::new(io::Cursor::new(bytes)).map_err(io::Error::other)?;
+    if archive.len() > max_files {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "archive has too many entries",
+        ));
+    }
+
+    let mut files: Vec<ArchiveFile> = Vec::new();
+    for index in 0..archive.len() {
+        let file = archive.by_index(index).map_err(io::Error::other)?;
+        if file.is_dir() {
+            continue;
+        }
+        let name = file.name().to_string();
+        let max_file_size = max_file_size(&name);
+        let mut bytes: Vec<u8> = Vec::new();
+        file.take(max_file_size + 1).read_to_end(&mut bytes)?;
+        let truncated = bytes.len() as u64 > max_file_size;
+        files.push(ArchiveFile {
+            name,
+            bytes,
+            truncated,
+        });
+    }
+    Ok(files)
+}
@@ -0,0 +1,411 @@
+use actix_ws::{AggregatedMessage, AggregatedMessageStream, Session};
+use automerge::sync::{self, SyncDoc};
+use automerge::transaction::Transactable;
+use automerge::{Automerge, AutomergeError, ObjType, ReadDoc, Value, ROOT};
+use futures::channel::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use futures::future::{self, Either};
+use futures::lock::{Mutex as AsyncMutex, MutexGuard as AsyncMutexGuard};
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::io;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, MutexGuard};
+use uuid::Uuid;
+
+// the shared document keeps the note content as a text object under this key
+const CONTENT_KEY: &str = "content";
+
+enum Outbound {
+    Sync(Vec<u8>),
+    Presence(String),
+}
+
+#[derive(Serialize)]
+struct PeerPresence<'a> {
+    peer: u64,
+    user: Uuid,
+    cursor: Option<&'a serde_json::Value>,
+}
+
+// sent to the clients as text frames, the document itself only travels in binary frames
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum PresenceEvent<'a> {
+    Peers {
+        peer: u64,
+        peers: Vec<PeerPresence<'a>>,
+    },
+    Join {
+        peer: u64,
+        user: Uuid,
+    },
+    Cursor {
+        peer: u64,
+        user: Uuid,
+        cursor: &'a serde_json::Value,
+    },
+    Leave {
+        peer: u64,
+        user: Uuid,
+    },
+}
+
+// what a client may send in a text frame, the cursor is relayed to the others as it is
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ClientPresence {
+    Cursor { cursor: serde_json::Value },
+}
+
+struct Peer {
+    user_id: Uuid,
+    sync_state: sync::State,
+    outbox: UnboundedSender<Outbound>,
+    cursor: Option<serde_json::Value>,
+}
+
+// `version` is the version of the note the document was last seeded from or saved as, a save
+// only goes through while the note is still at it
+struct Room {
+    doc: Automerge,
+    peers: HashMap<u64, Peer>,
+    dirty: bool,
+    editor: Option<Uuid>,
+    version: Option<i32>,
+}
+
+impl Room {
+    fn new(content: &str, version: Option<i32>) -> Result<Room, AutomergeError> {
+        let mut doc = Automerge::new();
+        let mut tx = doc.transaction();
+        let text = tx.put_object(ROOT, CONTENT_KEY, ObjType::Text)?;
+        tx.splice_text(&text, 0, 0, content)?;
+        tx.commit();
+        Ok(Room {
+            doc,
+            peers: HashMap::new(),
+            dirty: false,
+            editor: None,
+            version,
+        })
+    }
+
+    // replaces the text with the saved content as a diff, so the clients keep their cursors
+    fn reseed(&mut self, content: &str, version: Option<i32>) -> Result<(), AutomergeError> {
+        let mut tx = self.doc.transaction();
+        let text = match tx.get(ROOT, CONTENT_KEY)? {
+            Some((Value::Object(ObjType::Text), text)) => text,
+            _ => tx.put_object(ROOT, CONTENT_KEY, ObjType::Text)?,
+        };
+        tx.update_text(&text, content)?;
+        tx.commit();
+        self.dirty = false;
+        self.version = version;
+        self.sync_peers();
+        Ok(())
+    }
+
+    // None when a client replaced the text object with something else
+    fn content(&self) -> Option<String> {
+        match self.doc.get(ROOT, CONTENT_KEY) {
+            Ok(Some((Value::Object(ObjType::Text), text))) => self.doc.text(&text).ok(),
+            _ => None,
+        }
+    }
+
+    fn broadcast(&self, except: u64, event: &PresenceEvent) {
+        let Ok(text) = serde_json::to_string(event) else {
+            return;
+        };
+        for (peer_id, peer) in &self.peers {
+            if *peer_id != except {
+                let _ = peer.outbox.unbounded_send(Outbound::Presence(text.clone()));
+            }
+        }
+    }
+
+    fn sync_peers(&mut self) {
+        for peer in self.peers.values_mut() {
+            if let Some(message) = self.doc.generate_sync_message(&mut peer.sync_state) {
+                let _ = peer.outbox.unbounded_send(Outbound::Sync(message.encode()));
+            }
+        }
+    }
+}
+
+// one client connected to the document of a note
+pub struct CollabPeer {
+    pub note_id: Uuid,
+    pub peer_id: u64,
+    outbox: UnboundedReceiver<Outbound>,
+}
+
+// the merged content of a room that still has to be written back to its note
+pub struct CollabSnapshot {
+    pub note_id: Uuid,
+    pub content: String,
+    pub editor: Uuid,
+    pub version: Option<i32>,
+}
+
+// the documents being edited right now, one room per note shared by every worker. rooms live
+// in this process only, clients of one note connected to different instances end up in
+// different rooms and do not see each other. run a single instance or route a note's sessions
+// to the same one. the version check on save keeps such rooms from overwriting each other
+pub struct CollabHub {
+    rooms: Mutex<HashMap<Uuid, Room>>,
+    next_peer: AtomicU64,
+    saves: AsyncMutex<()>,
+}
+
+impl Default for CollabHub {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CollabHub {
+    pub fn new() -> CollabHub {
+        CollabHub {
+            rooms: Mutex::new(HashMap::new()),
+            next_peer: AtomicU64::new(1),
+            saves: AsyncMutex::new(()),
+        }
+    }
+
+    fn rooms(&self) -> MutexGuard<'_, HashMap<Uuid, Room>> {
+        self.rooms
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    // held while a room is saved and closed, and while a room is opened from the saved note, so
+    // a client joining right after the last one left never starts from content not saved yet
+    pub async fn lock_saves(&self) -> AsyncMutexGuard<'_, ()> {
+        self.saves.lock().await
+    }
+
+    // `content` and `version` are only used when nobody is editing the note yet
+    pub fn join(
+        &self,
+        note_id: Uuid,
+        content: &str,
+        version: Option<i32>,
+        user_id: Uuid,
+    ) -> io::Result<CollabPeer> {
+        let mut rooms = self.rooms();
+        let room = match rooms.entry(note_id) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                entry.insert(Room::new(content, version).map_err(io::Error::other)?)
+            }
+        };
+
+        let peer_id = self.next_peer.fetch_add(1, Ordering::Relaxed);
+        let (sender, outbox) = mpsc::unbounded();
+        let peers = room
+            .peers
+            .iter()
+            .map(|(peer, p)| PeerPresence {
+                peer: *peer,
+                user: p.user_id,
+                cursor: p.cursor.as_ref(),
+            })
+            .collect();
+        if let Ok(text) = serde_json::to_string(&PresenceEvent::Peers {
+            peer: peer_id,
+            peers,
+        }) {
+            let _ = sender.unbounded_send(Outbound::Presence(text));
+        }
+        room.broadcast(
+            peer_id,
+            &PresenceEvent::Join {
+                peer: peer_id,
+                user: user_id,
+            },
+        );
+
+        let mut peer = Peer {
+            user_id,
+            sync_state: sync::State::new(),
+            outbox: sender,
+            cursor: None,
+        };
+        if let Some(message) = room.doc.generate_sync_message(&mut peer.sync_state) {
+            let _ = peer.outbox.unbounded_send(Outbound::Sync(message.encode()));
+        }
+        room.peers.insert(peer_id, peer);
+
+        Ok(CollabPeer {
+            note_id,
+            peer_id,
+            outbox,
+        })
+    }
+
+    fn receive_sync(&self, note_id: Uuid, peer_id: u64, bytes: &[u8]) -> io::Result<()> {
+        let message = sync::Message::decode(bytes).map_err(io::Error::other)?;
+        let mut rooms = self.rooms();
+        let room = rooms
+            .get_mut(&note_id)
+            .ok_or_else(|| io::Error::other("room is missing"))?;
+        let peer = room
+            .peers
+            .get_mut(&peer_id)
+            .ok_or_else(|| io::Error::other("peer is missing"))?;
+
+        let heads = room.doc.get_heads();
+        room.doc
+            .receive_sync_message(&mut peer.sync_state, message)
+            .map_err(io::Error::other)?;
+        if room.doc.get_heads() != heads {
+            room.dirty = true;
+            room.editor = Some(peer.user_id);
+        }
+        room.sync_peers();
+        Ok(())
+    }
+
+    fn update_presence(&self, note_id: Uuid, peer_id: u64, text: &str) {
+        let Ok(ClientPresence::Cursor { cursor }) = serde_json::from_str(text) else {
+            return;
+        };
+        let mut rooms = self.rooms();
+        let Some(room) = rooms.get_mut(&note_id) else {
+            return;
+        };
+        let Some(peer) = room.peers.get_mut(&peer_id) else {
+            return;
+        };
+        peer.cursor = Some(cursor.clone());
+        let user_id = peer.user_id;
+        room.broadcast(
+            peer_id,
+            &PresenceEvent::Cursor {
+                peer: peer_id,
+                user: user_id,
+                cursor: &cursor,
+            },
+        );
+    }
+
+    // true once nobody is left editing the note
+    pub fn leave(&self, note_id: Uuid, peer_id: u64) -> bool {
+        let mut rooms = self.rooms();
+        let Some(room) = rooms.get_mut(&note_id) else {
+            return true;
+        };
+        if let Some(peer) = room.peers.remove(&peer_id) {
+            room.broadcast(
+                peer_id,
+                &PresenceEvent::Leave {
+                    peer: peer_id,
+                    user: peer.user_id,
+                },
+            );
+        }
+        room.peers.is_empty()
+    }
+
+    fn take_snapshot(note_id: Uuid, room: &mut Room) -> Option<CollabSnapshot> {
+        if !room.dirty {
+            return None;
+        }
+        room.dirty = false;
+        Some(CollabSnapshot {
+            note_id,
+            content: room.content()?,
+            editor: room.editor?,
+            version: room.version,
+        })
+    }
+
+    pub fn take_dirty(&self, note_id: Uuid) -> Option<CollabSnapshot> {
+        let mut rooms = self.rooms();
+        let room = rooms.get_mut(&note_id)?;
+        Self::take_snapshot(note_id, room)
+    }
+
+    pub fn take_all_dirty(&self) -> Vec<CollabSnapshot> {
+        self.rooms()
+            .iter_mut()
+            .filter_map(|(note_id, room)| Self::take_snapshot(*note_id, room))
+            .collect()
+    }
+
+    // a snapshot that could not be saved is tried again on the next round
+    pub fn mark_dirty(&self, note_id: Uuid) {
+        if let Some(room) = self.rooms().get_mut(&note_id) {
+            room.dirty = true;
+        }
+    }
+
+    // the next save is made against the version this one left the note at
+    pub fn mark_saved(&self, note_id: Uuid, version: Option<i32>) {
+        if let Some(room) = self.rooms().get_mut(&note_id) {
+            room.version = version;
+        }
+    }
+
+    // the note was changed outside the room, the saved note wins over what the room holds
+    pub fn reseed(&self, note_id: Uuid, content: &str, version: Option<i32>) -> io::Result<()> {
+        match self.rooms().get_mut(&note_id) {
+            Some(room) => room.reseed(content, version).map_err(io::Error::other),
+            None => Ok(()),
+        }
+    }
+
+    // drops the rooms nobody is connected to once everything in them has been saved
+    pub fn close_idle(&self) {
+        self.rooms()
+            .retain(|_, room| !room.peers.is_empty() || room.dirty);
+    }
+
+    // relays the frames of one client until it disconnects, binary frames carry automerge sync
+    // messages and text frames carry presence. true is returned when it was the last client
+    pub async fn serve(
+        &self,
+        mut peer: CollabPeer,
+        mut session: Session,
+        mut stream: AggregatedMessageStream,
+    ) -> bool {
+        loop {
+            match future::select(stream.next(), peer.outbox.next()).await {
+                Either::Left((message, _)) => match message {
+                    Some(Ok(AggregatedMessage::Binary(bytes))) => {
+                        if let Err(err) = self.receive_sync(peer.note_id, peer.peer_id, &bytes) {
+                            eprintln!("Error receive collaborative sync message: {:?}", err);
+                            break;
+                        }
+                    }
+                    Some(Ok(AggregatedMessage::Text(text))) => {
+                        self.update_presence(peer.note_id, peer.peer_id, &text)
+                    }
+                    Some(Ok(AggregatedMessage::Ping(bytes))) => {
+                        if session.pong(&bytes).await.is_err() {
+                            break;
+                        }
+                    }
+                    Some(Ok(AggregatedMessage::Pong(_))) => {}
+                    Some(Ok(AggregatedMessage::Close(_))) | Some(Err(_)) | None => break,
+                },
+                Either::Right((outbound, _)) => {
+                    let sent = match outbound {
+                        Some(Outbound::Sync(bytes)) => session.binary(bytes).await,
+                        Some(Outbound::Presence(text)) => session.text(text).await,
+                        None => break,
+                    };
+                    if sent.is_err() {
+                        break;
+                    }
+                }
+            }
+        }
+
+        let _ = session.close(None).await;
+        self.leave(peer.note_id, peer.peer_id)
+    }
+}
@@ -0,0 +1,38 @@
+use actix_web::http::header::{self, HeaderName};
+use actix_web::HttpRequest;
+
+// the entity tags listed in an If-Match / If-None-Match header, None when it is missing
+fn header_tags(req: &HttpRequest, name: HeaderName) -> Option<Vec<String>> {
+    let mut values = req.headers().get_all(name).peekable();
+    values.peek()?;
+
+    let tags = values
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(|tag| tag.trim().to_string())
+        .filter(|tag| !tag.is_empty())
+        .collect();
+    Some(tags)
+}
+
+// If-None-Match uses the weak comparison (RFC 9110 13.1.2), so `W/"3"` matches `"3"`
+pub fn none_match(req: &HttpRequest, etag: &str) -> bool {
+    header_tags(req, header::IF_NONE_MATCH).is_some_and(|tags| {
+        tags.iter()
+            .any(|tag| tag == "*" || tag.trim_start_matches("W/") == etag)
+    })
+}
+
+// If-Match uses the strong comparison, so weak tags are dropped. None means the request is
+// unconditional, either without the header or with `*`
+pub fn if_match(req: &HttpRequest) -> Option<Vec<String>> {
+    let tags = header_tags(req, header::IF_MATCH)?;
+    if tags.iter().any(|tag| tag == "*") {
+        return None;
+    }
+    Some(
+        tags.into_iter()
+            .filter(|tag| !tag.starts_with("W/"))
+            .collect(),
+    )
+}
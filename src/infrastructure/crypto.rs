@@ -0,0 +1,206 @@
+use crate::configuration::config::Config;
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use std::collections::BTreeMap;
+use std::sync::OnceLock;
+
+// sealed content looks like enc:v1:{key version}:{wrapped data key}:{ciphertext}. plain text
+// that happens to start with enc: is stored behind PLAIN_PREFIX, so it is never taken for
+// sealed content. anything else is plain text and read as is
+pub const SEALED_PREFIX: &str = "enc:v1:";
+const PLAIN_PREFIX: &str = "enc:plain:";
+const RESERVED_PREFIX: &str = "enc:";
+const NONCE_SIZE: usize = 12;
+const KEY_SIZE: usize = 32;
+
+// the repositories read and write note content with nothing but the pool at hand, so the
+// keyring is set up once at startup instead of being passed around in the app state
+static KEYRING: OnceLock<Option<Keyring>> = OnceLock::new();
+
+// master keys by version, the active one wraps the data keys of everything sealed now
+pub struct Keyring {
+    keys: BTreeMap<u32, Aes256Gcm>,
+    active: u32,
+}
+
+impl Keyring {
+    pub fn from_config(cfg: &Config) -> Result<Option<Keyring>, String> {
+        let mut entries: Vec<String> = cfg
+            .encryption_keys
+            .as_deref()
+            .unwrap_or_default()
+            .split(',')
+            .map(String::from)
+            .collect();
+        if let Some(path) = cfg.encryption_key_file.as_deref().filter(|p| !p.is_empty()) {
+            let file = std::fs::read_to_string(path)
+                .map_err(|err| format!("can not read encryption key file {}: {}", path, err))?;
+            entries.extend(file.lines().map(String::from));
+        }
+
+        let mut keys = BTreeMap::new();
+        for entry in entries.iter().map(|e| e.trim()) {
+            if entry.is_empty() || entry.starts_with('#') {
+                continue;
+            }
+            // never echo the entry back, it holds the key
+            let (version, key) = entry
+                .split_once(':')
+                .ok_or("encryption keys have to be given as version:base64 key")?;
+            let version = version
+                .trim()
+                .parse::<u32>()
+                .map_err(|_| format!("encryption key version {} is not a number", version))?;
+            let key = STANDARD
+                .decode(key.trim())
+                .map_err(|_| format!("encryption key {} is not valid base64", version))?;
+            if key.len() != KEY_SIZE {
+                return Err(format!(
+                    "encryption key {} has to be {} bytes",
+                    version, KEY_SIZE
+                ));
+            }
+            let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key));
+            if keys.insert(version, cipher).is_some() {
+                return Err(format!("encryption key {} is given twice", version));
+            }
+        }
+
+        let Some(latest) = keys.keys().last().copied() else {
+            return match cfg.encryption_active_key {
+                Some(_) => Err("an active encryption key is set but no keys are given".into()),
+                None => Ok(None),
+            };
+        };
+        let active = cfg.encryption_active_key.unwrap_or(latest);
+        if !keys.contains_key(&active) {
+            return Err(format!("active encryption key {} is not given", active));
+        }
+        Ok(Some(Keyring { keys, active }))
+    }
+
+    fn key(&self, version: u32) -> Result<&Aes256Gcm, String> {
+        self.keys
+            .get(&version)
+            .ok_or(format!("encryption key {} is not configured", version))
+    }
+
+    // every call draws a fresh data key, only the data key is encrypted with the master key
+    pub fn seal(&self, plain: &str) -> Result<String, String> {
+        let data_key = Aes256Gcm::generate_key(OsRng);
+        let nonce = Aes256Gcm::generate_nonce(OsRng);
+        let ciphertext = Aes256Gcm::new(&data_key)
+            .encrypt(&nonce, plain.as_bytes())
+            .map_err(|_| "can not encrypt content".to_string())?;
+
+        let wrap_nonce = Aes256Gcm::generate_nonce(OsRng);
+        let wrapped_key = self
+            .key(self.active)?
+            .encrypt(&wrap_nonce, data_key.as_slice())
+            .map_err(|_| "can not wrap data key".to_string())?;
+
+        Ok(format!(
+            "{}{}:{}:{}",
+            SEALED_PREFIX,
+            self.active,
+            STANDARD.encode([wrap_nonce.as_slice(), &wrapped_key].concat()),
+            STANDARD.encode([nonce.as_slice(), &ciphertext].concat())
+        ))
+    }
+
+    pub fn open(&self, sealed: &str) -> Result<String, String> {
+        let mut parts = sealed
+            .strip_prefix(SEALED_PREFIX)
+            .ok_or("content is not sealed")?
+            .splitn(3, ':');
+        let (Some(version), Some(wrapped_key), Some(ciphertext)) =
+            (parts.next(), parts.next(), parts.next())
+        else {
+            return Err("sealed content is malformed".into());
+        };
+        let version = version
+            .parse::<u32>()
+            .map_err(|_| "sealed content has no key version".to_string())?;
+
+        let data_key = decrypt(self.key(version)?, wrapped_key)
+            .map_err(|_| format!("can not unwrap data key with encryption key {}", version))?;
+        if data_key.len() != KEY_SIZE {
+            return Err("unwrapped data key has the wrong size".into());
+        }
+        let plain = decrypt(
+            &Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&data_key)),
+            ciphertext,
+        )
+        .map_err(|_| "can not decrypt content".to_string())?;
+        String::from_utf8(plain).map_err(|_| "decrypted content is not utf-8".to_string())
+    }
+}
+
+// nonce and ciphertext are stored together as one base64 string
+fn decrypt(cipher: &Aes256Gcm, encoded: &str) -> Result<Vec<u8>, ()> {
+    let bytes = STANDARD.decode(encoded).map_err(|_| ())?;
+    if bytes.len() < NONCE_SIZE {
+        return Err(());
+    }
+    let (nonce, ciphertext) = bytes.split_at(NONCE_SIZE);
+    cipher
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| ())
+}
+
+pub fn init(cfg: &Config) -> Result<(), String> {
+    let keyring = Keyring::from_config(cfg)?;
+    KEYRING
+        .set(keyring)
+        .map_err(|_| "encryption keys are already initialized".to_string())
+}
+
+fn keyring() -> Option<&'static Keyring> {
+    KEYRING.get().and_then(Option::as_ref)
+}
+
+pub fn enabled() -> bool {
+    keyring().is_some()
+}
+
+// without keys content is stored as plain text, like it was before
+pub fn seal(plain: &str) -> Result<String, String> {
+    match keyring() {
+        Some(keyring) => keyring.seal(plain),
+        None if plain.starts_with(RESERVED_PREFIX) => Ok(format!("{}{}", PLAIN_PREFIX, plain)),
+        None => Ok(plain.to_string()),
+    }
+}
+
+pub fn open(stored: &str) -> Result<String, String> {
+    if let Some(plain) = stored.strip_prefix(PLAIN_PREFIX) {
+        return Ok(plain.to_string());
+    }
+    if !stored.starts_with(SEALED_PREFIX) {
+        return Ok(stored.to_string());
+    }
+    keyring()
+        .ok_or("content is encrypted but no encryption keys are configured")?
+        .open(stored)
+}
+
+// content sealed with any of the configured keys starts with one of these
+pub fn key_prefixes() -> Vec<String> {
+    keyring()
+        .map(|keyring| {
+            keyring
+                .keys
+                .keys()
+                .map(|version| format!("{}{}:", SEALED_PREFIX, version))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+// content sealed with the active key starts with this, every other row still has to be
+// re-encrypted
+pub fn active_prefix() -> Option<String> {
+    keyring().map(|keyring| format!("{}{}:", SEALED_PREFIX, keyring.active))
+}
@@ -0,0 +1,60 @@
+use futures::channel::mpsc::{self, Receiver, Sender};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, MutexGuard};
+use uuid::Uuid;
+
+// events queued for one subscriber before it is considered too slow and dropped, a dropped
+// client reconnects and resumes from the event log
+const SUBSCRIBER_BUFFER: usize = 256;
+
+struct Subscriber<T> {
+    user_id: Uuid,
+    sender: Sender<T>,
+}
+
+// fans the events received by this server instance out to the clients connected to it
+pub struct EventHub<T> {
+    subscribers: Mutex<HashMap<u64, Subscriber<T>>>,
+    next_subscriber: AtomicU64,
+}
+
+impl<T: Clone> Default for EventHub<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Clone> EventHub<T> {
+    pub fn new() -> EventHub<T> {
+        EventHub {
+            subscribers: Mutex::new(HashMap::new()),
+            next_subscriber: AtomicU64::new(1),
+        }
+    }
+
+    fn subscribers(&self) -> MutexGuard<'_, HashMap<u64, Subscriber<T>>> {
+        self.subscribers
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    // the subscription ends when the receiver is dropped
+    pub fn subscribe(&self, user_id: Uuid) -> Receiver<T> {
+        let (sender, receiver) = mpsc::channel(SUBSCRIBER_BUFFER);
+        let id = self.next_subscriber.fetch_add(1, Ordering::Relaxed);
+        self.subscribers()
+            .insert(id, Subscriber { user_id, sender });
+        receiver
+    }
+
+    // sends the event to every subscriber `visible_to` accepts
+    pub fn publish(&self, event: &T, visible_to: impl Fn(Uuid) -> bool) {
+        self.subscribers().retain(|_, subscriber| {
+            if !visible_to(subscriber.user_id) {
+                return !subscriber.sender.is_closed();
+            }
+            subscriber.sender.try_send(event.clone()).is_ok()
+        });
+    }
+}
@@ -0,0 +1,124 @@
+use crate::infrastructure::html;
+use chrono::{DateTime, SecondsFormat, Utc};
+
+pub struct Feed {
+    pub id: String,
+    pub title: String,
+    pub author: String,
+    // where the feed itself is served, RSS also uses it as the channel link
+    pub url: String,
+    pub updated: DateTime<Utc>,
+    pub entries: Vec<FeedEntry>,
+}
+
+pub struct FeedEntry {
+    pub id: String,
+    pub title: String,
+    pub link: String,
+    pub category: Option<String>,
+    pub published: DateTime<Utc>,
+    pub updated: DateTime<Utc>,
+    // already rendered and sanitized, it is escaped once more as element text
+    pub content_html: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeedFormat {
+    Atom,
+    Rss,
+}
+
+impl FeedFormat {
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            FeedFormat::Atom => "application/atom+xml; charset=utf-8",
+            FeedFormat::Rss => "application/rss+xml; charset=utf-8",
+        }
+    }
+
+    pub fn render(&self, feed: &Feed) -> String {
+        match self {
+            FeedFormat::Atom => render_atom(feed),
+            FeedFormat::Rss => render_rss(feed),
+        }
+    }
+}
+
+fn atom_date(date: &DateTime<Utc>) -> String {
+    date.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+fn render_atom(feed: &Feed) -> String {
+    let mut xml = format!(
+        "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<feed xmlns=\"http://www.w3.org/2005/Atom\">\n\
+         <id>{}</id>\n<title>{}</title>\n<updated>{}</updated>\n\
+         <author><name>{}</name></author>\n\
+         <link rel=\"self\" type=\"application/atom+xml\" href=\"{}\"/>\n",
+        html::escape(&feed.id),
+        html::escape(&feed.title),
+        atom_date(&feed.updated),
+        html::escape(&feed.author),
+        html::escape(&feed.url)
+    );
+    for entry in &feed.entries {
+        xml.push_str(&format!(
+            "<entry>\n<id>{}</id>\n<title>{}</title>\n\
+             <link rel=\"alternate\" type=\"text/html\" href=\"{}\"/>\n\
+             <published>{}</published>\n<updated>{}</updated>\n",
+            html::escape(&entry.id),
+            html::escape(&entry.title),
+            html::escape(&entry.link),
+            atom_date(&entry.published),
+            atom_date(&entry.updated)
+        ));
+        if let Some(category) = &entry.category {
+            xml.push_str(&format!(
+                "<category term=\"{}\"/>\n",
+                html::escape(category)
+            ));
+        }
+        xml.push_str(&format!(
+            "<content type=\"html\">{}</content>\n</entry>\n",
+            html::escape(&entry.content_html)
+        ));
+    }
+    xml.push_str("</feed>\n");
+    xml
+}
+
+fn render_rss(feed: &Feed) -> String {
+    let mut xml = format!(
+        "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n\
+         <rss version=\"2.0\" xmlns:atom=\"http://www.w3.org/2005/Atom\">\n<channel>\n\
+         <title>{}</title>\n<link>{}</link>\n<description>{}</description>\n\
+         <atom:link href=\"{}\" rel=\"self\" type=\"application/rss+xml\"/>\n\
+         <lastBuildDate>{}</lastBuildDate>\n",
+        html::escape(&feed.title),
+        html::escape(&feed.url),
+        html::escape(&feed.title),
+        html::escape(&feed.url),
+        feed.updated.to_rfc2822()
+    );
+    for entry in &feed.entries {
+        xml.push_str(&format!(
+            "<item>\n<title>{}</title>\n<link>{}</link>\n\
+             <guid isPermaLink=\"false\">{}</guid>\n<pubDate>{}</pubDate>\n",
+            html::escape(&entry.title),
+            html::escape(&entry.link),
+            html::escape(&entry.id),
+            entry.published.to_rfc2822()
+        ));
+        if let Some(category) = &entry.category {
+            xml.push_str(&format!(
+                "<category>{}</category>\n",
+                html::escape(category)
+            ));
+        }
+        xml.push_str(&format!(
+            "<description>{}</description>\n</item>\n",
+            html::escape(&entry.content_html)
+        ));
+    }
+    xml.push_str("</channel>\n</rss>\n");
+    xml
+}
@@ -0,0 +1,40 @@
+pub fn escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#x27;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+// body_html is inserted as is, callers are responsible for escaping it
+pub fn render_page(title: &str, body_html: &str) -> String {
+    format!(
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<title>{}</title>
+<style>
+body {{ font-family: sans-serif; max-width: 48rem; margin: 2rem auto; padding: 0 1rem; line-height: 1.6; }}
+.meta {{ color: #666; font-size: 0.9rem; }}
+pre {{ overflow-x: auto; background: #f6f8fa; padding: 0.75rem; }}
+table {{ border-collapse: collapse; }}
+th, td {{ border: 1px solid #ddd; padding: 0.25rem 0.5rem; }}
+</style>
+</head>
+<body>
+{}
+</body>
+</html>"#,
+        escape(title),
+        body_html
+    )
+}
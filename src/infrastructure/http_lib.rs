@@ -53,10 +53,10 @@ pub struct Pagination<T> {
     status: String,
     code: u16,
     message: String,
-    page: u16,
-    size: u16,
-    total_count: u16,
-    total_pages: u16,
+    page: i32,
+    size: i32,
+    total_count: i64,
+    total_pages: i64,
     data: Option<T>,
 }
 
@@ -65,19 +65,48 @@ impl<T> Pagination<T> {
         pg: pagination::PaginationQuery,
         message: &str,
         data: T,
-        count: u16,
+        count: i64,
     ) -> Pagination<T> {
         Pagination {
             status: StatusCode::OK.to_string(),
             code: StatusCode::OK.as_u16(),
             message: message.to_string(),
-            page: pagination::get_page(pg.clone()) as u16,
-            size: pagination::get_size(pg.clone()) as u16,
+            page: pagination::get_page(pg.clone()),
+            size: pagination::get_size(pg.clone()),
             total_count: count,
-            total_pages: pagination::get_total_pages(
-                count.clone() as i32,
-                pagination::get_size(pg.clone()),
-            ) as u16,
+            total_pages: pagination::get_total_pages(count, pagination::get_size(pg.clone())),
+            data: Some(data),
+        }
+    }
+}
+
+// cursor mode has no page numbers and no total count, only the way forward and back
+#[derive(Debug, Serialize)]
+pub struct CursorPagination<T> {
+    status: String,
+    code: u16,
+    message: String,
+    size: i32,
+    next_cursor: Option<String>,
+    prev_cursor: Option<String>,
+    data: Option<T>,
+}
+
+impl<T> CursorPagination<T> {
+    pub fn success(
+        message: &str,
+        data: T,
+        size: i32,
+        next_cursor: Option<String>,
+        prev_cursor: Option<String>,
+    ) -> CursorPagination<T> {
+        CursorPagination {
+            status: StatusCode::OK.to_string(),
+            code: StatusCode::OK.as_u16(),
+            message: message.to_string(),
+            size,
+            next_cursor,
+            prev_cursor,
             data: Some(data),
         }
     }
@@ -0,0 +1,111 @@
+use ammonia::Builder;
+use pulldown_cmark::{html, Options, Parser};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use uuid::Uuid;
+
+const DEFAULT_CACHE_CAPACITY: usize = 1024;
+
+// CommonMark plus the GFM extensions (tables, task lists, strikethrough, footnotes)
+fn markdown_options() -> Options {
+    let mut options = Options::empty();
+    options.insert(Options::ENABLE_TABLES);
+    options.insert(Options::ENABLE_TASKLISTS);
+    options.insert(Options::ENABLE_STRIKETHROUGH);
+    options.insert(Options::ENABLE_FOOTNOTES);
+    options.insert(Options::ENABLE_GFM);
+    options
+}
+
+fn sanitize(unsafe_html: &str) -> String {
+    Builder::default()
+        .add_tags(&["input"])
+        .add_tag_attributes("input", &["type", "checked", "disabled"])
+        .add_tag_attributes("code", &["class"])
+        .attribute_filter(|element, attribute, value| match (element, attribute) {
+            // only the read-only checkboxes emitted for task lists are kept
+            ("input", "type") if value != "checkbox" => None,
+            ("code", "class") if !value.starts_with("language-") => None,
+            _ => Some(Cow::Borrowed(value)),
+        })
+        .link_rel(Some("noopener"))
+        .clean(unsafe_html)
+        .to_string()
+}
+
+pub fn render(content: &str) -> String {
+    let parser = Parser::new_ext(content, markdown_options());
+    let mut unsafe_html = String::new();
+    html::push_html(&mut unsafe_html, parser);
+    sanitize(&unsafe_html)
+}
+
+struct CachedHtml {
+    revision: String,
+    html: String,
+    used: u64,
+}
+
+// `clock` counts the lookups, an entry remembers the one it was last used in
+#[derive(Default)]
+struct RenderEntries {
+    notes: HashMap<Uuid, CachedHtml>,
+    clock: u64,
+}
+
+pub struct RenderCache {
+    capacity: usize,
+    entries: Mutex<RenderEntries>,
+}
+
+impl Default for RenderCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RenderCache {
+    pub fn new() -> RenderCache {
+        RenderCache {
+            capacity: DEFAULT_CACHE_CAPACITY,
+            entries: Mutex::new(RenderEntries::default()),
+        }
+    }
+
+    // one entry per note, a new revision replaces the html rendered for the previous one.
+    // when the cache is full the note that was used the longest ago makes room
+    pub fn get_or_render(&self, note_id: Uuid, revision: &str, content: &str) -> String {
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.clock += 1;
+            let clock = entries.clock;
+            if let Some(cached) = entries.notes.get_mut(&note_id) {
+                if cached.revision == revision {
+                    cached.used = clock;
+                    return cached.html.clone();
+                }
+            }
+        }
+
+        let rendered = render(content);
+        if let Ok(mut entries) = self.entries.lock() {
+            if entries.notes.len() >= self.capacity && !entries.notes.contains_key(&note_id) {
+                let oldest = entries
+                    .notes
+                    .iter()
+                    .min_by_key(|(_, cached)| cached.used)
+                    .map(|(id, _)| *id);
+                if let Some(oldest) = oldest {
+                    entries.notes.remove(&oldest);
+                }
+            }
+            let cached = CachedHtml {
+                revision: revision.to_string(),
+                html: rendered.clone(),
+                used: entries.clock,
+            };
+            entries.notes.insert(note_id, cached);
+        }
+        rendered
+    }
+}
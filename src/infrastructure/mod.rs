@@ -1,4 +1,14 @@
+pub mod archive;
 pub mod auth_jwt;
+pub mod collab;
+pub mod conditional;
+pub mod crypto;
+pub mod events;
+pub mod feed;
+pub mod html;
 pub mod http_lib;
+pub mod markdown;
+pub mod notifier;
 pub mod pagination;
 pub mod password;
+pub mod storage;
@@ -0,0 +1,186 @@
+use crate::configuration::config::Config;
+use async_trait::async_trait;
+use lettre::message::header::ContentType;
+use lettre::message::Mailbox;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use serde::Serialize;
+use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
+
+const DEFAULT_SMTP_PORT: u16 = 587;
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+// what a channel gets to tell the owner of a note, also the body posted to a webhook
+#[derive(Debug, Serialize, Clone)]
+pub struct Reminder {
+    pub note_id: Uuid,
+    pub title: String,
+    #[serde(rename = "dueAt")]
+    pub due_at: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(rename = "remindAt")]
+    pub remind_at: chrono::DateTime<chrono::Utc>,
+    pub user_id: Uuid,
+    pub email: Option<String>,
+    pub fullname: Option<String>,
+}
+
+impl Reminder {
+    fn text(&self) -> String {
+        let mut text = format!("Reminder for your note \"{}\".", self.title);
+        if let Some(due_at) = self.due_at {
+            text.push_str(&format!("\nIt is due at {}.", due_at.to_rfc3339()));
+        }
+        text
+    }
+}
+
+#[async_trait]
+pub trait Notifier: Send + Sync {
+    async fn notify(&self, reminder: &Reminder) -> Result<(), String>;
+}
+
+// the default channel only writes the reminder to the server output
+pub struct LogNotifier;
+
+#[async_trait]
+impl Notifier for LogNotifier {
+    async fn notify(&self, reminder: &Reminder) -> Result<(), String> {
+        println!(
+            "Reminder for user {} on note {}: {}",
+            reminder.user_id,
+            reminder.note_id,
+            reminder.text()
+        );
+        Ok(())
+    }
+}
+
+pub struct EmailNotifier {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+}
+
+impl EmailNotifier {
+    pub fn new(cfg: &Config) -> Result<EmailNotifier, String> {
+        let host = cfg
+            .smtp_host
+            .as_deref()
+            .filter(|host| !host.is_empty())
+            .ok_or("empty smtp host")?;
+        let from = cfg
+            .smtp_from
+            .as_deref()
+            .ok_or("empty smtp from")?
+            .parse::<Mailbox>()
+            .map_err(|err| err.to_string())?;
+        let port = cfg
+            .smtp_port
+            .map(|port| u16::try_from(port).map_err(|err| err.to_string()))
+            .transpose()?
+            .unwrap_or(DEFAULT_SMTP_PORT);
+
+        // `tls` wraps the whole connection (usually port 465), `none` is for a local relay
+        let mut builder = match cfg.smtp_tls.as_deref().unwrap_or("starttls") {
+            "starttls" => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(host)
+                .map_err(|err| err.to_string())?,
+            "tls" => {
+                AsyncSmtpTransport::<Tokio1Executor>::relay(host).map_err(|err| err.to_string())?
+            }
+            "none" => AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(host),
+            tls => return Err(format!("unknown smtp tls mode {}", tls)),
+        }
+        .port(port);
+        if let Some(username) = cfg.smtp_username.as_ref().filter(|u| !u.is_empty()) {
+            builder = builder.credentials(Credentials::new(
+                username.to_string(),
+                cfg.smtp_password.clone().unwrap_or_default(),
+            ));
+        }
+
+        Ok(EmailNotifier {
+            transport: builder.build(),
+            from,
+        })
+    }
+}
+
+#[async_trait]
+impl Notifier for EmailNotifier {
+    async fn notify(&self, reminder: &Reminder) -> Result<(), String> {
+        let address = reminder
+            .email
+            .as_deref()
+            .filter(|email| !email.is_empty())
+            .ok_or(format!("user {} has no email", reminder.user_id))?;
+        let to = Mailbox::new(
+            reminder.fullname.clone(),
+            address.parse().map_err(|err| format!("{:?}", err))?,
+        );
+        let message = Message::builder()
+            .from(self.from.clone())
+            .to(to)
+            .subject(format!("Reminder: {}", reminder.title))
+            .header(ContentType::TEXT_PLAIN)
+            .body(reminder.text())
+            .map_err(|err| err.to_string())?;
+        self.transport
+            .send(message)
+            .await
+            .map(|_| ())
+            .map_err(|err| err.to_string())
+    }
+}
+
+pub struct WebhookNotifier {
+    client: reqwest::Client,
+    url: String,
+    secret: Option<String>,
+}
+
+impl WebhookNotifier {
+    pub fn new(cfg: &Config) -> Result<WebhookNotifier, String> {
+        let url = cfg
+            .reminder_webhook_url
+            .clone()
+            .filter(|url| !url.is_empty())
+            .ok_or("empty reminder webhook url")?;
+        let client = reqwest::Client::builder()
+            .timeout(WEBHOOK_TIMEOUT)
+            .build()
+            .map_err(|err| err.to_string())?;
+        Ok(WebhookNotifier {
+            client,
+            url,
+            secret: cfg
+                .reminder_webhook_secret
+                .clone()
+                .filter(|s| !s.is_empty()),
+        })
+    }
+}
+
+#[async_trait]
+impl Notifier for WebhookNotifier {
+    async fn notify(&self, reminder: &Reminder) -> Result<(), String> {
+        let mut request = self.client.post(&self.url).json(reminder);
+        if let Some(secret) = &self.secret {
+            request = request.bearer_auth(secret);
+        }
+        let response = request.send().await.map_err(|err| err.to_string())?;
+        if !response.status().is_success() {
+            return Err(format!("webhook returned status {}", response.status()));
+        }
+        Ok(())
+    }
+}
+
+pub fn from_config(cfg: &Config) -> Result<Arc<dyn Notifier>, String> {
+    match cfg.reminder_channel.as_deref().unwrap_or("log") {
+        "log" => Ok(Arc::new(LogNotifier)),
+        "email" => Ok(Arc::new(EmailNotifier::new(cfg)?)),
+        "webhook" => Ok(Arc::new(WebhookNotifier::new(cfg)?)),
+        channel => Err(format!("unknown reminder channel {}", channel)),
+    }
+}
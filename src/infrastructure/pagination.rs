@@ -1,44 +1,88 @@
-use serde::Deserialize;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
 
 const DEFAULT_SIZE: i32 = 10;
+const MAX_SIZE: i32 = 100;
 
-#[derive(Debug, Deserialize, Clone)]
+// paging and sorting for every list endpoint. `limit` and `sort_by` are accepted as the
+// older names of `size` and `order_by`, the newer name wins when both are sent
+#[derive(Debug, Deserialize, Clone, Default)]
 pub struct PaginationQuery {
-    sort_order: Option<String>,
-    order_by: Option<String>,
-    size: Option<i32>,
-    page: Option<i32>,
+    pub sort_order: Option<String>,
+    pub order_by: Option<String>,
+    pub sort_by: Option<String>,
+    pub size: Option<i32>,
+    pub limit: Option<i32>,
+    pub page: Option<i32>,
+    pub pagination: Option<String>,
+    pub after: Option<String>,
+    pub before: Option<String>,
 }
 
-pub fn get_total_pages(total_count: i32, page_size: i32) -> i32 {
-    let d = f64::from(total_count) / f64::from(page_size);
-    f64::ceil(d) as i32
+// keyset position of a row: the values of the sort keys it was listed by plus its id
+// as the tie breaker. clients only ever see it as an opaque string
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct Cursor {
+    pub sort: String,
+    pub values: Vec<String>,
+    pub id: Uuid,
+}
+
+pub fn encode_cursor(cursor: &Cursor) -> String {
+    let json = serde_json::to_vec(cursor).unwrap_or_default();
+    URL_SAFE_NO_PAD.encode(json)
+}
+
+pub fn decode_cursor(cursor: &str) -> Option<Cursor> {
+    let json = URL_SAFE_NO_PAD.decode(cursor.trim()).ok()?;
+    serde_json::from_slice(&json).ok()
+}
+
+pub fn get_total_pages(total_count: i64, page_size: i32) -> i64 {
+    if page_size <= 0 {
+        return 0;
+    }
+    let d = total_count as f64 / f64::from(page_size);
+    f64::ceil(d) as i64
 }
 
 pub fn get_size(req: PaginationQuery) -> i32 {
-    return if let Some(req_size) = req.size.clone() {
-        req_size
+    return if let Some(req_size) = req.size.or(req.limit) {
+        req_size.clamp(1, MAX_SIZE)
     } else {
         DEFAULT_SIZE
     };
 }
 
+// pages are 1-based
 pub fn get_page(req: PaginationQuery) -> i32 {
     return if let Some(req_page) = req.page.clone() {
-        req_page
+        req_page.max(1)
     } else {
-        0
+        1
     };
 }
-#[allow(dead_code)]
+
+pub fn get_offset(req: PaginationQuery) -> i64 {
+    i64::from(get_page(req.clone()) - 1) * i64::from(get_size(req))
+}
+
 pub fn get_order_by(req: PaginationQuery) -> String {
-    return if let Some(req_order_by) = req.order_by.clone() {
+    let order_by = req.order_by.filter(|o| !o.is_empty());
+    return if let Some(req_order_by) = order_by.or(req.sort_by).filter(|o| !o.is_empty()) {
         req_order_by
     } else {
         "created_at".to_string()
     };
 }
-#[allow(dead_code)]
+
+// cursor mode is asked for explicitly or implied by a cursor, offset stays the default
+pub fn wants_cursor(req: &PaginationQuery) -> bool {
+    req.pagination.as_deref() == Some("cursor") || req.after.is_some() || req.before.is_some()
+}
+
 pub fn get_sort_order(req: PaginationQuery) -> String {
     if let Some(req_sort_order) = req.sort_order.clone() {
         if req_sort_order.to_lowercase() == "asc" {
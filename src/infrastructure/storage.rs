@@ -0,0 +1,193 @@
+use crate::configuration::config::Config;
+use async_trait::async_trait;
+use s3::creds::Credentials;
+use s3::{Bucket, Region};
+use std::io::SeekFrom;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+
+const DEFAULT_LOCAL_PATH: &str = "./storage";
+
+// byte range with an inclusive end, the same convention as the http `Range` header
+pub type ByteRange = (u64, u64);
+
+#[async_trait]
+pub trait Storage: Send + Sync {
+    async fn put(&self, key: &str, content_type: &str, bytes: &[u8]) -> Result<(), String>;
+    async fn get(&self, key: &str, range: Option<ByteRange>) -> Result<Vec<u8>, String>;
+    async fn delete(&self, key: &str) -> Result<(), String>;
+}
+
+pub struct LocalStorage {
+    root: PathBuf,
+}
+
+impl LocalStorage {
+    pub fn new(root: &str) -> LocalStorage {
+        LocalStorage {
+            root: PathBuf::from(root),
+        }
+    }
+
+    fn path_for(&self, key: &str) -> Result<PathBuf, String> {
+        // keys are generated by the server, but never let one escape the storage root
+        let relative = Path::new(key);
+        if relative
+            .components()
+            .any(|component| !matches!(component, Component::Normal(_)))
+        {
+            return Err(format!("invalid storage key {}", key));
+        }
+        Ok(self.root.join(relative))
+    }
+}
+
+#[async_trait]
+impl Storage for LocalStorage {
+    async fn put(&self, key: &str, _content_type: &str, bytes: &[u8]) -> Result<(), String> {
+        let path = self.path_for(key)?;
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .map_err(|err| err.to_string())?;
+        }
+        tokio::fs::write(&path, bytes)
+            .await
+            .map_err(|err| err.to_string())
+    }
+
+    async fn get(&self, key: &str, range: Option<ByteRange>) -> Result<Vec<u8>, String> {
+        let path = self.path_for(key)?;
+        let (start, end) = match range {
+            Some(range) => range,
+            None => return tokio::fs::read(&path).await.map_err(|err| err.to_string()),
+        };
+
+        let mut file = tokio::fs::File::open(&path)
+            .await
+            .map_err(|err| err.to_string())?;
+        file.seek(SeekFrom::Start(start))
+            .await
+            .map_err(|err| err.to_string())?;
+        let mut bytes = vec![0; (end - start + 1) as usize];
+        file.read_exact(&mut bytes)
+            .await
+            .map_err(|err| err.to_string())?;
+        Ok(bytes)
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), String> {
+        let path = self.path_for(key)?;
+        match tokio::fs::remove_file(&path).await {
+            Ok(_) => Ok(()),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(err) => Err(err.to_string()),
+        }
+    }
+}
+
+pub struct S3Storage {
+    bucket: Box<Bucket>,
+}
+
+impl S3Storage {
+    pub fn new(cfg: &Config) -> Result<S3Storage, String> {
+        let bucket_name = cfg
+            .s3_bucket
+            .as_ref()
+            .filter(|bucket| !bucket.is_empty())
+            .ok_or("empty s3 bucket")?;
+        let region_name = cfg.s3_region.clone().unwrap_or("us-east-1".to_string());
+
+        // a custom endpoint lets any S3 compatible server (minio, localstack, ...) stand in
+        let region = match cfg.s3_endpoint.as_ref().filter(|e| !e.is_empty()) {
+            Some(endpoint) => Region::Custom {
+                region: region_name,
+                endpoint: endpoint.to_string(),
+            },
+            None => region_name.parse().map_err(|err| format!("{:?}", err))?,
+        };
+        let credentials = Credentials::new(
+            cfg.s3_access_key.as_deref(),
+            cfg.s3_secret_key.as_deref(),
+            None,
+            None,
+            None,
+        )
+        .map_err(|err| err.to_string())?;
+
+        let bucket = Bucket::new(bucket_name, region, credentials)
+            .map_err(|err| err.to_string())?
+            .with_path_style();
+        Ok(S3Storage { bucket })
+    }
+}
+
+fn check_s3_status(status_code: u16, key: &str) -> Result<(), String> {
+    if !(200..300).contains(&status_code) {
+        return Err(format!("s3 returned status {} for {}", status_code, key));
+    }
+    Ok(())
+}
+
+#[async_trait]
+impl Storage for S3Storage {
+    async fn put(&self, key: &str, content_type: &str, bytes: &[u8]) -> Result<(), String> {
+        let response = self
+            .bucket
+            .put_object_with_content_type(key, bytes, content_type)
+            .await
+            .map_err(|err| err.to_string())?;
+        check_s3_status(response.status_code(), key)
+    }
+
+    async fn get(&self, key: &str, range: Option<ByteRange>) -> Result<Vec<u8>, String> {
+        let response = match range {
+            // the client refuses single byte ranges, those are cut out of the full object
+            Some((start, end)) if start < end => self
+                .bucket
+                .get_object_range(key, start, Some(end))
+                .await
+                .map_err(|err| err.to_string())?,
+            _ => self
+                .bucket
+                .get_object(key)
+                .await
+                .map_err(|err| err.to_string())?,
+        };
+        check_s3_status(response.status_code(), key)?;
+
+        let bytes = response.to_vec();
+        match range {
+            Some((start, end)) if start == end => bytes
+                .get(start as usize..=end as usize)
+                .map(|slice| slice.to_vec())
+                .ok_or(format!("range out of bounds for {}", key)),
+            _ => Ok(bytes),
+        }
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), String> {
+        let response = self
+            .bucket
+            .delete_object(key)
+            .await
+            .map_err(|err| err.to_string())?;
+        check_s3_status(response.status_code(), key)
+    }
+}
+
+pub fn from_config(cfg: &Config) -> Result<Arc<dyn Storage>, String> {
+    match cfg.storage_backend.as_deref().unwrap_or("local") {
+        "local" => {
+            let root = cfg
+                .storage_local_path
+                .as_deref()
+                .unwrap_or(DEFAULT_LOCAL_PATH);
+            Ok(Arc::new(LocalStorage::new(root)))
+        }
+        "s3" => Ok(Arc::new(S3Storage::new(cfg)?)),
+        backend => Err(format!("unknown storage backend {}", backend)),
+    }
+}
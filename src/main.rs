@@ -12,7 +12,14 @@ use actix_web::http::StatusCode;
 use actix_web::middleware::Logger;
 use actix_web::{web, App, HttpResponse, HttpServer};
 use configuration::config::Config;
+use infrastructure::collab::CollabHub;
+use infrastructure::crypto;
+use infrastructure::events::EventHub;
 use infrastructure::http_lib::Response;
+use infrastructure::markdown::RenderCache;
+use infrastructure::notifier;
+use infrastructure::storage::{self, Storage};
+use modules::notes::model::NoteEventModel;
 use sqlx::{Pool, Postgres};
 use std::env;
 use std::sync::{Arc, Mutex};
@@ -20,6 +27,10 @@ use std::sync::{Arc, Mutex};
 pub struct AppState {
     db: Pool<Postgres>,
     cfg: Config,
+    render_cache: Arc<RenderCache>,
+    storage: Arc<dyn Storage>,
+    collab: Arc<CollabHub>,
+    note_events: Arc<EventHub<NoteEventModel>>,
 }
 
 async fn not_found() -> HttpResponse {
@@ -60,6 +71,65 @@ async fn main() -> std::io::Result<()> {
             std::process::exit(1);
         });
 
+    crypto::init(&config).unwrap_or_else(|err| {
+        eprintln!("Failed to initialize encryption keys: {:?}", err);
+        std::process::exit(1);
+    });
+
+    // `reencrypt` seals every note with the active encryption key and exits instead of serving
+    if env::args().nth(1).as_deref() == Some("reencrypt") {
+        match modules::notes::service::reencrypt_notes_service(&pool).await {
+            Ok((count, skipped)) => {
+                println!("🔐 Re-encrypted {} notes", count);
+                for note_id in skipped {
+                    eprintln!("⚠️⚠️⚠️ Can't open note {}, it is left as it is", note_id);
+                }
+                return Ok(());
+            }
+            Err(err) => {
+                eprintln!("Failed to re-encrypt notes: {:?}", err);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    let storage = storage::from_config(&config).unwrap_or_else(|err| {
+        eprintln!("Failed to initialize attachment storage: {:?}", err);
+        std::process::exit(1);
+    });
+
+    // Shared by every worker so a note revision is rendered only once
+    let render_cache = Arc::new(RenderCache::new());
+
+    // Notes being edited together live here, saved back to the database in the background
+    let collab = Arc::new(CollabHub::new());
+    actix_web::rt::spawn(modules::notes::service::collab_autosave_service(
+        pool.clone(),
+        collab.clone(),
+    ));
+
+    // Note changes announced by any instance are pushed to the clients connected to this one
+    let note_events = Arc::new(EventHub::new());
+    actix_web::rt::spawn(modules::notes::service::note_events_listener_service(
+        pool.clone(),
+        note_events.clone(),
+    ));
+
+    // Scheduled notes are published and unpublished by every instance, each note only once
+    actix_web::rt::spawn(modules::notes::service::publish_scheduler_service(
+        pool.clone(),
+    ));
+
+    // Due reminders are sent from every instance, the database keeps them from going out twice
+    let notifier = notifier::from_config(&config).unwrap_or_else(|err| {
+        eprintln!("Failed to initialize reminder channel: {:?}", err);
+        std::process::exit(1);
+    });
+    actix_web::rt::spawn(modules::notes::service::reminder_scheduler_service(
+        pool.clone(),
+        notifier,
+    ));
+
     println!("🚀🚀🚀 Server starting!");
 
     HttpServer::new(move || {
@@ -68,6 +138,10 @@ async fn main() -> std::io::Result<()> {
             .app_data(web::Data::new(AppState {
                 db: pool.clone(),
                 cfg: config.clone(),
+                render_cache: render_cache.clone(),
+                storage: storage.clone(),
+                collab: collab.clone(),
+                note_events: note_events.clone(),
             }))
             .configure(route::initiate_routes)
             .default_service(web::route().to(not_found))
@@ -0,0 +1,13 @@
+pub const ATTACHMENT_NOT_FOUND: &str = "attachment not found";
+pub const PARAMETER_ATTACHMENT_ID_INVALID: &str = "parameter attachment_id is invalid";
+pub const ATTACHMENT_FOUND: &str = "success get data attachment";
+pub const ATTACHMENT_SUCCESS_SAVED: &str = "success saved data attachment";
+pub const ATTACHMENT_SUCCESS_DELETED: &str = "success deleted data attachment";
+pub const ATTACHMENT_CANT_BE_FETCHED: &str = "something went wrong while get attachment";
+pub const ATTACHMENT_CANT_BE_SAVED: &str = "something went wrong while saving the attachment";
+pub const ATTACHMENT_CANT_BE_DELETE: &str = "something went wrong while delete the attachment";
+pub const ATTACHMENT_CANT_BE_READ: &str = "something went wrong while reading the attachment";
+pub const ATTACHMENT_FILE_REQUIRED: &str = "multipart field file is required";
+pub const ATTACHMENT_TOO_LARGE: &str = "attachment exceeds the maximum allowed size";
+pub const ATTACHMENT_TYPE_NOT_ALLOWED: &str = "attachment content type is not allowed";
+pub const ATTACHMENT_RANGE_NOT_SATISFIABLE: &str = "requested range not satisfiable";
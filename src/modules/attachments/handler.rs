@@ -0,0 +1,284 @@
+use crate::infrastructure::http_lib::Response;
+use crate::modules::attachments::constants;
+use crate::modules::attachments::model::{AttachmentModel, UploadedFile};
+use crate::modules::attachments::service;
+use crate::modules::notes::constants as note_constants;
+use crate::utils::utils;
+use crate::AppState;
+use actix_multipart::Multipart;
+use actix_web::http::header::{self, ContentDisposition, DispositionParam, DispositionType};
+use actix_web::http::StatusCode;
+use actix_web::{delete, get, post, web, HttpRequest, HttpResponse, Responder};
+use futures::StreamExt;
+
+fn error_response(err: &str) -> HttpResponse {
+    let status = if err.contains(note_constants::NOTE_NOT_FOUND)
+        || err.contains(constants::ATTACHMENT_NOT_FOUND)
+    {
+        StatusCode::NOT_FOUND
+    } else if err.contains(constants::ATTACHMENT_TOO_LARGE) {
+        StatusCode::PAYLOAD_TOO_LARGE
+    } else if err.contains(constants::ATTACHMENT_TYPE_NOT_ALLOWED) {
+        StatusCode::UNSUPPORTED_MEDIA_TYPE
+    } else if err.contains(constants::ATTACHMENT_FILE_REQUIRED) {
+        StatusCode::BAD_REQUEST
+    } else {
+        StatusCode::INTERNAL_SERVER_ERROR
+    };
+    let resp: Response<(), ()> = Response::error(status, err);
+    HttpResponse::build(status).json(resp)
+}
+
+// reads the `file` field, stopping as soon as it grows past the configured limit
+async fn read_upload(mut payload: Multipart, max_size: usize) -> Result<UploadedFile, String> {
+    while let Some(item) = payload.next().await {
+        let mut field = item.map_err(|err| err.to_string())?;
+        if field.content_disposition().get_name() != Some("file") {
+            continue;
+        }
+
+        let file_name = field
+            .content_disposition()
+            .get_filename()
+            .unwrap_or_default()
+            .to_string();
+        let mut bytes: Vec<u8> = Vec::new();
+        while let Some(chunk) = field.next().await {
+            let chunk = chunk.map_err(|err| err.to_string())?;
+            if bytes.len() + chunk.len() > max_size {
+                return Err(constants::ATTACHMENT_TOO_LARGE.to_string());
+            }
+            bytes.extend_from_slice(&chunk);
+        }
+        return Ok(UploadedFile { file_name, bytes });
+    }
+
+    Err(constants::ATTACHMENT_FILE_REQUIRED.to_string())
+}
+
+#[post("")]
+pub async fn upload_attachment_handler(
+    path: web::Path<String>,
+    payload: Multipart,
+    data: web::Data<AppState>,
+    req: HttpRequest,
+) -> impl Responder {
+    let note_id_str = path.into_inner();
+
+    //get user_id from authorization token
+    let user_id = utils::get_current_user_uuid_from_jwt(&data.cfg.clone(), req);
+    if let Err(err) = user_id {
+        let resp: Response<(), ()> = Response::error(StatusCode::UNAUTHORIZED, err.as_str());
+        return HttpResponse::Unauthorized().json(resp);
+    }
+
+    // Attempt to parse the UUID from the path
+    let note_id = match uuid::Uuid::parse_str(&note_id_str) {
+        Ok(uuid) => uuid,
+        Err(err) => {
+            eprintln!("match uuid::Uuid::parse_str, got err : {:?}", err);
+            let msg = note_constants::PARAMETER_NOTE_ID_INVALID;
+            let error_response: Response<(), ()> = Response::error(StatusCode::BAD_REQUEST, msg);
+            return HttpResponse::BadRequest().json(error_response);
+        }
+    };
+
+    let upload = match read_upload(payload, service::max_size(&data.cfg)).await {
+        Ok(upload) => upload,
+        Err(err) => {
+            eprintln!("error read multipart upload: {:?}", err);
+            return error_response(&err);
+        }
+    };
+
+    let attachment = match service::upload_attachment_service(
+        &data.db,
+        data.storage.as_ref(),
+        &data.cfg,
+        note_id,
+        upload,
+        user_id.unwrap(),
+    )
+    .await
+    {
+        Ok(attachment) => attachment,
+        Err(err) => return error_response(&err),
+    };
+
+    let msg = constants::ATTACHMENT_SUCCESS_SAVED;
+    let resp: Response<AttachmentModel, ()> = Response::success(StatusCode::OK, attachment, msg);
+    return HttpResponse::Ok().json(resp);
+}
+
+#[get("")]
+pub async fn attachment_list_handler(
+    path: web::Path<String>,
+    data: web::Data<AppState>,
+    req: HttpRequest,
+) -> impl Responder {
+    let note_id_str = path.into_inner();
+
+    //get user_id from authorization token
+    let user_id = utils::get_current_user_uuid_from_jwt(&data.cfg.clone(), req);
+    if let Err(err) = user_id {
+        let resp: Response<(), ()> = Response::error(StatusCode::UNAUTHORIZED, err.as_str());
+        return HttpResponse::Unauthorized().json(resp);
+    }
+
+    // Attempt to parse the UUID from the path
+    let note_id = match uuid::Uuid::parse_str(&note_id_str) {
+        Ok(uuid) => uuid,
+        Err(err) => {
+            eprintln!("match uuid::Uuid::parse_str, got err : {:?}", err);
+            let msg = note_constants::PARAMETER_NOTE_ID_INVALID;
+            let error_response: Response<(), ()> = Response::error(StatusCode::BAD_REQUEST, msg);
+            return HttpResponse::BadRequest().json(error_response);
+        }
+    };
+
+    let attachments =
+        match service::get_attachments_service(&data.db, note_id, user_id.unwrap()).await {
+            Ok(attachments) => attachments,
+            Err(err) => return error_response(&err),
+        };
+
+    let msg = constants::ATTACHMENT_FOUND;
+    let resp: Response<Vec<AttachmentModel>, ()> =
+        Response::success(StatusCode::OK, attachments, msg);
+    return HttpResponse::Ok().json(resp);
+}
+
+#[get("/{attachment_id}")]
+pub async fn download_attachment_handler(
+    path: web::Path<(String, String)>,
+    data: web::Data<AppState>,
+    req: HttpRequest,
+) -> impl Responder {
+    let (note_id_str, attachment_id_str) = path.into_inner();
+    let range_header = req
+        .headers()
+        .get(header::RANGE)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.to_string());
+
+    //get user_id from authorization token
+    let user_id = utils::get_current_user_uuid_from_jwt(&data.cfg.clone(), req);
+    if let Err(err) = user_id {
+        let resp: Response<(), ()> = Response::error(StatusCode::UNAUTHORIZED, err.as_str());
+        return HttpResponse::Unauthorized().json(resp);
+    }
+
+    // Attempt to parse the UUID from the path
+    let note_id = match uuid::Uuid::parse_str(&note_id_str) {
+        Ok(uuid) => uuid,
+        Err(err) => {
+            eprintln!("match uuid::Uuid::parse_str, got err : {:?}", err);
+            let msg = note_constants::PARAMETER_NOTE_ID_INVALID;
+            let error_response: Response<(), ()> = Response::error(StatusCode::BAD_REQUEST, msg);
+            return HttpResponse::BadRequest().json(error_response);
+        }
+    };
+    let attachment_id = match uuid::Uuid::parse_str(&attachment_id_str) {
+        Ok(uuid) => uuid,
+        Err(err) => {
+            eprintln!("match uuid::Uuid::parse_str, got err : {:?}", err);
+            let msg = constants::PARAMETER_ATTACHMENT_ID_INVALID;
+            let error_response: Response<(), ()> = Response::error(StatusCode::BAD_REQUEST, msg);
+            return HttpResponse::BadRequest().json(error_response);
+        }
+    };
+
+    let attachment =
+        match service::get_attachment_service(&data.db, note_id, attachment_id, user_id.unwrap())
+            .await
+        {
+            Ok(attachment) => attachment,
+            Err(err) => return error_response(&err),
+        };
+
+    let size = attachment.size_bytes as u64;
+    let range = match range_header.map(|value| service::parse_range(&value, size)) {
+        Some(Ok(range)) => range,
+        Some(Err(err)) => {
+            let resp: Response<(), ()> =
+                Response::error(StatusCode::RANGE_NOT_SATISFIABLE, err.as_str());
+            return HttpResponse::RangeNotSatisfiable()
+                .insert_header((header::CONTENT_RANGE, format!("bytes */{}", size)))
+                .json(resp);
+        }
+        None => None,
+    };
+
+    let bytes =
+        match service::read_attachment_service(data.storage.as_ref(), &attachment, range).await {
+            Ok(bytes) => bytes,
+            Err(err) => return error_response(&err),
+        };
+
+    let mut response = match range {
+        Some((start, end)) => {
+            let mut response = HttpResponse::PartialContent();
+            response.insert_header((
+                header::CONTENT_RANGE,
+                format!("bytes {}-{}/{}", start, end, size),
+            ));
+            response
+        }
+        None => HttpResponse::Ok(),
+    };
+    response
+        .content_type(attachment.content_type.as_str())
+        .insert_header((header::ACCEPT_RANGES, "bytes"))
+        .insert_header((header::ETAG, format!("\"{}\"", attachment.checksum)))
+        .insert_header(ContentDisposition {
+            disposition: DispositionType::Attachment,
+            parameters: vec![DispositionParam::Filename(attachment.file_name.clone())],
+        })
+        .body(bytes)
+}
+
+#[delete("/{attachment_id}")]
+pub async fn delete_attachment_handler(
+    path: web::Path<(String, String)>,
+    data: web::Data<AppState>,
+    req: HttpRequest,
+) -> impl Responder {
+    let (note_id_str, attachment_id_str) = path.into_inner();
+
+    //get user_id from authorization token
+    let user_id = utils::get_current_user_uuid_from_jwt(&data.cfg.clone(), req);
+    if let Err(err) = user_id {
+        let resp: Response<(), ()> = Response::error(StatusCode::UNAUTHORIZED, err.as_str());
+        return HttpResponse::Unauthorized().json(resp);
+    }
+
+    // Attempt to parse the UUID from the path
+    let note_id = match uuid::Uuid::parse_str(&note_id_str) {
+        Ok(uuid) => uuid,
+        Err(err) => {
+            eprintln!("match uuid::Uuid::parse_str, got err : {:?}", err);
+            let msg = note_constants::PARAMETER_NOTE_ID_INVALID;
+            let error_response: Response<(), ()> = Response::error(StatusCode::BAD_REQUEST, msg);
+            return HttpResponse::BadRequest().json(error_response);
+        }
+    };
+    let attachment_id = match uuid::Uuid::parse_str(&attachment_id_str) {
+        Ok(uuid) => uuid,
+        Err(err) => {
+            eprintln!("match uuid::Uuid::parse_str, got err : {:?}", err);
+            let msg = constants::PARAMETER_ATTACHMENT_ID_INVALID;
+            let error_response: Response<(), ()> = Response::error(StatusCode::BAD_REQUEST, msg);
+            return HttpResponse::BadRequest().json(error_response);
+        }
+    };
+
+    if let Err(err) =
+        service::delete_attachment_service(&data.db, note_id, attachment_id, user_id.unwrap()).await
+    {
+        return error_response(&err);
+    }
+
+    let msg = constants::ATTACHMENT_SUCCESS_DELETED;
+    let resp: Response<(), ()> = Response::success(StatusCode::OK, (), msg);
+    return HttpResponse::Ok().json(resp);
+}
@@ -0,0 +1,6 @@
+mod constants;
+mod handler;
+mod model;
+mod repository;
+pub mod routes;
+mod service;
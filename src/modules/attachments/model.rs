@@ -0,0 +1,42 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+#[derive(Debug, FromRow, Deserialize, Serialize, Clone)]
+#[allow(non_snake_case)]
+pub struct AttachmentModel {
+    pub id: Uuid,
+    pub note_id: Uuid,
+    pub owner_id: Uuid,
+    pub file_name: String,
+    pub content_type: String,
+    pub size_bytes: i64,
+    pub checksum: String,
+    #[serde(skip_serializing)]
+    pub storage_key: String,
+    #[serde(rename = "createdAt")]
+    pub created_at: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(rename = "updatedAt")]
+    pub updated_at: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(rename = "deletedAt")]
+    pub deleted_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[allow(non_snake_case)]
+pub struct AttachmentSaveModel {
+    pub id: Uuid,
+    pub note_id: Uuid,
+    pub owner_id: Uuid,
+    pub file_name: String,
+    pub content_type: String,
+    pub size_bytes: i64,
+    pub checksum: String,
+    pub storage_key: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct UploadedFile {
+    pub file_name: String,
+    pub bytes: Vec<u8>,
+}
@@ -0,0 +1,66 @@
+use crate::modules::attachments::model::{AttachmentModel, AttachmentSaveModel};
+use chrono::{DateTime, Utc};
+use sqlx::postgres::PgQueryResult;
+use sqlx::{Error, PgPool};
+use uuid::Uuid;
+
+pub async fn save_attachment(
+    pool: &PgPool,
+    body: AttachmentSaveModel,
+) -> Result<AttachmentModel, Error> {
+    let query = "INSERT INTO attachments (id,note_id,owner_id,file_name,content_type,size_bytes,checksum,storage_key) VALUES ($1, $2, $3, $4, $5, $6, $7, $8) RETURNING *";
+    sqlx::query_as::<_, AttachmentModel>(query)
+        .bind(body.id)
+        .bind(body.note_id)
+        .bind(body.owner_id)
+        .bind(body.file_name)
+        .bind(body.content_type)
+        .bind(body.size_bytes)
+        .bind(body.checksum)
+        .bind(body.storage_key)
+        .fetch_one(pool)
+        .await
+}
+
+pub async fn get_attachments_by_note_id(
+    pool: &PgPool,
+    note_id: Uuid,
+) -> Result<Vec<AttachmentModel>, Error> {
+    let query = "SELECT * FROM attachments a where a.deleted_at is null and a.note_id = $1 ORDER BY a.created_at";
+    sqlx::query_as::<_, AttachmentModel>(query)
+        .bind(note_id)
+        .fetch_all(pool)
+        .await
+}
+
+pub async fn get_attachment_by_id(
+    pool: &PgPool,
+    note_id: Uuid,
+    attachment_id: Uuid,
+) -> Result<AttachmentModel, Error> {
+    let query =
+        "SELECT * FROM attachments a where a.deleted_at is null and a.id = $1 and a.note_id = $2";
+    sqlx::query_as::<_, AttachmentModel>(query)
+        .bind(attachment_id)
+        .bind(note_id)
+        .fetch_one(pool)
+        .await
+}
+
+pub async fn delete_attachment_by_id(
+    pool: &PgPool,
+    note_id: Uuid,
+    attachment_id: Uuid,
+) -> Result<i32, Error> {
+    let now: DateTime<Utc> = Utc::now();
+    let rows_affected: PgQueryResult = sqlx::query::<_>(
+        "UPDATE attachments SET deleted_at = $1 WHERE id = $2 AND note_id = $3 AND deleted_at IS NULL",
+    )
+    .bind(now)
+    .bind(attachment_id)
+    .bind(note_id)
+    .execute(pool)
+    .await?;
+
+    Ok(rows_affected.rows_affected() as i32)
+}
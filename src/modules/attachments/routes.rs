@@ -0,0 +1,12 @@
+use crate::modules::attachments::handler;
+use actix_web::web;
+
+pub fn routes(conf: &mut web::ServiceConfig) {
+    let scope = web::scope("/notes/{note_id}/attachments")
+        .service(handler::upload_attachment_handler)
+        .service(handler::attachment_list_handler)
+        .service(handler::download_attachment_handler)
+        .service(handler::delete_attachment_handler);
+
+    conf.service(scope);
+}
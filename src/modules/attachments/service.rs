@@ -0,0 +1,223 @@
+use crate::configuration::config::Config;
+use crate::infrastructure::storage::{ByteRange, Storage};
+use crate::modules::attachments::constants;
+use crate::modules::attachments::model::{AttachmentModel, AttachmentSaveModel, UploadedFile};
+use crate::modules::attachments::repository;
+use crate::modules::notes::service as note_service;
+use sha2::{Digest, Sha256};
+use sqlx::{Error, PgPool};
+use uuid::Uuid;
+
+const DEFAULT_MAX_SIZE: i64 = 10 * 1024 * 1024;
+const DEFAULT_ALLOWED_TYPES: &str = "image/png,image/jpeg,image/gif,image/webp,application/pdf";
+
+pub fn max_size(cfg: &Config) -> usize {
+    cfg.attachment_max_size.unwrap_or(DEFAULT_MAX_SIZE).max(0) as usize
+}
+
+fn allowed_types(cfg: &Config) -> Vec<String> {
+    cfg.attachment_allowed_types
+        .as_deref()
+        .unwrap_or(DEFAULT_ALLOWED_TYPES)
+        .split(',')
+        .map(|content_type| content_type.trim().to_lowercase())
+        .filter(|content_type| !content_type.is_empty())
+        .collect()
+}
+
+// the content type sent by the client is not trusted, it is sniffed from the magic bytes
+fn detect_content_type(bytes: &[u8]) -> Option<&'static str> {
+    if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+        Some("image/png")
+    } else if bytes.starts_with(b"\xff\xd8\xff") {
+        Some("image/jpeg")
+    } else if bytes.starts_with(b"GIF87a") || bytes.starts_with(b"GIF89a") {
+        Some("image/gif")
+    } else if bytes.len() >= 12 && &bytes[..4] == b"RIFF" && &bytes[8..12] == b"WEBP" {
+        Some("image/webp")
+    } else if bytes.starts_with(b"%PDF-") {
+        Some("application/pdf")
+    } else {
+        None
+    }
+}
+
+fn sanitize_file_name(file_name: &str) -> String {
+    let base_name = file_name
+        .rsplit(['/', '\\'])
+        .next()
+        .unwrap_or_default()
+        .trim();
+    let sanitized: String = base_name
+        .chars()
+        .filter(|c| !c.is_control() && *c != '"')
+        .take(255)
+        .collect();
+    if sanitized.is_empty() {
+        return "attachment".to_string();
+    }
+    sanitized
+}
+
+async fn check_note_owner(pool: &PgPool, note_id: Uuid, user_id: Uuid) -> Result<(), String> {
+    note_service::get_note_detail_service(pool, note_id, user_id)
+        .await
+        .map(|_| ())
+}
+
+pub async fn upload_attachment_service(
+    pool: &PgPool,
+    storage: &dyn Storage,
+    cfg: &Config,
+    note_id: Uuid,
+    upload: UploadedFile,
+    user_id: Uuid,
+) -> Result<AttachmentModel, String> {
+    check_note_owner(pool, note_id, user_id).await?;
+
+    if upload.bytes.len() > max_size(cfg) {
+        return Err(constants::ATTACHMENT_TOO_LARGE.to_string());
+    }
+    let content_type = match detect_content_type(&upload.bytes) {
+        Some(content_type) if allowed_types(cfg).iter().any(|t| t == content_type) => content_type,
+        _ => return Err(constants::ATTACHMENT_TYPE_NOT_ALLOWED.to_string()),
+    };
+
+    let attachment_id = Uuid::new_v4();
+    let storage_key = format!("{}/{}", note_id, attachment_id);
+    if let Err(err) = storage.put(&storage_key, content_type, &upload.bytes).await {
+        eprintln!("Error storing attachment: {:?}", err);
+        return Err(constants::ATTACHMENT_CANT_BE_SAVED.to_string());
+    }
+
+    let attachment_save_model = AttachmentSaveModel {
+        id: attachment_id,
+        note_id,
+        owner_id: user_id,
+        file_name: sanitize_file_name(&upload.file_name),
+        content_type: content_type.to_string(),
+        size_bytes: upload.bytes.len() as i64,
+        checksum: format!("{:x}", Sha256::digest(&upload.bytes)),
+        storage_key: storage_key.clone(),
+    };
+
+    match repository::save_attachment(pool, attachment_save_model).await {
+        Ok(attachment) => Ok(attachment),
+        Err(err) => {
+            eprintln!("Error saving attachment: {:?}", err);
+            // do not leave an orphan blob behind when the row could not be written
+            if let Err(err) = storage.delete(&storage_key).await {
+                eprintln!("Error removing orphan attachment blob: {:?}", err);
+            }
+            Err(constants::ATTACHMENT_CANT_BE_SAVED.to_string())
+        }
+    }
+}
+
+pub async fn get_attachments_service(
+    pool: &PgPool,
+    note_id: Uuid,
+    user_id: Uuid,
+) -> Result<Vec<AttachmentModel>, String> {
+    check_note_owner(pool, note_id, user_id).await?;
+
+    match repository::get_attachments_by_note_id(pool, note_id).await {
+        Ok(attachments) => Ok(attachments),
+        Err(err) => {
+            eprintln!("error get attachments {:?}", err);
+            Err(constants::ATTACHMENT_CANT_BE_FETCHED.to_string())
+        }
+    }
+}
+
+pub async fn get_attachment_service(
+    pool: &PgPool,
+    note_id: Uuid,
+    attachment_id: Uuid,
+    user_id: Uuid,
+) -> Result<AttachmentModel, String> {
+    check_note_owner(pool, note_id, user_id).await?;
+
+    match repository::get_attachment_by_id(pool, note_id, attachment_id).await {
+        Ok(attachment) => Ok(attachment),
+        Err(err) => match err {
+            Error::RowNotFound => Err(constants::ATTACHMENT_NOT_FOUND.to_string()),
+            _ => {
+                eprintln!("error get attachment {:?}", err);
+                Err(constants::ATTACHMENT_CANT_BE_FETCHED.to_string())
+            }
+        },
+    }
+}
+
+pub async fn read_attachment_service(
+    storage: &dyn Storage,
+    attachment: &AttachmentModel,
+    range: Option<ByteRange>,
+) -> Result<Vec<u8>, String> {
+    match storage.get(&attachment.storage_key, range).await {
+        Ok(bytes) => Ok(bytes),
+        Err(err) => {
+            eprintln!("error read attachment blob {:?}", err);
+            Err(constants::ATTACHMENT_CANT_BE_READ.to_string())
+        }
+    }
+}
+
+pub async fn delete_attachment_service(
+    pool: &PgPool,
+    note_id: Uuid,
+    attachment_id: Uuid,
+    user_id: Uuid,
+) -> Result<i32, String> {
+    check_note_owner(pool, note_id, user_id).await?;
+
+    match repository::delete_attachment_by_id(pool, note_id, attachment_id).await {
+        Ok(0) => Err(constants::ATTACHMENT_NOT_FOUND.to_string()),
+        Ok(rows) => Ok(rows),
+        Err(err) => {
+            eprintln!("Error delete attachment: {:?}", err);
+            Err(constants::ATTACHMENT_CANT_BE_DELETE.to_string())
+        }
+    }
+}
+
+// only a single range is served, anything the server does not understand falls back
+// to the full body as RFC 9110 allows
+pub fn parse_range(header: &str, size: u64) -> Result<Option<ByteRange>, String> {
+    let spec = match header.trim().strip_prefix("bytes=") {
+        Some(spec) if !spec.contains(',') => spec.trim(),
+        _ => return Ok(None),
+    };
+    let (start, end) = match spec.split_once('-') {
+        Some(bounds) => bounds,
+        None => return Ok(None),
+    };
+
+    let range = if start.is_empty() {
+        match end.parse::<u64>() {
+            Ok(suffix) if suffix > 0 && size > 0 => (size - suffix.min(size), size - 1),
+            Ok(_) => return Err(constants::ATTACHMENT_RANGE_NOT_SATISFIABLE.to_string()),
+            Err(_) => return Ok(None),
+        }
+    } else {
+        let start = match start.parse::<u64>() {
+            Ok(start) => start,
+            Err(_) => return Ok(None),
+        };
+        let end = if end.is_empty() {
+            size.saturating_sub(1)
+        } else {
+            match end.parse::<u64>() {
+                Ok(end) if end >= start => end.min(size.saturating_sub(1)),
+                _ => return Ok(None),
+            }
+        };
+        if start >= size {
+            return Err(constants::ATTACHMENT_RANGE_NOT_SATISFIABLE.to_string());
+        }
+        (start, end)
+    };
+
+    Ok(Some(range))
+}
@@ -0,0 +1,11 @@
+pub const CATEGORY_NOT_FOUND: &str = "category not found";
+pub const CATEGORY_NAME_ALREADY_EXIST: &str = "category name already exist";
+pub const PARAMETER_CATEGORY_ID_INVALID: &str = "parameter id is invalid";
+pub const CATEGORY_FOUND: &str = "success get data category";
+pub const CATEGORY_SUCCESS_SAVED: &str = "success saved data category";
+pub const CATEGORY_SUCCESS_PATCHED: &str = "success patched data category";
+pub const CATEGORY_SUCCESS_DELETED: &str = "success deleted data category";
+pub const CATEGORY_CANT_BE_FETCHED: &str = "something went wrong while get category";
+pub const CATEGORY_CANT_BE_SAVED: &str = "something went wrong while saving the category";
+pub const CATEGORY_CANT_BE_PATCHED: &str = "something went wrong while updating the category";
+pub const CATEGORY_CANT_BE_DELETE: &str = "something went wrong while delete the category";
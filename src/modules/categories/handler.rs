@@ -0,0 +1,200 @@
+use crate::infrastructure::http_lib::Response;
+use crate::modules::categories::constants;
+use crate::modules::categories::model::CategoryModel;
+use crate::modules::categories::schema::CategorySchema;
+use crate::modules::categories::service;
+use crate::utils::utils;
+use crate::AppState;
+use actix_web::http::StatusCode;
+use actix_web::{delete, get, post, put, web, HttpRequest, HttpResponse, Responder};
+use validator::Validate;
+
+fn error_response(err: &str) -> HttpResponse {
+    let status = if err.contains(constants::CATEGORY_NOT_FOUND) {
+        StatusCode::NOT_FOUND
+    } else if err.contains(constants::CATEGORY_NAME_ALREADY_EXIST) {
+        StatusCode::CONFLICT
+    } else {
+        StatusCode::INTERNAL_SERVER_ERROR
+    };
+    let resp: Response<(), ()> = Response::error(status, err);
+    HttpResponse::build(status).json(resp)
+}
+
+#[get("")]
+pub async fn category_list_handler(data: web::Data<AppState>, req: HttpRequest) -> impl Responder {
+    //get user_id from authorization token
+    let user_id = utils::get_current_user_uuid_from_jwt(&data.cfg.clone(), req);
+    if let Err(err) = user_id {
+        let resp: Response<(), ()> = Response::error(StatusCode::UNAUTHORIZED, err.as_str());
+        return HttpResponse::Unauthorized().json(resp);
+    }
+
+    let categories = match service::get_categories_service(&data.db, user_id.unwrap()).await {
+        Ok(categories) => categories,
+        Err(err) => return error_response(&err),
+    };
+
+    let msg = constants::CATEGORY_FOUND;
+    let resp: Response<Vec<CategoryModel>, ()> = Response::success(StatusCode::OK, categories, msg);
+    return HttpResponse::Ok().json(resp);
+}
+
+#[post("")]
+pub async fn create_category_handler(
+    body: web::Json<CategorySchema>,
+    data: web::Data<AppState>,
+    req: HttpRequest,
+) -> impl Responder {
+    //get user_id from authorization token
+    let user_id = utils::get_current_user_uuid_from_jwt(&data.cfg.clone(), req);
+    if let Err(err) = user_id {
+        let resp: Response<(), ()> = Response::error(StatusCode::UNAUTHORIZED, err.as_str());
+        return HttpResponse::Unauthorized().json(resp);
+    }
+
+    //validate the struct from body
+    if let Err(errors) = body.validate() {
+        let resp = Response::custom(
+            StatusCode::BAD_REQUEST,
+            StatusCode::BAD_REQUEST.as_str(),
+            (),
+            errors,
+        );
+        return HttpResponse::BadRequest().json(resp);
+    }
+
+    let category = match service::save_category_service(&data.db, &body.0, user_id.unwrap()).await {
+        Ok(category) => category,
+        Err(err) => return error_response(&err),
+    };
+
+    let msg = constants::CATEGORY_SUCCESS_SAVED;
+    let resp: Response<CategoryModel, ()> = Response::success(StatusCode::OK, category, msg);
+    return HttpResponse::Ok().json(resp);
+}
+
+#[get("/{id}")]
+pub async fn get_category_handler(
+    path: web::Path<String>,
+    data: web::Data<AppState>,
+    req: HttpRequest,
+) -> impl Responder {
+    let category_id_str = path.into_inner();
+
+    //get user_id from authorization token
+    let user_id = utils::get_current_user_uuid_from_jwt(&data.cfg.clone(), req);
+    if let Err(err) = user_id {
+        let resp: Response<(), ()> = Response::error(StatusCode::UNAUTHORIZED, err.as_str());
+        return HttpResponse::Unauthorized().json(resp);
+    }
+
+    // Attempt to parse the UUID from the path
+    let category_id = match uuid::Uuid::parse_str(&category_id_str) {
+        Ok(uuid) => uuid,
+        Err(err) => {
+            eprintln!("match uuid::Uuid::parse_str, got err : {:?}", err);
+            let msg = constants::PARAMETER_CATEGORY_ID_INVALID;
+            let error_response: Response<(), ()> = Response::error(StatusCode::BAD_REQUEST, msg);
+            return HttpResponse::BadRequest().json(error_response);
+        }
+    };
+
+    let category =
+        match service::get_category_service(&data.db, category_id, user_id.unwrap()).await {
+            Ok(category) => category,
+            Err(err) => return error_response(&err),
+        };
+
+    let msg = constants::CATEGORY_FOUND;
+    let resp: Response<CategoryModel, ()> = Response::success(StatusCode::OK, category, msg);
+    return HttpResponse::Ok().json(resp);
+}
+
+#[put("/{id}")]
+pub async fn edit_category_handler(
+    path: web::Path<String>,
+    body: web::Json<CategorySchema>,
+    data: web::Data<AppState>,
+    req: HttpRequest,
+) -> impl Responder {
+    let category_id_str = path.into_inner();
+
+    //get user_id from authorization token
+    let user_id = utils::get_current_user_uuid_from_jwt(&data.cfg.clone(), req);
+    if let Err(err) = user_id {
+        let resp: Response<(), ()> = Response::error(StatusCode::UNAUTHORIZED, err.as_str());
+        return HttpResponse::Unauthorized().json(resp);
+    }
+
+    // Attempt to parse the UUID from the path
+    let category_id = match uuid::Uuid::parse_str(&category_id_str) {
+        Ok(uuid) => uuid,
+        Err(err) => {
+            eprintln!("match uuid::Uuid::parse_str, got err : {:?}", err);
+            let msg = constants::PARAMETER_CATEGORY_ID_INVALID;
+            let error_response: Response<(), ()> = Response::error(StatusCode::BAD_REQUEST, msg);
+            return HttpResponse::BadRequest().json(error_response);
+        }
+    };
+
+    //validate the struct from body
+    if let Err(errors) = body.validate() {
+        let resp = Response::custom(
+            StatusCode::BAD_REQUEST,
+            StatusCode::BAD_REQUEST.as_str(),
+            (),
+            errors,
+        );
+        return HttpResponse::BadRequest().json(resp);
+    }
+
+    let category =
+        match service::update_category_service(&data.db, category_id, &body.0, user_id.unwrap())
+            .await
+        {
+            Ok(category) => category,
+            Err(err) => return error_response(&err),
+        };
+
+    let msg = constants::CATEGORY_SUCCESS_PATCHED;
+    let resp: Response<CategoryModel, ()> = Response::success(StatusCode::OK, category, msg);
+    return HttpResponse::Ok().json(resp);
+}
+
+#[delete("/{id}")]
+pub async fn delete_category_handler(
+    path: web::Path<String>,
+    data: web::Data<AppState>,
+    req: HttpRequest,
+) -> impl Responder {
+    let category_id_str = path.into_inner();
+
+    //get user_id from authorization token
+    let user_id = utils::get_current_user_uuid_from_jwt(&data.cfg.clone(), req);
+    if let Err(err) = user_id {
+        let resp: Response<(), ()> = Response::error(StatusCode::UNAUTHORIZED, err.as_str());
+        return HttpResponse::Unauthorized().json(resp);
+    }
+
+    // Attempt to parse the UUID from the path
+    let category_id = match uuid::Uuid::parse_str(&category_id_str) {
+        Ok(uuid) => uuid,
+        Err(err) => {
+            eprintln!("match uuid::Uuid::parse_str, got err : {:?}", err);
+            let msg = constants::PARAMETER_CATEGORY_ID_INVALID;
+            let error_response: Response<(), ()> = Response::error(StatusCode::BAD_REQUEST, msg);
+            return HttpResponse::BadRequest().json(error_response);
+        }
+    };
+
+    if let Err(err) =
+        service::delete_category_service(&data.db, category_id, user_id.unwrap()).await
+    {
+        return error_response(&err);
+    }
+
+    let msg = constants::CATEGORY_SUCCESS_DELETED;
+    let resp: Response<(), ()> = Response::success(StatusCode::OK, (), msg);
+    return HttpResponse::Ok().json(resp);
+}
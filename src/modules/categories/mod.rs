@@ -0,0 +1,7 @@
+pub(crate) mod constants;
+mod handler;
+mod model;
+mod repository;
+pub mod routes;
+pub(crate) mod schema;
+pub(crate) mod service;
@@ -0,0 +1,30 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+#[derive(Debug, FromRow, Deserialize, Serialize, Clone)]
+#[allow(non_snake_case)]
+pub struct CategoryModel {
+    pub id: Uuid,
+    pub name: String,
+    pub color: String,
+    #[serde(rename = "sortOrder")]
+    pub sort_order: i32,
+    pub created_by: Uuid,
+    pub updated_by: Option<Uuid>,
+    #[serde(rename = "createdAt")]
+    pub created_at: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(rename = "updatedAt")]
+    pub updated_at: Option<chrono::DateTime<chrono::Utc>>,
+    #[sqlx(default)]
+    #[serde(rename = "noteCount", skip_serializing_if = "Option::is_none")]
+    pub note_count: Option<i64>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[allow(non_snake_case)]
+pub struct CategorySaveModel {
+    pub name: String,
+    pub color: Option<String>,
+    pub sort_order: Option<i32>,
+}
@@ -0,0 +1,129 @@
+use crate::modules::categories::model::{CategoryModel, CategorySaveModel};
+use chrono::Utc;
+use sqlx::postgres::PgQueryResult;
+use sqlx::{Error, PgPool};
+use uuid::Uuid;
+
+// the same default the categories table has for the ones created from a note
+const DEFAULT_COLOR: &str = "#9e9e9e";
+const NOTE_COUNT_COLUMN: &str = "(SELECT count(n.id) FROM notes n WHERE n.category_id = categories.id AND n.deleted_at IS NULL) AS note_count";
+
+pub async fn save_category(
+    pool: &PgPool,
+    body: CategorySaveModel,
+    user_id: Uuid,
+) -> Result<CategoryModel, Error> {
+    let query = "INSERT INTO categories (name,color,sort_order,created_by) VALUES ($1, $2, COALESCE($3, (SELECT COALESCE(max(sort_order), -1) + 1 FROM categories WHERE created_by = $4)), $4) RETURNING *";
+    sqlx::query_as::<_, CategoryModel>(query)
+        .bind(body.name)
+        .bind(body.color.unwrap_or_else(|| DEFAULT_COLOR.to_string()))
+        .bind(body.sort_order)
+        .bind(user_id)
+        .fetch_one(pool)
+        .await
+}
+
+pub async fn get_categories_user(
+    pool: &PgPool,
+    user_id: Uuid,
+) -> Result<Vec<CategoryModel>, Error> {
+    let query = format!(
+        "SELECT categories.*, {} FROM categories WHERE created_by = $1 ORDER BY sort_order, lower(name)",
+        NOTE_COUNT_COLUMN
+    );
+    sqlx::query_as::<_, CategoryModel>(&query)
+        .bind(user_id)
+        .fetch_all(pool)
+        .await
+}
+
+pub async fn get_category_user_by_id(
+    pool: &PgPool,
+    category_id: Uuid,
+    user_id: Uuid,
+) -> Result<CategoryModel, Error> {
+    let query = format!(
+        "SELECT categories.*, {} FROM categories WHERE id = $1 AND created_by = $2",
+        NOTE_COUNT_COLUMN
+    );
+    sqlx::query_as::<_, CategoryModel>(&query)
+        .bind(category_id)
+        .bind(user_id)
+        .fetch_one(pool)
+        .await
+}
+
+pub async fn get_category_user_by_name(
+    pool: &PgPool,
+    name: &str,
+    user_id: Uuid,
+) -> Result<Option<CategoryModel>, Error> {
+    let query = "SELECT * FROM categories WHERE lower(name) = lower($1) AND created_by = $2";
+    sqlx::query_as::<_, CategoryModel>(query)
+        .bind(name)
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await
+}
+
+// a rename is written to every note of the category, trashed ones included, so the
+// denormalized notes.category never shows the old name
+pub async fn update_category(
+    pool: &PgPool,
+    category_id: Uuid,
+    body: CategorySaveModel,
+    user_id: Uuid,
+) -> Result<CategoryModel, Error> {
+    let now = Utc::now();
+    let mut tx = pool.begin().await?;
+    let category = sqlx::query_as::<_, CategoryModel>(
+        "UPDATE categories SET name = $1, color = COALESCE($2, color), sort_order = COALESCE($3, sort_order), updated_by = $4, updated_at = $5 WHERE id = $6 AND created_by = $4 RETURNING *",
+    )
+    .bind(body.name)
+    .bind(body.color)
+    .bind(body.sort_order)
+    .bind(user_id)
+    .bind(now)
+    .bind(category_id)
+    .fetch_one(&mut tx)
+    .await?;
+    sqlx::query::<_>(
+        "UPDATE notes SET version = version + 1, category = $1, updated_by = $2, updated_at = $3 WHERE category_id = $4 AND category IS DISTINCT FROM $1",
+    )
+    .bind(&category.name)
+    .bind(user_id)
+    .bind(now)
+    .bind(category_id)
+    .execute(&mut tx)
+    .await?;
+    tx.commit().await?;
+
+    Ok(category)
+}
+
+// the notes are kept and lose their category
+pub async fn delete_category_by_id(
+    pool: &PgPool,
+    category_id: Uuid,
+    user_id: Uuid,
+) -> Result<i32, Error> {
+    let now = Utc::now();
+    let mut tx = pool.begin().await?;
+    sqlx::query::<_>(
+        "UPDATE notes SET version = version + 1, category = NULL, updated_by = $1, updated_at = $2 WHERE category_id = $3",
+    )
+    .bind(user_id)
+    .bind(now)
+    .bind(category_id)
+    .execute(&mut tx)
+    .await?;
+    let rows_affected: PgQueryResult =
+        sqlx::query::<_>("DELETE FROM categories WHERE id = $1 AND created_by = $2")
+            .bind(category_id)
+            .bind(user_id)
+            .execute(&mut tx)
+            .await?;
+    tx.commit().await?;
+
+    Ok(rows_affected.rows_affected() as i32)
+}
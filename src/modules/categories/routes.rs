@@ -0,0 +1,13 @@
+use crate::modules::categories::handler;
+use actix_web::web;
+
+pub fn routes(conf: &mut web::ServiceConfig) {
+    let scope = web::scope("/categories")
+        .service(handler::category_list_handler)
+        .service(handler::create_category_handler)
+        .service(handler::get_category_handler)
+        .service(handler::edit_category_handler)
+        .service(handler::delete_category_handler);
+
+    conf.service(scope);
+}
@@ -0,0 +1,37 @@
+use serde::{Deserialize, Serialize};
+use validator::ValidationError;
+use validator_derive::Validate;
+
+// used for both creating and replacing a category, a category without a sort_order is put
+// after the others
+#[derive(Deserialize, Serialize, Debug, Validate)]
+pub struct CategorySchema {
+    #[validate(custom = "validate_name")]
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[validate(custom = "validate_color")]
+    pub color: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sort_order: Option<i32>,
+}
+
+fn validate_name(name: &str) -> Result<(), ValidationError> {
+    if name.trim().is_empty() || name.trim().chars().count() > 100 {
+        return Err(ValidationError::new(
+            "name must have a length between 1 and 100 characters",
+        ));
+    }
+    Ok(())
+}
+
+fn validate_color(color: &str) -> Result<(), ValidationError> {
+    let valid = color.len() == 7
+        && color.starts_with('#')
+        && color[1..].chars().all(|c| c.is_ascii_hexdigit());
+    if !valid {
+        return Err(ValidationError::new(
+            "color must be a hex color like #1e88e5",
+        ));
+    }
+    Ok(())
+}
@@ -0,0 +1,170 @@
+use crate::modules::categories::constants;
+use crate::modules::categories::model::{CategoryModel, CategorySaveModel};
+use crate::modules::categories::repository;
+use crate::modules::categories::schema::CategorySchema;
+use sqlx::{Error, PgPool};
+use uuid::Uuid;
+
+fn to_category_save_model(body: &CategorySchema) -> CategorySaveModel {
+    CategorySaveModel {
+        name: body.name.trim().to_string(),
+        color: body.color.as_ref().map(|color| color.to_lowercase()),
+        sort_order: body.sort_order,
+    }
+}
+
+// names only differing in case count as the same category
+async fn ensure_category_name_free(
+    pool: &PgPool,
+    name: &str,
+    category_id: Option<Uuid>,
+    user_id: Uuid,
+) -> Result<(), String> {
+    match repository::get_category_user_by_name(pool, name, user_id).await {
+        Ok(Some(category)) if Some(category.id) != category_id => {
+            Err(constants::CATEGORY_NAME_ALREADY_EXIST.to_string())
+        }
+        Ok(_) => Ok(()),
+        Err(err) => {
+            eprintln!("error get category by name {:?}", err);
+            Err(constants::CATEGORY_CANT_BE_FETCHED.to_string())
+        }
+    }
+}
+
+pub async fn get_categories_service(
+    pool: &PgPool,
+    user_id: Uuid,
+) -> Result<Vec<CategoryModel>, String> {
+    match repository::get_categories_user(pool, user_id).await {
+        Ok(categories) => Ok(categories),
+        Err(err) => {
+            eprintln!("error get categories {:?}", err);
+            Err(constants::CATEGORY_CANT_BE_FETCHED.to_string())
+        }
+    }
+}
+
+pub async fn get_category_service(
+    pool: &PgPool,
+    category_id: Uuid,
+    user_id: Uuid,
+) -> Result<CategoryModel, String> {
+    match repository::get_category_user_by_id(pool, category_id, user_id).await {
+        Ok(category) => Ok(category),
+        Err(err) => match err {
+            Error::RowNotFound => Err(constants::CATEGORY_NOT_FOUND.to_string()),
+            _ => {
+                eprintln!("error get category {:?}", err);
+                Err(constants::CATEGORY_CANT_BE_FETCHED.to_string())
+            }
+        },
+    }
+}
+
+// names are matched without regard to case
+pub async fn get_category_by_name_service(
+    pool: &PgPool,
+    name: &str,
+    user_id: Uuid,
+) -> Result<CategoryModel, String> {
+    match repository::get_category_user_by_name(pool, name.trim(), user_id).await {
+        Ok(Some(category)) => Ok(category),
+        Ok(None) => Err(constants::CATEGORY_NOT_FOUND.to_string()),
+        Err(err) => {
+            eprintln!("error get category by name {:?}", err);
+            Err(constants::CATEGORY_CANT_BE_FETCHED.to_string())
+        }
+    }
+}
+
+// an imported archive brings its categories along, the ones the user does not have yet are
+// created. everywhere else a note can only be put in an existing category
+pub async fn get_or_save_category_service(
+    pool: &PgPool,
+    name: &str,
+    user_id: Uuid,
+) -> Result<CategoryModel, String> {
+    match get_category_by_name_service(pool, name, user_id).await {
+        Err(err) if err == constants::CATEGORY_NOT_FOUND => {}
+        result => return result,
+    }
+    let body = CategorySchema {
+        name: name.to_string(),
+        color: None,
+        sort_order: None,
+    };
+    match save_category_service(pool, &body, user_id).await {
+        // created by another import at the same time
+        Err(err) if err == constants::CATEGORY_NAME_ALREADY_EXIST => {
+            get_category_by_name_service(pool, name, user_id).await
+        }
+        result => result,
+    }
+}
+
+pub async fn save_category_service(
+    pool: &PgPool,
+    body: &CategorySchema,
+    user_id: Uuid,
+) -> Result<CategoryModel, String> {
+    let category_save_model = to_category_save_model(body);
+    ensure_category_name_free(pool, &category_save_model.name, None, user_id).await?;
+
+    match repository::save_category(pool, category_save_model, user_id).await {
+        Ok(category) => Ok(CategoryModel {
+            note_count: Some(0),
+            ..category
+        }),
+        // the name was taken between the check and the insert
+        Err(Error::Database(err)) if err.code().as_deref() == Some("23505") => {
+            Err(constants::CATEGORY_NAME_ALREADY_EXIST.to_string())
+        }
+        Err(err) => {
+            eprintln!("Error saving category: {:?}", err);
+            Err(constants::CATEGORY_CANT_BE_SAVED.to_string())
+        }
+    }
+}
+
+pub async fn update_category_service(
+    pool: &PgPool,
+    category_id: Uuid,
+    body: &CategorySchema,
+    user_id: Uuid,
+) -> Result<CategoryModel, String> {
+    get_category_service(pool, category_id, user_id).await?;
+    let category_save_model = to_category_save_model(body);
+    ensure_category_name_free(pool, &category_save_model.name, Some(category_id), user_id).await?;
+
+    if let Err(err) =
+        repository::update_category(pool, category_id, category_save_model, user_id).await
+    {
+        return match err {
+            Error::RowNotFound => Err(constants::CATEGORY_NOT_FOUND.to_string()),
+            Error::Database(err) if err.code().as_deref() == Some("23505") => {
+                Err(constants::CATEGORY_NAME_ALREADY_EXIST.to_string())
+            }
+            _ => {
+                eprintln!("Error update category: {:?}", err);
+                Err(constants::CATEGORY_CANT_BE_PATCHED.to_string())
+            }
+        };
+    }
+    get_category_service(pool, category_id, user_id).await
+}
+
+pub async fn delete_category_service(
+    pool: &PgPool,
+    category_id: Uuid,
+    user_id: Uuid,
+) -> Result<i32, String> {
+    match repository::delete_category_by_id(pool, category_id, user_id).await {
+        Ok(0) => Err(constants::CATEGORY_NOT_FOUND.to_string()),
+        Ok(rows) => Ok(rows),
+        Err(err) => {
+            eprintln!("Error delete category: {:?}", err);
+            Err(constants::CATEGORY_CANT_BE_DELETE.to_string())
+        }
+    }
+}
@@ -0,0 +1,17 @@
+pub const CHECKLIST_ITEM_NOT_FOUND: &str = "checklist item not found";
+pub const CHECKLIST_ORDER_INVALID: &str =
+    "ids has to list every item of the checklist exactly once";
+pub const PARAMETER_CHECKLIST_ITEM_ID_INVALID: &str = "parameter item_id is invalid";
+pub const CHECKLIST_FOUND: &str = "success get data checklist";
+pub const CHECKLIST_ITEM_SUCCESS_SAVED: &str = "success saved data checklist item";
+pub const CHECKLIST_ITEM_SUCCESS_TOGGLED: &str = "success toggled data checklist item";
+pub const CHECKLIST_ITEM_SUCCESS_DELETED: &str = "success deleted data checklist item";
+pub const CHECKLIST_SUCCESS_REORDERED: &str = "success reordered data checklist";
+pub const CHECKLIST_CANT_BE_FETCHED: &str = "something went wrong while get checklist";
+pub const CHECKLIST_ITEM_CANT_BE_SAVED: &str =
+    "something went wrong while saving the checklist item";
+pub const CHECKLIST_ITEM_CANT_BE_TOGGLED: &str =
+    "something went wrong while toggling the checklist item";
+pub const CHECKLIST_ITEM_CANT_BE_DELETE: &str =
+    "something went wrong while delete the checklist item";
+pub const CHECKLIST_CANT_BE_REORDERED: &str = "something went wrong while reordering the checklist";
@@ -0,0 +1,264 @@
+use crate::infrastructure::http_lib::Response;
+use crate::modules::checklists::constants;
+use crate::modules::checklists::model::ChecklistItemModel;
+use crate::modules::checklists::schema::{
+    ChecklistResponse, CreateChecklistItemSchema, ReorderChecklistSchema,
+};
+use crate::modules::checklists::service;
+use crate::modules::notes::constants as note_constants;
+use crate::utils::utils;
+use crate::AppState;
+use actix_web::http::StatusCode;
+use actix_web::{delete, get, post, put, web, HttpRequest, HttpResponse, Responder};
+use validator::Validate;
+
+fn error_response(err: &str) -> HttpResponse {
+    let status = if err.contains(note_constants::NOTE_NOT_FOUND)
+        || err.contains(constants::CHECKLIST_ITEM_NOT_FOUND)
+    {
+        StatusCode::NOT_FOUND
+    } else if err.contains(constants::CHECKLIST_ORDER_INVALID) {
+        StatusCode::BAD_REQUEST
+    } else {
+        StatusCode::INTERNAL_SERVER_ERROR
+    };
+    let resp: Response<(), ()> = Response::error(status, err);
+    HttpResponse::build(status).json(resp)
+}
+
+#[get("")]
+pub async fn checklist_handler(
+    path: web::Path<String>,
+    data: web::Data<AppState>,
+    req: HttpRequest,
+) -> impl Responder {
+    let note_id_str = path.into_inner();
+
+    //get user_id from authorization token
+    let user_id = utils::get_current_user_uuid_from_jwt(&data.cfg.clone(), req);
+    if let Err(err) = user_id {
+        let resp: Response<(), ()> = Response::error(StatusCode::UNAUTHORIZED, err.as_str());
+        return HttpResponse::Unauthorized().json(resp);
+    }
+
+    // Attempt to parse the UUID from the path
+    let note_id = match uuid::Uuid::parse_str(&note_id_str) {
+        Ok(uuid) => uuid,
+        Err(err) => {
+            eprintln!("match uuid::Uuid::parse_str, got err : {:?}", err);
+            let msg = note_constants::PARAMETER_NOTE_ID_INVALID;
+            let error_response: Response<(), ()> = Response::error(StatusCode::BAD_REQUEST, msg);
+            return HttpResponse::BadRequest().json(error_response);
+        }
+    };
+
+    let checklist = match service::get_checklist_service(&data.db, note_id, user_id.unwrap()).await
+    {
+        Ok(checklist) => checklist,
+        Err(err) => return error_response(&err),
+    };
+
+    let msg = constants::CHECKLIST_FOUND;
+    let resp: Response<ChecklistResponse, ()> = Response::success(StatusCode::OK, checklist, msg);
+    return HttpResponse::Ok().json(resp);
+}
+
+#[post("")]
+pub async fn create_checklist_item_handler(
+    path: web::Path<String>,
+    body: web::Json<CreateChecklistItemSchema>,
+    data: web::Data<AppState>,
+    req: HttpRequest,
+) -> impl Responder {
+    let note_id_str = path.into_inner();
+
+    //get user_id from authorization token
+    let user_id = utils::get_current_user_uuid_from_jwt(&data.cfg.clone(), req);
+    if let Err(err) = user_id {
+        let resp: Response<(), ()> = Response::error(StatusCode::UNAUTHORIZED, err.as_str());
+        return HttpResponse::Unauthorized().json(resp);
+    }
+
+    // Attempt to parse the UUID from the path
+    let note_id = match uuid::Uuid::parse_str(&note_id_str) {
+        Ok(uuid) => uuid,
+        Err(err) => {
+            eprintln!("match uuid::Uuid::parse_str, got err : {:?}", err);
+            let msg = note_constants::PARAMETER_NOTE_ID_INVALID;
+            let error_response: Response<(), ()> = Response::error(StatusCode::BAD_REQUEST, msg);
+            return HttpResponse::BadRequest().json(error_response);
+        }
+    };
+
+    //validate the struct from body
+    if let Err(errors) = body.validate() {
+        let resp = Response::custom(
+            StatusCode::BAD_REQUEST,
+            StatusCode::BAD_REQUEST.as_str(),
+            (),
+            errors,
+        );
+        return HttpResponse::BadRequest().json(resp);
+    }
+
+    let item =
+        match service::save_checklist_item_service(&data.db, note_id, &body.0, user_id.unwrap())
+            .await
+        {
+            Ok(item) => item,
+            Err(err) => return error_response(&err),
+        };
+
+    let msg = constants::CHECKLIST_ITEM_SUCCESS_SAVED;
+    let resp: Response<ChecklistItemModel, ()> = Response::success(StatusCode::OK, item, msg);
+    return HttpResponse::Ok().json(resp);
+}
+
+#[put("/order")]
+pub async fn reorder_checklist_handler(
+    path: web::Path<String>,
+    body: web::Json<ReorderChecklistSchema>,
+    data: web::Data<AppState>,
+    req: HttpRequest,
+) -> impl Responder {
+    let note_id_str = path.into_inner();
+
+    //get user_id from authorization token
+    let user_id = utils::get_current_user_uuid_from_jwt(&data.cfg.clone(), req);
+    if let Err(err) = user_id {
+        let resp: Response<(), ()> = Response::error(StatusCode::UNAUTHORIZED, err.as_str());
+        return HttpResponse::Unauthorized().json(resp);
+    }
+
+    // Attempt to parse the UUID from the path
+    let note_id = match uuid::Uuid::parse_str(&note_id_str) {
+        Ok(uuid) => uuid,
+        Err(err) => {
+            eprintln!("match uuid::Uuid::parse_str, got err : {:?}", err);
+            let msg = note_constants::PARAMETER_NOTE_ID_INVALID;
+            let error_response: Response<(), ()> = Response::error(StatusCode::BAD_REQUEST, msg);
+            return HttpResponse::BadRequest().json(error_response);
+        }
+    };
+
+    //validate the struct from body
+    if let Err(errors) = body.validate() {
+        let resp = Response::custom(
+            StatusCode::BAD_REQUEST,
+            StatusCode::BAD_REQUEST.as_str(),
+            (),
+            errors,
+        );
+        return HttpResponse::BadRequest().json(resp);
+    }
+
+    let checklist = match service::reorder_checklist_service(
+        &data.db,
+        note_id,
+        &body.0,
+        user_id.unwrap(),
+    )
+    .await
+    {
+        Ok(checklist) => checklist,
+        Err(err) => return error_response(&err),
+    };
+
+    let msg = constants::CHECKLIST_SUCCESS_REORDERED;
+    let resp: Response<ChecklistResponse, ()> = Response::success(StatusCode::OK, checklist, msg);
+    return HttpResponse::Ok().json(resp);
+}
+
+#[post("/{item_id}/toggle")]
+pub async fn toggle_checklist_item_handler(
+    path: web::Path<(String, String)>,
+    data: web::Data<AppState>,
+    req: HttpRequest,
+) -> impl Responder {
+    let (note_id_str, item_id_str) = path.into_inner();
+
+    //get user_id from authorization token
+    let user_id = utils::get_current_user_uuid_from_jwt(&data.cfg.clone(), req);
+    if let Err(err) = user_id {
+        let resp: Response<(), ()> = Response::error(StatusCode::UNAUTHORIZED, err.as_str());
+        return HttpResponse::Unauthorized().json(resp);
+    }
+
+    // Attempt to parse the UUID from the path
+    let note_id = match uuid::Uuid::parse_str(&note_id_str) {
+        Ok(uuid) => uuid,
+        Err(err) => {
+            eprintln!("match uuid::Uuid::parse_str, got err : {:?}", err);
+            let msg = note_constants::PARAMETER_NOTE_ID_INVALID;
+            let error_response: Response<(), ()> = Response::error(StatusCode::BAD_REQUEST, msg);
+            return HttpResponse::BadRequest().json(error_response);
+        }
+    };
+    let item_id = match uuid::Uuid::parse_str(&item_id_str) {
+        Ok(uuid) => uuid,
+        Err(err) => {
+            eprintln!("match uuid::Uuid::parse_str, got err : {:?}", err);
+            let msg = constants::PARAMETER_CHECKLIST_ITEM_ID_INVALID;
+            let error_response: Response<(), ()> = Response::error(StatusCode::BAD_REQUEST, msg);
+            return HttpResponse::BadRequest().json(error_response);
+        }
+    };
+
+    let item =
+        match service::toggle_checklist_item_service(&data.db, note_id, item_id, user_id.unwrap())
+            .await
+        {
+            Ok(item) => item,
+            Err(err) => return error_response(&err),
+        };
+
+    let msg = constants::CHECKLIST_ITEM_SUCCESS_TOGGLED;
+    let resp: Response<ChecklistItemModel, ()> = Response::success(StatusCode::OK, item, msg);
+    return HttpResponse::Ok().json(resp);
+}
+
+#[delete("/{item_id}")]
+pub async fn delete_checklist_item_handler(
+    path: web::Path<(String, String)>,
+    data: web::Data<AppState>,
+    req: HttpRequest,
+) -> impl Responder {
+    let (note_id_str, item_id_str) = path.into_inner();
+
+    //get user_id from authorization token
+    let user_id = utils::get_current_user_uuid_from_jwt(&data.cfg.clone(), req);
+    if let Err(err) = user_id {
+        let resp: Response<(), ()> = Response::error(StatusCode::UNAUTHORIZED, err.as_str());
+        return HttpResponse::Unauthorized().json(resp);
+    }
+
+    // Attempt to parse the UUID from the path
+    let note_id = match uuid::Uuid::parse_str(&note_id_str) {
+        Ok(uuid) => uuid,
+        Err(err) => {
+            eprintln!("match uuid::Uuid::parse_str, got err : {:?}", err);
+            let msg = note_constants::PARAMETER_NOTE_ID_INVALID;
+            let error_response: Response<(), ()> = Response::error(StatusCode::BAD_REQUEST, msg);
+            return HttpResponse::BadRequest().json(error_response);
+        }
+    };
+    let item_id = match uuid::Uuid::parse_str(&item_id_str) {
+        Ok(uuid) => uuid,
+        Err(err) => {
+            eprintln!("match uuid::Uuid::parse_str, got err : {:?}", err);
+            let msg = constants::PARAMETER_CHECKLIST_ITEM_ID_INVALID;
+            let error_response: Response<(), ()> = Response::error(StatusCode::BAD_REQUEST, msg);
+            return HttpResponse::BadRequest().json(error_response);
+        }
+    };
+
+    if let Err(err) =
+        service::delete_checklist_item_service(&data.db, note_id, item_id, user_id.unwrap()).await
+    {
+        return error_response(&err);
+    }
+
+    let msg = constants::CHECKLIST_ITEM_SUCCESS_DELETED;
+    let resp: Response<(), ()> = Response::success(StatusCode::OK, (), msg);
+    return HttpResponse::Ok().json(resp);
+}
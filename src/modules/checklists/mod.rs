@@ -0,0 +1,7 @@
+mod constants;
+mod handler;
+mod model;
+mod repository;
+pub mod routes;
+mod schema;
+mod service;
@@ -0,0 +1,27 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+#[derive(Debug, FromRow, Deserialize, Serialize, Clone)]
+#[allow(non_snake_case)]
+pub struct ChecklistItemModel {
+    pub id: Uuid,
+    pub note_id: Uuid,
+    pub position: i32,
+    pub text: String,
+    pub checked: bool,
+    pub created_by: Uuid,
+    pub updated_by: Option<Uuid>,
+    #[serde(rename = "createdAt")]
+    pub created_at: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(rename = "updatedAt")]
+    pub updated_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[allow(non_snake_case)]
+pub struct ChecklistItemSaveModel {
+    pub note_id: Uuid,
+    pub text: String,
+    pub created_by: Uuid,
+}
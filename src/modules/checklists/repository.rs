@@ -0,0 +1,100 @@
+use crate::modules::checklists::model::{ChecklistItemModel, ChecklistItemSaveModel};
+use chrono::Utc;
+use sqlx::postgres::PgQueryResult;
+use sqlx::{Error, PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+// a new item goes to the end of the checklist. the note stays locked until the item is saved,
+// so items added at the same time never get the same position
+pub async fn save_checklist_item(
+    pool: &PgPool,
+    body: ChecklistItemSaveModel,
+) -> Result<ChecklistItemModel, Error> {
+    let mut tx = pool.begin().await?;
+    sqlx::query("SELECT id FROM notes WHERE id = $1 FOR NO KEY UPDATE")
+        .bind(body.note_id)
+        .execute(&mut tx)
+        .await?;
+    let query = "INSERT INTO note_checklist_items (note_id,position,text,created_by) SELECT $1, COALESCE(MAX(position) + 1, 0), $2, $3 FROM note_checklist_items WHERE note_id = $1 RETURNING *";
+    let item = sqlx::query_as::<_, ChecklistItemModel>(query)
+        .bind(body.note_id)
+        .bind(body.text)
+        .bind(body.created_by)
+        .fetch_one(&mut tx)
+        .await?;
+    tx.commit().await?;
+
+    Ok(item)
+}
+
+pub async fn get_checklist_items_by_note_id(
+    pool: &PgPool,
+    note_id: Uuid,
+) -> Result<Vec<ChecklistItemModel>, Error> {
+    let query = "SELECT * FROM note_checklist_items i where i.note_id = $1 ORDER BY i.position, i.created_at, i.id";
+    sqlx::query_as::<_, ChecklistItemModel>(query)
+        .bind(note_id)
+        .fetch_all(pool)
+        .await
+}
+
+pub async fn toggle_checklist_item(
+    pool: &PgPool,
+    note_id: Uuid,
+    item_id: Uuid,
+    user_id: Uuid,
+) -> Result<ChecklistItemModel, Error> {
+    let now = Utc::now();
+    let query = "UPDATE note_checklist_items SET checked = NOT checked, updated_by = $1, updated_at = $2 WHERE id = $3 AND note_id = $4 RETURNING *";
+    sqlx::query_as::<_, ChecklistItemModel>(query)
+        .bind(user_id)
+        .bind(now)
+        .bind(item_id)
+        .bind(note_id)
+        .fetch_one(pool)
+        .await
+}
+
+pub async fn delete_checklist_item(
+    pool: &PgPool,
+    note_id: Uuid,
+    item_id: Uuid,
+) -> Result<PgQueryResult, Error> {
+    sqlx::query("DELETE FROM note_checklist_items WHERE id = $1 AND note_id = $2")
+        .bind(item_id)
+        .bind(note_id)
+        .execute(pool)
+        .await
+}
+
+// the items stay locked until the reorder transaction ends
+pub async fn get_checklist_item_ids_for_update(
+    tx: &mut Transaction<'_, Postgres>,
+    note_id: Uuid,
+) -> Result<Vec<Uuid>, Error> {
+    let rows: Vec<(Uuid,)> = sqlx::query_as(
+        "SELECT id FROM note_checklist_items WHERE note_id = $1 ORDER BY id FOR UPDATE",
+    )
+    .bind(note_id)
+    .fetch_all(&mut *tx)
+    .await?;
+    Ok(rows.into_iter().map(|(id,)| id).collect())
+}
+
+// every item takes its index in `ids` as its position
+pub async fn reorder_checklist_items(
+    tx: &mut Transaction<'_, Postgres>,
+    note_id: Uuid,
+    ids: &[Uuid],
+    user_id: Uuid,
+) -> Result<PgQueryResult, Error> {
+    let now = Utc::now();
+    let query = "UPDATE note_checklist_items i SET position = o.position - 1, updated_by = $1, updated_at = $2 FROM unnest($3::uuid[]) WITH ORDINALITY AS o(id, position) WHERE i.id = o.id AND i.note_id = $4 AND i.position <> o.position - 1";
+    sqlx::query(query)
+        .bind(user_id)
+        .bind(now)
+        .bind(ids)
+        .bind(note_id)
+        .execute(&mut *tx)
+        .await
+}
@@ -0,0 +1,13 @@
+use crate::modules::checklists::handler;
+use actix_web::web;
+
+pub fn routes(conf: &mut web::ServiceConfig) {
+    let scope = web::scope("/notes/{note_id}/checklist")
+        .service(handler::checklist_handler)
+        .service(handler::create_checklist_item_handler)
+        .service(handler::reorder_checklist_handler)
+        .service(handler::toggle_checklist_item_handler)
+        .service(handler::delete_checklist_item_handler);
+
+    conf.service(scope);
+}
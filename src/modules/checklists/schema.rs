@@ -0,0 +1,37 @@
+use crate::modules::checklists::model::ChecklistItemModel;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::ValidationError;
+use validator_derive::Validate;
+
+#[derive(Deserialize, Serialize, Debug, Validate)]
+pub struct CreateChecklistItemSchema {
+    #[validate(custom = "validate_text")]
+    pub text: String,
+}
+
+// the new order of the checklist, every item id once
+#[derive(Deserialize, Serialize, Debug, Validate)]
+pub struct ReorderChecklistSchema {
+    #[validate(length(min = 1, max = 1000))]
+    pub ids: Vec<Uuid>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct ChecklistResponse {
+    pub done: i64,
+    pub total: i64,
+    pub progress: String,
+    pub items: Vec<ChecklistItemModel>,
+}
+
+// the text is saved trimmed, so that is what has to fit
+fn validate_text(text: &str) -> Result<(), ValidationError> {
+    let length = text.trim().chars().count();
+    if !(1..=1000).contains(&length) {
+        return Err(ValidationError::new(
+            "text must have a length between 1 and 1000 characters",
+        ));
+    }
+    Ok(())
+}
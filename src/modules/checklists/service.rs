@@ -0,0 +1,135 @@
+use crate::modules::checklists::constants;
+use crate::modules::checklists::model::{ChecklistItemModel, ChecklistItemSaveModel};
+use crate::modules::checklists::repository;
+use crate::modules::checklists::schema::{
+    ChecklistResponse, CreateChecklistItemSchema, ReorderChecklistSchema,
+};
+use crate::modules::notes::service as note_service;
+use sqlx::{Error, PgPool};
+use std::collections::HashSet;
+use uuid::Uuid;
+
+fn to_checklist_response(items: Vec<ChecklistItemModel>) -> ChecklistResponse {
+    let done = items.iter().filter(|item| item.checked).count() as i64;
+    let total = items.len() as i64;
+    ChecklistResponse {
+        done,
+        total,
+        progress: format!("{}/{} done", done, total),
+        items,
+    }
+}
+
+async fn get_checklist(pool: &PgPool, note_id: Uuid) -> Result<ChecklistResponse, String> {
+    match repository::get_checklist_items_by_note_id(pool, note_id).await {
+        Ok(items) => Ok(to_checklist_response(items)),
+        Err(err) => {
+            eprintln!("error get checklist {:?}", err);
+            Err(constants::CHECKLIST_CANT_BE_FETCHED.to_string())
+        }
+    }
+}
+
+// anyone who can read the note can read its checklist
+pub async fn get_checklist_service(
+    pool: &PgPool,
+    note_id: Uuid,
+    user_id: Uuid,
+) -> Result<ChecklistResponse, String> {
+    note_service::get_readable_note_service(pool, note_id, user_id).await?;
+    get_checklist(pool, note_id).await
+}
+
+// only the owner of the note changes its checklist
+pub async fn save_checklist_item_service(
+    pool: &PgPool,
+    note_id: Uuid,
+    body: &CreateChecklistItemSchema,
+    user_id: Uuid,
+) -> Result<ChecklistItemModel, String> {
+    note_service::get_note_detail_service(pool, note_id, user_id).await?;
+
+    let item_save_model = ChecklistItemSaveModel {
+        note_id,
+        text: body.text.trim().to_string(),
+        created_by: user_id,
+    };
+
+    match repository::save_checklist_item(pool, item_save_model).await {
+        Ok(item) => Ok(item),
+        Err(err) => {
+            eprintln!("Error saving checklist item: {:?}", err);
+            Err(constants::CHECKLIST_ITEM_CANT_BE_SAVED.to_string())
+        }
+    }
+}
+
+pub async fn toggle_checklist_item_service(
+    pool: &PgPool,
+    note_id: Uuid,
+    item_id: Uuid,
+    user_id: Uuid,
+) -> Result<ChecklistItemModel, String> {
+    note_service::get_note_detail_service(pool, note_id, user_id).await?;
+
+    match repository::toggle_checklist_item(pool, note_id, item_id, user_id).await {
+        Ok(item) => Ok(item),
+        Err(Error::RowNotFound) => Err(constants::CHECKLIST_ITEM_NOT_FOUND.to_string()),
+        Err(err) => {
+            eprintln!("Error toggle checklist item: {:?}", err);
+            Err(constants::CHECKLIST_ITEM_CANT_BE_TOGGLED.to_string())
+        }
+    }
+}
+
+pub async fn delete_checklist_item_service(
+    pool: &PgPool,
+    note_id: Uuid,
+    item_id: Uuid,
+    user_id: Uuid,
+) -> Result<(), String> {
+    note_service::get_note_detail_service(pool, note_id, user_id).await?;
+
+    match repository::delete_checklist_item(pool, note_id, item_id).await {
+        Ok(result) if result.rows_affected() == 0 => {
+            Err(constants::CHECKLIST_ITEM_NOT_FOUND.to_string())
+        }
+        Ok(_) => Ok(()),
+        Err(err) => {
+            eprintln!("Error delete checklist item: {:?}", err);
+            Err(constants::CHECKLIST_ITEM_CANT_BE_DELETE.to_string())
+        }
+    }
+}
+
+// a partial order would leave the positions ambiguous, so the ids have to match the
+// checklist exactly
+pub async fn reorder_checklist_service(
+    pool: &PgPool,
+    note_id: Uuid,
+    body: &ReorderChecklistSchema,
+    user_id: Uuid,
+) -> Result<ChecklistResponse, String> {
+    note_service::get_note_detail_service(pool, note_id, user_id).await?;
+
+    let reorder_error = |err: Error| {
+        eprintln!("Error reorder checklist: {:?}", err);
+        constants::CHECKLIST_CANT_BE_REORDERED.to_string()
+    };
+    let mut tx = pool.begin().await.map_err(reorder_error)?;
+    let existing: HashSet<Uuid> = repository::get_checklist_item_ids_for_update(&mut tx, note_id)
+        .await
+        .map_err(reorder_error)?
+        .into_iter()
+        .collect();
+    let requested: HashSet<Uuid> = body.ids.iter().copied().collect();
+    if requested.len() != body.ids.len() || requested != existing {
+        return Err(constants::CHECKLIST_ORDER_INVALID.to_string());
+    }
+    repository::reorder_checklist_items(&mut tx, note_id, &body.ids, user_id)
+        .await
+        .map_err(reorder_error)?;
+    tx.commit().await.map_err(reorder_error)?;
+
+    get_checklist(pool, note_id).await
+}
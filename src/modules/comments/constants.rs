@@ -0,0 +1,13 @@
+pub const COMMENT_NOT_FOUND: &str = "comment not found";
+pub const COMMENT_PARENT_NOT_FOUND: &str = "parent comment not found";
+pub const COMMENT_FORBIDDEN: &str =
+    "only the comment author or the note owner can change the comment";
+pub const PARAMETER_COMMENT_ID_INVALID: &str = "parameter comment_id is invalid";
+pub const COMMENT_FOUND: &str = "success get data comment";
+pub const COMMENT_SUCCESS_SAVED: &str = "success saved data comment";
+pub const COMMENT_SUCCESS_PATCHED: &str = "success patched data comment";
+pub const COMMENT_SUCCESS_DELETED: &str = "success deleted data comment";
+pub const COMMENT_CANT_BE_FETCHED: &str = "something went wrong while get comment";
+pub const COMMENT_CANT_BE_SAVED: &str = "something went wrong while saving the comment";
+pub const COMMENT_CANT_BE_PATCHED: &str = "something went wrong while updating the comment";
+pub const COMMENT_CANT_BE_DELETE: &str = "something went wrong while delete the comment";
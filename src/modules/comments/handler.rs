@@ -0,0 +1,222 @@
+use crate::infrastructure::http_lib::Response;
+use crate::modules::comments::constants;
+use crate::modules::comments::schema::{CommentResponse, CreateCommentSchema, UpdateCommentSchema};
+use crate::modules::comments::service;
+use crate::modules::notes::constants as note_constants;
+use crate::utils::utils;
+use crate::AppState;
+use actix_web::http::StatusCode;
+use actix_web::{delete, get, post, put, web, HttpRequest, HttpResponse, Responder};
+use validator::Validate;
+
+fn error_response(err: &str) -> HttpResponse {
+    let status = if err.contains(note_constants::NOTE_NOT_FOUND)
+        || err.contains(constants::COMMENT_NOT_FOUND)
+    {
+        StatusCode::NOT_FOUND
+    } else if err.contains(constants::COMMENT_PARENT_NOT_FOUND) {
+        StatusCode::BAD_REQUEST
+    } else if err.contains(constants::COMMENT_FORBIDDEN) {
+        StatusCode::FORBIDDEN
+    } else {
+        StatusCode::INTERNAL_SERVER_ERROR
+    };
+    let resp: Response<(), ()> = Response::error(status, err);
+    HttpResponse::build(status).json(resp)
+}
+
+#[get("")]
+pub async fn comment_list_handler(
+    path: web::Path<String>,
+    data: web::Data<AppState>,
+    req: HttpRequest,
+) -> impl Responder {
+    let note_id_str = path.into_inner();
+
+    //get user_id from authorization token
+    let user_id = utils::get_current_user_uuid_from_jwt(&data.cfg.clone(), req);
+    if let Err(err) = user_id {
+        let resp: Response<(), ()> = Response::error(StatusCode::UNAUTHORIZED, err.as_str());
+        return HttpResponse::Unauthorized().json(resp);
+    }
+
+    // Attempt to parse the UUID from the path
+    let note_id = match uuid::Uuid::parse_str(&note_id_str) {
+        Ok(uuid) => uuid,
+        Err(err) => {
+            eprintln!("match uuid::Uuid::parse_str, got err : {:?}", err);
+            let msg = note_constants::PARAMETER_NOTE_ID_INVALID;
+            let error_response: Response<(), ()> = Response::error(StatusCode::BAD_REQUEST, msg);
+            return HttpResponse::BadRequest().json(error_response);
+        }
+    };
+
+    let comments = match service::get_comments_service(&data.db, note_id, user_id.unwrap()).await {
+        Ok(comments) => comments,
+        Err(err) => return error_response(&err),
+    };
+
+    let msg = constants::COMMENT_FOUND;
+    let resp: Response<Vec<CommentResponse>, ()> = Response::success(StatusCode::OK, comments, msg);
+    return HttpResponse::Ok().json(resp);
+}
+
+#[post("")]
+pub async fn create_comment_handler(
+    path: web::Path<String>,
+    body: web::Json<CreateCommentSchema>,
+    data: web::Data<AppState>,
+    req: HttpRequest,
+) -> impl Responder {
+    let note_id_str = path.into_inner();
+
+    //get user_id from authorization token
+    let user_id = utils::get_current_user_uuid_from_jwt(&data.cfg.clone(), req);
+    if let Err(err) = user_id {
+        let resp: Response<(), ()> = Response::error(StatusCode::UNAUTHORIZED, err.as_str());
+        return HttpResponse::Unauthorized().json(resp);
+    }
+
+    // Attempt to parse the UUID from the path
+    let note_id = match uuid::Uuid::parse_str(&note_id_str) {
+        Ok(uuid) => uuid,
+        Err(err) => {
+            eprintln!("match uuid::Uuid::parse_str, got err : {:?}", err);
+            let msg = note_constants::PARAMETER_NOTE_ID_INVALID;
+            let error_response: Response<(), ()> = Response::error(StatusCode::BAD_REQUEST, msg);
+            return HttpResponse::BadRequest().json(error_response);
+        }
+    };
+
+    //validate the struct from body
+    if let Err(errors) = body.validate() {
+        let resp = Response::custom(
+            StatusCode::BAD_REQUEST,
+            StatusCode::BAD_REQUEST.as_str(),
+            (),
+            errors,
+        );
+        return HttpResponse::BadRequest().json(resp);
+    }
+
+    let comment =
+        match service::save_comment_service(&data.db, note_id, &body.0, user_id.unwrap()).await {
+            Ok(comment) => comment,
+            Err(err) => return error_response(&err),
+        };
+
+    let msg = constants::COMMENT_SUCCESS_SAVED;
+    let resp: Response<CommentResponse, ()> = Response::success(StatusCode::OK, comment, msg);
+    return HttpResponse::Ok().json(resp);
+}
+
+#[put("/{comment_id}")]
+pub async fn edit_comment_handler(
+    path: web::Path<(String, String)>,
+    body: web::Json<UpdateCommentSchema>,
+    data: web::Data<AppState>,
+    req: HttpRequest,
+) -> impl Responder {
+    let (note_id_str, comment_id_str) = path.into_inner();
+
+    //get user_id from authorization token
+    let user_id = utils::get_current_user_uuid_from_jwt(&data.cfg.clone(), req);
+    if let Err(err) = user_id {
+        let resp: Response<(), ()> = Response::error(StatusCode::UNAUTHORIZED, err.as_str());
+        return HttpResponse::Unauthorized().json(resp);
+    }
+
+    // Attempt to parse the UUID from the path
+    let note_id = match uuid::Uuid::parse_str(&note_id_str) {
+        Ok(uuid) => uuid,
+        Err(err) => {
+            eprintln!("match uuid::Uuid::parse_str, got err : {:?}", err);
+            let msg = note_constants::PARAMETER_NOTE_ID_INVALID;
+            let error_response: Response<(), ()> = Response::error(StatusCode::BAD_REQUEST, msg);
+            return HttpResponse::BadRequest().json(error_response);
+        }
+    };
+    let comment_id = match uuid::Uuid::parse_str(&comment_id_str) {
+        Ok(uuid) => uuid,
+        Err(err) => {
+            eprintln!("match uuid::Uuid::parse_str, got err : {:?}", err);
+            let msg = constants::PARAMETER_COMMENT_ID_INVALID;
+            let error_response: Response<(), ()> = Response::error(StatusCode::BAD_REQUEST, msg);
+            return HttpResponse::BadRequest().json(error_response);
+        }
+    };
+
+    //validate the struct from body
+    if let Err(errors) = body.validate() {
+        let resp = Response::custom(
+            StatusCode::BAD_REQUEST,
+            StatusCode::BAD_REQUEST.as_str(),
+            (),
+            errors,
+        );
+        return HttpResponse::BadRequest().json(resp);
+    }
+
+    let comment = match service::update_comment_service(
+        &data.db,
+        note_id,
+        comment_id,
+        &body.0,
+        user_id.unwrap(),
+    )
+    .await
+    {
+        Ok(comment) => comment,
+        Err(err) => return error_response(&err),
+    };
+
+    let msg = constants::COMMENT_SUCCESS_PATCHED;
+    let resp: Response<CommentResponse, ()> = Response::success(StatusCode::OK, comment, msg);
+    return HttpResponse::Ok().json(resp);
+}
+
+#[delete("/{comment_id}")]
+pub async fn delete_comment_handler(
+    path: web::Path<(String, String)>,
+    data: web::Data<AppState>,
+    req: HttpRequest,
+) -> impl Responder {
+    let (note_id_str, comment_id_str) = path.into_inner();
+
+    //get user_id from authorization token
+    let user_id = utils::get_current_user_uuid_from_jwt(&data.cfg.clone(), req);
+    if let Err(err) = user_id {
+        let resp: Response<(), ()> = Response::error(StatusCode::UNAUTHORIZED, err.as_str());
+        return HttpResponse::Unauthorized().json(resp);
+    }
+
+    // Attempt to parse the UUID from the path
+    let note_id = match uuid::Uuid::parse_str(&note_id_str) {
+        Ok(uuid) => uuid,
+        Err(err) => {
+            eprintln!("match uuid::Uuid::parse_str, got err : {:?}", err);
+            let msg = note_constants::PARAMETER_NOTE_ID_INVALID;
+            let error_response: Response<(), ()> = Response::error(StatusCode::BAD_REQUEST, msg);
+            return HttpResponse::BadRequest().json(error_response);
+        }
+    };
+    let comment_id = match uuid::Uuid::parse_str(&comment_id_str) {
+        Ok(uuid) => uuid,
+        Err(err) => {
+            eprintln!("match uuid::Uuid::parse_str, got err : {:?}", err);
+            let msg = constants::PARAMETER_COMMENT_ID_INVALID;
+            let error_response: Response<(), ()> = Response::error(StatusCode::BAD_REQUEST, msg);
+            return HttpResponse::BadRequest().json(error_response);
+        }
+    };
+
+    if let Err(err) =
+        service::delete_comment_service(&data.db, note_id, comment_id, user_id.unwrap()).await
+    {
+        return error_response(&err);
+    }
+
+    let msg = constants::COMMENT_SUCCESS_DELETED;
+    let resp: Response<(), ()> = Response::success(StatusCode::OK, (), msg);
+    return HttpResponse::Ok().json(resp);
+}
@@ -0,0 +1,29 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+#[derive(Debug, FromRow, Deserialize, Serialize, Clone)]
+#[allow(non_snake_case)]
+pub struct CommentModel {
+    pub id: Uuid,
+    pub note_id: Uuid,
+    pub parent_id: Option<Uuid>,
+    pub content: String,
+    pub created_by: Uuid,
+    pub updated_by: Option<Uuid>,
+    #[serde(rename = "createdAt")]
+    pub created_at: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(rename = "updatedAt")]
+    pub updated_at: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(rename = "deletedAt")]
+    pub deleted_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[allow(non_snake_case)]
+pub struct CommentSaveModel {
+    pub note_id: Uuid,
+    pub parent_id: Option<Uuid>,
+    pub content: String,
+    pub created_by: Uuid,
+}
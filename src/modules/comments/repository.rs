@@ -0,0 +1,77 @@
+use crate::modules::comments::model::{CommentModel, CommentSaveModel};
+use chrono::{DateTime, Utc};
+use sqlx::postgres::PgQueryResult;
+use sqlx::{Error, PgPool};
+use uuid::Uuid;
+
+pub async fn save_comment(pool: &PgPool, body: CommentSaveModel) -> Result<CommentModel, Error> {
+    let query = "INSERT INTO note_comments (note_id,parent_id,content,created_by) VALUES ($1, $2, $3, $4) RETURNING *";
+    sqlx::query_as::<_, CommentModel>(query)
+        .bind(body.note_id)
+        .bind(body.parent_id)
+        .bind(body.content)
+        .bind(body.created_by)
+        .fetch_one(pool)
+        .await
+}
+
+pub async fn get_comments_by_note_id(
+    pool: &PgPool,
+    note_id: Uuid,
+) -> Result<Vec<CommentModel>, Error> {
+    let query = "SELECT * FROM note_comments c where c.deleted_at is null and c.note_id = $1 ORDER BY c.created_at, c.id";
+    sqlx::query_as::<_, CommentModel>(query)
+        .bind(note_id)
+        .fetch_all(pool)
+        .await
+}
+
+pub async fn get_comment_by_id(
+    pool: &PgPool,
+    note_id: Uuid,
+    comment_id: Uuid,
+) -> Result<CommentModel, Error> {
+    let query =
+        "SELECT * FROM note_comments c where c.deleted_at is null and c.id = $1 and c.note_id = $2";
+    sqlx::query_as::<_, CommentModel>(query)
+        .bind(comment_id)
+        .bind(note_id)
+        .fetch_one(pool)
+        .await
+}
+
+pub async fn update_comment(
+    pool: &PgPool,
+    comment_id: Uuid,
+    content: String,
+    user_id: Uuid,
+) -> Result<CommentModel, Error> {
+    let now = Utc::now();
+    let query = "UPDATE note_comments SET content = $1, updated_by = $2, updated_at = $3 WHERE id = $4 RETURNING *";
+    sqlx::query_as::<_, CommentModel>(query)
+        .bind(content)
+        .bind(user_id)
+        .bind(now)
+        .bind(comment_id)
+        .fetch_one(pool)
+        .await
+}
+
+// a deleted comment takes its whole reply thread with it
+pub async fn delete_comment_thread_by_id(pool: &PgPool, comment_id: Uuid) -> Result<i32, Error> {
+    let now: DateTime<Utc> = Utc::now();
+    let query = "WITH RECURSIVE thread AS ( \
+            SELECT id FROM note_comments WHERE id = $2 \
+            UNION ALL \
+            SELECT c.id FROM note_comments c JOIN thread t ON c.parent_id = t.id \
+        ) \
+        UPDATE note_comments SET deleted_at = $1 \
+        WHERE id IN (SELECT id FROM thread) AND deleted_at IS NULL";
+    let rows_affected: PgQueryResult = sqlx::query::<_>(query)
+        .bind(now)
+        .bind(comment_id)
+        .execute(pool)
+        .await?;
+
+    Ok(rows_affected.rows_affected() as i32)
+}
@@ -0,0 +1,12 @@
+use crate::modules::comments::handler;
+use actix_web::web;
+
+pub fn routes(conf: &mut web::ServiceConfig) {
+    let scope = web::scope("/notes/{note_id}/comments")
+        .service(handler::comment_list_handler)
+        .service(handler::create_comment_handler)
+        .service(handler::edit_comment_handler)
+        .service(handler::delete_comment_handler);
+
+    conf.service(scope);
+}
@@ -0,0 +1,33 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator_derive::Validate;
+
+#[derive(Deserialize, Serialize, Debug, Validate)]
+pub struct CreateCommentSchema {
+    #[validate(length(min = 1, max = 10000))]
+    pub content: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parent_id: Option<Uuid>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Validate)]
+pub struct UpdateCommentSchema {
+    #[validate(length(min = 1, max = 10000))]
+    pub content: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[allow(non_snake_case)]
+pub struct CommentResponse {
+    pub id: Uuid,
+    pub note_id: Uuid,
+    pub parent_id: Option<Uuid>,
+    pub content: String,
+    pub created_by: Uuid,
+    pub updated_by: Option<Uuid>,
+    #[serde(rename = "createdAt")]
+    pub created_at: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(rename = "updatedAt")]
+    pub updated_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub replies: Vec<CommentResponse>,
+}
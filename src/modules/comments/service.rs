@@ -0,0 +1,171 @@
+use crate::modules::comments::constants;
+use crate::modules::comments::model::{CommentModel, CommentSaveModel};
+use crate::modules::comments::repository;
+use crate::modules::comments::schema::{CommentResponse, CreateCommentSchema, UpdateCommentSchema};
+use crate::modules::notes::model::NoteModel;
+use crate::modules::notes::service as note_service;
+use sqlx::{Error, PgPool};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+fn to_comment_response(
+    comment: CommentModel,
+    replies_by_parent: &mut HashMap<Uuid, Vec<CommentModel>>,
+) -> CommentResponse {
+    let replies = replies_by_parent
+        .remove(&comment.id)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|reply| to_comment_response(reply, replies_by_parent))
+        .collect();
+
+    CommentResponse {
+        id: comment.id,
+        note_id: comment.note_id,
+        parent_id: comment.parent_id,
+        content: comment.content,
+        created_by: comment.created_by,
+        updated_by: comment.updated_by,
+        created_at: comment.created_at,
+        updated_at: comment.updated_at,
+        replies,
+    }
+}
+
+// comments come back ordered by creation, so every reply list keeps that order
+fn build_comment_tree(comments: Vec<CommentModel>) -> Vec<CommentResponse> {
+    let mut roots: Vec<CommentModel> = Vec::new();
+    let mut replies_by_parent: HashMap<Uuid, Vec<CommentModel>> = HashMap::new();
+    for comment in comments {
+        match comment.parent_id {
+            Some(parent_id) => replies_by_parent
+                .entry(parent_id)
+                .or_default()
+                .push(comment),
+            None => roots.push(comment),
+        }
+    }
+
+    roots
+        .into_iter()
+        .map(|comment| to_comment_response(comment, &mut replies_by_parent))
+        .collect()
+}
+
+async fn get_comment(
+    pool: &PgPool,
+    note_id: Uuid,
+    comment_id: Uuid,
+    not_found_message: &str,
+) -> Result<CommentModel, String> {
+    match repository::get_comment_by_id(pool, note_id, comment_id).await {
+        Ok(comment) => Ok(comment),
+        Err(err) => match err {
+            Error::RowNotFound => Err(not_found_message.to_string()),
+            _ => {
+                eprintln!("error get comment {:?}", err);
+                Err(constants::COMMENT_CANT_BE_FETCHED.to_string())
+            }
+        },
+    }
+}
+
+// comment authors and the owner of the note are the only ones allowed to change a comment
+async fn get_changeable_comment(
+    pool: &PgPool,
+    note_id: Uuid,
+    comment_id: Uuid,
+    user_id: Uuid,
+) -> Result<CommentModel, String> {
+    let note: NoteModel = note_service::get_readable_note_service(pool, note_id, user_id).await?;
+    let comment = get_comment(pool, note_id, comment_id, constants::COMMENT_NOT_FOUND).await?;
+    if comment.created_by != user_id && note.created_by != Some(user_id) {
+        return Err(constants::COMMENT_FORBIDDEN.to_string());
+    }
+    Ok(comment)
+}
+
+pub async fn get_comments_service(
+    pool: &PgPool,
+    note_id: Uuid,
+    user_id: Uuid,
+) -> Result<Vec<CommentResponse>, String> {
+    note_service::get_readable_note_service(pool, note_id, user_id).await?;
+
+    match repository::get_comments_by_note_id(pool, note_id).await {
+        Ok(comments) => Ok(build_comment_tree(comments)),
+        Err(err) => {
+            eprintln!("error get comments {:?}", err);
+            Err(constants::COMMENT_CANT_BE_FETCHED.to_string())
+        }
+    }
+}
+
+pub async fn save_comment_service(
+    pool: &PgPool,
+    note_id: Uuid,
+    body: &CreateCommentSchema,
+    user_id: Uuid,
+) -> Result<CommentResponse, String> {
+    note_service::get_readable_note_service(pool, note_id, user_id).await?;
+
+    if let Some(parent_id) = body.parent_id {
+        get_comment(
+            pool,
+            note_id,
+            parent_id,
+            constants::COMMENT_PARENT_NOT_FOUND,
+        )
+        .await?;
+    }
+
+    let comment_save_model = CommentSaveModel {
+        note_id,
+        parent_id: body.parent_id,
+        content: body.content.to_string(),
+        created_by: user_id,
+    };
+
+    match repository::save_comment(pool, comment_save_model).await {
+        Ok(comment) => Ok(to_comment_response(comment, &mut HashMap::new())),
+        Err(err) => {
+            eprintln!("Error saving comment: {:?}", err);
+            Err(constants::COMMENT_CANT_BE_SAVED.to_string())
+        }
+    }
+}
+
+pub async fn update_comment_service(
+    pool: &PgPool,
+    note_id: Uuid,
+    comment_id: Uuid,
+    body: &UpdateCommentSchema,
+    user_id: Uuid,
+) -> Result<CommentResponse, String> {
+    get_changeable_comment(pool, note_id, comment_id, user_id).await?;
+
+    match repository::update_comment(pool, comment_id, body.content.to_string(), user_id).await {
+        Ok(comment) => Ok(to_comment_response(comment, &mut HashMap::new())),
+        Err(err) => {
+            eprintln!("Error update comment: {:?}", err);
+            Err(constants::COMMENT_CANT_BE_PATCHED.to_string())
+        }
+    }
+}
+
+pub async fn delete_comment_service(
+    pool: &PgPool,
+    note_id: Uuid,
+    comment_id: Uuid,
+    user_id: Uuid,
+) -> Result<i32, String> {
+    get_changeable_comment(pool, note_id, comment_id, user_id).await?;
+
+    match repository::delete_comment_thread_by_id(pool, comment_id).await {
+        Ok(rows) => Ok(rows),
+        Err(err) => {
+            eprintln!("Error delete comment: {:?}", err);
+            Err(constants::COMMENT_CANT_BE_DELETE.to_string())
+        }
+    }
+}
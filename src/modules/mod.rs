@@ -1,3 +1,9 @@
+pub mod attachments;
 pub mod auth;
+pub mod categories;
+pub mod checklists;
+pub mod comments;
+pub mod notebooks;
 pub mod notes;
+pub mod templates;
 pub mod users;
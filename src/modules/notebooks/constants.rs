@@ -0,0 +1,16 @@
+pub const NOTEBOOK_NOT_FOUND: &str = "notebook not found";
+pub const PARENT_NOTEBOOK_NOT_FOUND: &str = "parent notebook not found";
+pub const NOTEBOOK_MOVE_CYCLE: &str =
+    "notebook can not be moved into itself or one of its children";
+pub const PARAMETER_NOTEBOOK_ID_INVALID: &str = "parameter id is invalid";
+pub const PARAMETER_DELETE_MODE_INVALID: &str = "parameter mode is invalid, use trash or root";
+pub const NOTEBOOK_FOUND: &str = "success get data notebook";
+pub const NOTEBOOK_SUCCESS_SAVED: &str = "success saved data notebook";
+pub const NOTEBOOK_SUCCESS_PATCHED: &str = "success patched data notebook";
+pub const NOTEBOOK_SUCCESS_MOVED: &str = "success moved data notebook";
+pub const NOTEBOOK_SUCCESS_DELETED: &str = "success deleted data notebook";
+pub const NOTEBOOK_CANT_BE_FETCHED: &str = "something went wrong while get notebook";
+pub const NOTEBOOK_CANT_BE_SAVED: &str = "something went wrong while saving the notebook";
+pub const NOTEBOOK_CANT_BE_PATCHED: &str = "something went wrong while updating the notebook";
+pub const NOTEBOOK_CANT_BE_MOVED: &str = "something went wrong while moving the notebook";
+pub const NOTEBOOK_CANT_BE_DELETE: &str = "something went wrong while delete the notebook";
@@ -0,0 +1,254 @@
+use crate::infrastructure::http_lib::Response;
+use crate::modules::notebooks::constants;
+use crate::modules::notebooks::model::NotebookModel;
+use crate::modules::notebooks::schema::{
+    CreateNotebookSchema, DeleteNotebookOptions, MoveNotebookSchema, NotebookResponse,
+    UpdateNotebookSchema,
+};
+use crate::modules::notebooks::service;
+use crate::utils::utils;
+use crate::AppState;
+use actix_web::http::StatusCode;
+use actix_web::{delete, get, post, put, web, HttpRequest, HttpResponse, Responder};
+use validator::Validate;
+
+fn error_response(err: &str) -> HttpResponse {
+    // the parent check comes first, its message contains the one of a missing notebook
+    let status = if err.contains(constants::PARENT_NOTEBOOK_NOT_FOUND)
+        || err.contains(constants::PARAMETER_DELETE_MODE_INVALID)
+    {
+        StatusCode::BAD_REQUEST
+    } else if err.contains(constants::NOTEBOOK_NOT_FOUND) {
+        StatusCode::NOT_FOUND
+    } else if err.contains(constants::NOTEBOOK_MOVE_CYCLE) {
+        StatusCode::CONFLICT
+    } else {
+        StatusCode::INTERNAL_SERVER_ERROR
+    };
+    let resp: Response<(), ()> = Response::error(status, err);
+    HttpResponse::build(status).json(resp)
+}
+
+#[get("")]
+pub async fn notebook_list_handler(data: web::Data<AppState>, req: HttpRequest) -> impl Responder {
+    //get user_id from authorization token
+    let user_id = utils::get_current_user_uuid_from_jwt(&data.cfg.clone(), req);
+    if let Err(err) = user_id {
+        let resp: Response<(), ()> = Response::error(StatusCode::UNAUTHORIZED, err.as_str());
+        return HttpResponse::Unauthorized().json(resp);
+    }
+
+    let notebooks = match service::get_notebooks_service(&data.db, user_id.unwrap()).await {
+        Ok(notebooks) => notebooks,
+        Err(err) => return error_response(&err),
+    };
+
+    let msg = constants::NOTEBOOK_FOUND;
+    let resp: Response<Vec<NotebookResponse>, ()> =
+        Response::success(StatusCode::OK, notebooks, msg);
+    HttpResponse::Ok().json(resp)
+}
+
+#[post("")]
+pub async fn create_notebook_handler(
+    body: web::Json<CreateNotebookSchema>,
+    data: web::Data<AppState>,
+    req: HttpRequest,
+) -> impl Responder {
+    //get user_id from authorization token
+    let user_id = utils::get_current_user_uuid_from_jwt(&data.cfg.clone(), req);
+    if let Err(err) = user_id {
+        let resp: Response<(), ()> = Response::error(StatusCode::UNAUTHORIZED, err.as_str());
+        return HttpResponse::Unauthorized().json(resp);
+    }
+
+    //validate the struct from body
+    if let Err(errors) = body.validate() {
+        let resp = Response::custom(
+            StatusCode::BAD_REQUEST,
+            StatusCode::BAD_REQUEST.as_str(),
+            (),
+            errors,
+        );
+        return HttpResponse::BadRequest().json(resp);
+    }
+
+    let notebook = match service::save_notebook_service(&data.db, &body.0, user_id.unwrap()).await {
+        Ok(notebook) => notebook,
+        Err(err) => return error_response(&err),
+    };
+
+    let msg = constants::NOTEBOOK_SUCCESS_SAVED;
+    let resp: Response<NotebookModel, ()> = Response::success(StatusCode::OK, notebook, msg);
+    HttpResponse::Ok().json(resp)
+}
+
+#[get("/{id}")]
+pub async fn get_notebook_handler(
+    path: web::Path<String>,
+    data: web::Data<AppState>,
+    req: HttpRequest,
+) -> impl Responder {
+    let notebook_id_str = path.into_inner();
+
+    //get user_id from authorization token
+    let user_id = utils::get_current_user_uuid_from_jwt(&data.cfg.clone(), req);
+    if let Err(err) = user_id {
+        let resp: Response<(), ()> = Response::error(StatusCode::UNAUTHORIZED, err.as_str());
+        return HttpResponse::Unauthorized().json(resp);
+    }
+
+    // Attempt to parse the UUID from the path
+    let notebook_id = match uuid::Uuid::parse_str(&notebook_id_str) {
+        Ok(uuid) => uuid,
+        Err(err) => {
+            eprintln!("match uuid::Uuid::parse_str, got err : {:?}", err);
+            let msg = constants::PARAMETER_NOTEBOOK_ID_INVALID;
+            let error_response: Response<(), ()> = Response::error(StatusCode::BAD_REQUEST, msg);
+            return HttpResponse::BadRequest().json(error_response);
+        }
+    };
+
+    let notebook =
+        match service::get_notebook_service(&data.db, notebook_id, user_id.unwrap()).await {
+            Ok(notebook) => notebook,
+            Err(err) => return error_response(&err),
+        };
+
+    let msg = constants::NOTEBOOK_FOUND;
+    let resp: Response<NotebookModel, ()> = Response::success(StatusCode::OK, notebook, msg);
+    HttpResponse::Ok().json(resp)
+}
+
+#[put("/{id}")]
+pub async fn edit_notebook_handler(
+    path: web::Path<String>,
+    body: web::Json<UpdateNotebookSchema>,
+    data: web::Data<AppState>,
+    req: HttpRequest,
+) -> impl Responder {
+    let notebook_id_str = path.into_inner();
+
+    //get user_id from authorization token
+    let user_id = utils::get_current_user_uuid_from_jwt(&data.cfg.clone(), req);
+    if let Err(err) = user_id {
+        let resp: Response<(), ()> = Response::error(StatusCode::UNAUTHORIZED, err.as_str());
+        return HttpResponse::Unauthorized().json(resp);
+    }
+
+    // Attempt to parse the UUID from the path
+    let notebook_id = match uuid::Uuid::parse_str(&notebook_id_str) {
+        Ok(uuid) => uuid,
+        Err(err) => {
+            eprintln!("match uuid::Uuid::parse_str, got err : {:?}", err);
+            let msg = constants::PARAMETER_NOTEBOOK_ID_INVALID;
+            let error_response: Response<(), ()> = Response::error(StatusCode::BAD_REQUEST, msg);
+            return HttpResponse::BadRequest().json(error_response);
+        }
+    };
+
+    //validate the struct from body
+    if let Err(errors) = body.validate() {
+        let resp = Response::custom(
+            StatusCode::BAD_REQUEST,
+            StatusCode::BAD_REQUEST.as_str(),
+            (),
+            errors,
+        );
+        return HttpResponse::BadRequest().json(resp);
+    }
+
+    let notebook =
+        match service::update_notebook_service(&data.db, notebook_id, &body.0, user_id.unwrap())
+            .await
+        {
+            Ok(notebook) => notebook,
+            Err(err) => return error_response(&err),
+        };
+
+    let msg = constants::NOTEBOOK_SUCCESS_PATCHED;
+    let resp: Response<NotebookModel, ()> = Response::success(StatusCode::OK, notebook, msg);
+    HttpResponse::Ok().json(resp)
+}
+
+#[put("/{id}/move")]
+pub async fn move_notebook_handler(
+    path: web::Path<String>,
+    body: web::Json<MoveNotebookSchema>,
+    data: web::Data<AppState>,
+    req: HttpRequest,
+) -> impl Responder {
+    let notebook_id_str = path.into_inner();
+
+    //get user_id from authorization token
+    let user_id = utils::get_current_user_uuid_from_jwt(&data.cfg.clone(), req);
+    if let Err(err) = user_id {
+        let resp: Response<(), ()> = Response::error(StatusCode::UNAUTHORIZED, err.as_str());
+        return HttpResponse::Unauthorized().json(resp);
+    }
+
+    // Attempt to parse the UUID from the path
+    let notebook_id = match uuid::Uuid::parse_str(&notebook_id_str) {
+        Ok(uuid) => uuid,
+        Err(err) => {
+            eprintln!("match uuid::Uuid::parse_str, got err : {:?}", err);
+            let msg = constants::PARAMETER_NOTEBOOK_ID_INVALID;
+            let error_response: Response<(), ()> = Response::error(StatusCode::BAD_REQUEST, msg);
+            return HttpResponse::BadRequest().json(error_response);
+        }
+    };
+
+    let notebook = match service::move_notebook_service(
+        &data.db,
+        notebook_id,
+        &body.0,
+        user_id.unwrap(),
+    )
+    .await
+    {
+        Ok(notebook) => notebook,
+        Err(err) => return error_response(&err),
+    };
+
+    let msg = constants::NOTEBOOK_SUCCESS_MOVED;
+    let resp: Response<NotebookModel, ()> = Response::success(StatusCode::OK, notebook, msg);
+    HttpResponse::Ok().json(resp)
+}
+
+#[delete("/{id}")]
+pub async fn delete_notebook_handler(
+    path: web::Path<String>,
+    options: web::Query<DeleteNotebookOptions>,
+    data: web::Data<AppState>,
+    req: HttpRequest,
+) -> impl Responder {
+    let notebook_id_str = path.into_inner();
+
+    //get user_id from authorization token
+    let user_id = utils::get_current_user_uuid_from_jwt(&data.cfg.clone(), req);
+    if let Err(err) = user_id {
+        let resp: Response<(), ()> = Response::error(StatusCode::UNAUTHORIZED, err.as_str());
+        return HttpResponse::Unauthorized().json(resp);
+    }
+
+    // Attempt to parse the UUID from the path
+    let notebook_id = match uuid::Uuid::parse_str(&notebook_id_str) {
+        Ok(uuid) => uuid,
+        Err(err) => {
+            eprintln!("match uuid::Uuid::parse_str, got err : {:?}", err);
+            let msg = constants::PARAMETER_NOTEBOOK_ID_INVALID;
+            let error_response: Response<(), ()> = Response::error(StatusCode::BAD_REQUEST, msg);
+            return HttpResponse::BadRequest().json(error_response);
+        }
+    };
+
+    if let Err(err) =
+        service::delete_notebook_service(&data.db, notebook_id, &options, user_id.unwrap()).await
+    {
+        return error_response(&err);
+    }
+
+    let msg = constants::NOTEBOOK_SUCCESS_DELETED;
+    let resp: Response<(), ()> = Response::success(StatusCode::OK, (), msg);
+    HttpResponse::Ok().json(resp)
+}
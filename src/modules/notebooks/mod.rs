@@ -0,0 +1,7 @@
+pub(crate) mod constants;
+mod handler;
+mod model;
+mod repository;
+pub mod routes;
+mod schema;
+pub(crate) mod service;
@@ -0,0 +1,184 @@
+use crate::modules::notebooks::model::{NotebookModel, NotebookSaveModel};
+use chrono::{DateTime, Utc};
+use sqlx::postgres::PgQueryResult;
+use sqlx::{Error, PgPool};
+use uuid::Uuid;
+
+const NOTE_COUNT_COLUMN: &str = "(SELECT count(n.id) FROM notes n WHERE n.notebook_id = notebooks.id AND n.deleted_at IS NULL) AS note_count";
+
+// UNION instead of UNION ALL keeps the walk finite even if a cycle ever slipped in
+const SUBTREE_CTE: &str = "WITH RECURSIVE subtree AS ( \
+        SELECT id FROM notebooks WHERE id = $1 \
+        UNION \
+        SELECT nb.id FROM notebooks nb JOIN subtree s ON nb.parent_id = s.id WHERE nb.deleted_at IS NULL \
+    )";
+
+pub async fn save_notebook(pool: &PgPool, body: NotebookSaveModel) -> Result<NotebookModel, Error> {
+    let query = "INSERT INTO notebooks (name,parent_id,created_by) VALUES ($1, $2, $3) RETURNING *";
+    sqlx::query_as::<_, NotebookModel>(query)
+        .bind(body.name)
+        .bind(body.parent_id)
+        .bind(body.created_by)
+        .fetch_one(pool)
+        .await
+}
+
+pub async fn get_notebooks_user(pool: &PgPool, user_id: Uuid) -> Result<Vec<NotebookModel>, Error> {
+    let query = format!(
+        "SELECT notebooks.*, {} FROM notebooks WHERE deleted_at IS NULL AND created_by = $1 ORDER BY name, created_at",
+        NOTE_COUNT_COLUMN
+    );
+    sqlx::query_as::<_, NotebookModel>(&query)
+        .bind(user_id)
+        .fetch_all(pool)
+        .await
+}
+
+pub async fn get_notebook_user_by_id(
+    pool: &PgPool,
+    notebook_id: Uuid,
+    user_id: Uuid,
+) -> Result<NotebookModel, Error> {
+    let query = format!(
+        "SELECT notebooks.*, {} FROM notebooks WHERE deleted_at IS NULL AND id = $1 AND created_by = $2",
+        NOTE_COUNT_COLUMN
+    );
+    sqlx::query_as::<_, NotebookModel>(&query)
+        .bind(notebook_id)
+        .bind(user_id)
+        .fetch_one(pool)
+        .await
+}
+
+// the notebook itself followed by every notebook nested below it
+pub async fn get_notebook_subtree_ids(
+    pool: &PgPool,
+    notebook_id: Uuid,
+) -> Result<Vec<Uuid>, Error> {
+    let query = format!("{} SELECT id FROM subtree", SUBTREE_CTE);
+    sqlx::query_scalar(&query)
+        .bind(notebook_id)
+        .fetch_all(pool)
+        .await
+}
+
+pub async fn update_notebook_name(
+    pool: &PgPool,
+    notebook_id: Uuid,
+    name: String,
+    user_id: Uuid,
+) -> Result<NotebookModel, Error> {
+    let now = Utc::now();
+    let query = "UPDATE notebooks SET name = $1, updated_by = $2, updated_at = $3 WHERE id = $4 AND created_by = $2 RETURNING *";
+    sqlx::query_as::<_, NotebookModel>(query)
+        .bind(name)
+        .bind(user_id)
+        .bind(now)
+        .bind(notebook_id)
+        .fetch_one(pool)
+        .await
+}
+
+// the cycle check and the update run as one statement, so a concurrent move can not
+// sneak a cycle in between them. no row comes back when the new parent is the notebook
+// itself or one of its descendants
+pub async fn move_notebook(
+    pool: &PgPool,
+    notebook_id: Uuid,
+    parent_id: Option<Uuid>,
+    user_id: Uuid,
+) -> Result<Option<NotebookModel>, Error> {
+    let now = Utc::now();
+    let query = format!(
+        "{} , moved AS ( \
+            UPDATE notebooks SET parent_id = $2, updated_by = $3, updated_at = $4 \
+            WHERE id = $1 AND created_by = $3 \
+            AND ($2::uuid IS NULL OR $2::uuid NOT IN (SELECT id FROM subtree)) \
+            RETURNING * \
+        ) \
+        SELECT * FROM moved",
+        SUBTREE_CTE
+    );
+    sqlx::query_as::<_, NotebookModel>(&query)
+        .bind(notebook_id)
+        .bind(parent_id)
+        .bind(user_id)
+        .bind(now)
+        .fetch_optional(pool)
+        .await
+}
+
+// move-to-root: the notes and child notebooks are kept and moved up to the top level
+pub async fn delete_notebook_to_root(
+    pool: &PgPool,
+    notebook_id: Uuid,
+    user_id: Uuid,
+) -> Result<i32, Error> {
+    let now: DateTime<Utc> = Utc::now();
+    let mut tx = pool.begin().await?;
+    sqlx::query::<_>(
+        "UPDATE notes SET version = version + 1, notebook_id = NULL WHERE notebook_id = $1",
+    )
+    .bind(notebook_id)
+    .execute(&mut tx)
+    .await?;
+    sqlx::query::<_>(
+        "UPDATE notebooks SET parent_id = NULL WHERE parent_id = $1 AND deleted_at IS NULL",
+    )
+    .bind(notebook_id)
+    .execute(&mut tx)
+    .await?;
+    let rows_affected: PgQueryResult = sqlx::query::<_>(
+        "UPDATE notebooks SET deleted_at = $1 WHERE id = $2 AND created_by = $3 AND deleted_at IS NULL",
+    )
+    .bind(now)
+    .bind(notebook_id)
+    .bind(user_id)
+    .execute(&mut tx)
+    .await?;
+    tx.commit().await?;
+
+    Ok(rows_affected.rows_affected() as i32)
+}
+
+// cascade-to-trash: the whole subtree is soft deleted together with its notes, all of it
+// sharing one deleted_at like a single note delete does
+pub async fn delete_notebook_to_trash(
+    pool: &PgPool,
+    notebook_id: Uuid,
+    user_id: Uuid,
+) -> Result<i32, Error> {
+    let now: DateTime<Utc> = Utc::now();
+    let mut tx = pool.begin().await?;
+    let subtree_query = format!("{} SELECT id FROM subtree", SUBTREE_CTE);
+    let notebook_ids: Vec<Uuid> = sqlx::query_scalar(&subtree_query)
+        .bind(notebook_id)
+        .fetch_all(&mut tx)
+        .await?;
+    let note_ids: Vec<Uuid> = sqlx::query_scalar(
+        "UPDATE notes SET version = version + 1, deleted_at = $1 WHERE notebook_id = ANY($2) AND created_by = $3 AND deleted_at IS NULL RETURNING id",
+    )
+    .bind(now)
+    .bind(&notebook_ids)
+    .bind(user_id)
+    .fetch_all(&mut tx)
+    .await?;
+    sqlx::query::<_>(
+        "UPDATE attachments SET deleted_at = $1 WHERE note_id = ANY($2) AND deleted_at IS NULL",
+    )
+    .bind(now)
+    .bind(&note_ids)
+    .execute(&mut tx)
+    .await?;
+    let rows_affected: PgQueryResult = sqlx::query::<_>(
+        "UPDATE notebooks SET deleted_at = $1 WHERE id = ANY($2) AND created_by = $3 AND deleted_at IS NULL",
+    )
+    .bind(now)
+    .bind(&notebook_ids)
+    .bind(user_id)
+    .execute(&mut tx)
+    .await?;
+    tx.commit().await?;
+
+    Ok(rows_affected.rows_affected() as i32)
+}
@@ -0,0 +1,14 @@
+use crate::modules::notebooks::handler;
+use actix_web::web;
+
+pub fn routes(conf: &mut web::ServiceConfig) {
+    let scope = web::scope("/notebooks")
+        .service(handler::notebook_list_handler)
+        .service(handler::create_notebook_handler)
+        .service(handler::get_notebook_handler)
+        .service(handler::edit_notebook_handler)
+        .service(handler::move_notebook_handler)
+        .service(handler::delete_notebook_handler);
+
+    conf.service(scope);
+}
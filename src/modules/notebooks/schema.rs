@@ -0,0 +1,43 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator_derive::Validate;
+
+#[derive(Deserialize, Serialize, Debug, Validate)]
+pub struct CreateNotebookSchema {
+    #[validate(length(min = 1, max = 255))]
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parent_id: Option<Uuid>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Validate)]
+pub struct UpdateNotebookSchema {
+    #[validate(length(min = 1, max = 255))]
+    pub name: String,
+}
+
+// a missing or null parent_id moves the notebook to the root
+#[derive(Deserialize, Serialize, Debug)]
+pub struct MoveNotebookSchema {
+    pub parent_id: Option<Uuid>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct DeleteNotebookOptions {
+    pub mode: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[allow(non_snake_case)]
+pub struct NotebookResponse {
+    pub id: Uuid,
+    pub name: String,
+    pub parent_id: Option<Uuid>,
+    #[serde(rename = "noteCount")]
+    pub note_count: i64,
+    #[serde(rename = "createdAt")]
+    pub created_at: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(rename = "updatedAt")]
+    pub updated_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub children: Vec<NotebookResponse>,
+}
@@ -0,0 +1,227 @@
+use crate::modules::notebooks::constants;
+use crate::modules::notebooks::model::{NotebookModel, NotebookSaveModel};
+use crate::modules::notebooks::repository;
+use crate::modules::notebooks::schema::{
+    CreateNotebookSchema, DeleteNotebookOptions, MoveNotebookSchema, NotebookResponse,
+    UpdateNotebookSchema,
+};
+use sqlx::{Error, PgPool};
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+enum DeleteMode {
+    Trash,
+    Root,
+}
+
+// deleting keeps the content by default, trashing a whole subtree has to be asked for
+fn parse_delete_mode(options: &DeleteNotebookOptions) -> Result<DeleteMode, String> {
+    match options.mode.as_deref() {
+        None | Some("") | Some("root") => Ok(DeleteMode::Root),
+        Some("trash") => Ok(DeleteMode::Trash),
+        Some(_) => Err(constants::PARAMETER_DELETE_MODE_INVALID.to_string()),
+    }
+}
+
+fn to_notebook_response(
+    notebook: NotebookModel,
+    children_by_parent: &mut HashMap<Uuid, Vec<NotebookModel>>,
+) -> NotebookResponse {
+    let children = children_by_parent
+        .remove(&notebook.id)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|child| to_notebook_response(child, children_by_parent))
+        .collect();
+
+    NotebookResponse {
+        id: notebook.id,
+        name: notebook.name,
+        parent_id: notebook.parent_id,
+        note_count: notebook.note_count.unwrap_or_default(),
+        created_at: notebook.created_at,
+        updated_at: notebook.updated_at,
+        children,
+    }
+}
+
+fn build_notebook_tree(notebooks: Vec<NotebookModel>) -> Vec<NotebookResponse> {
+    let ids: HashSet<Uuid> = notebooks.iter().map(|notebook| notebook.id).collect();
+    let mut roots: Vec<NotebookModel> = Vec::new();
+    let mut children_by_parent: HashMap<Uuid, Vec<NotebookModel>> = HashMap::new();
+    for notebook in notebooks {
+        match notebook
+            .parent_id
+            .filter(|parent_id| ids.contains(parent_id))
+        {
+            Some(parent_id) => children_by_parent
+                .entry(parent_id)
+                .or_default()
+                .push(notebook),
+            None => roots.push(notebook),
+        }
+    }
+
+    roots
+        .into_iter()
+        .map(|notebook| to_notebook_response(notebook, &mut children_by_parent))
+        .collect()
+}
+
+async fn get_owned_notebook(
+    pool: &PgPool,
+    notebook_id: Uuid,
+    user_id: Uuid,
+    not_found_message: &str,
+) -> Result<NotebookModel, String> {
+    match repository::get_notebook_user_by_id(pool, notebook_id, user_id).await {
+        Ok(notebook) => Ok(notebook),
+        Err(err) => match err {
+            Error::RowNotFound => Err(not_found_message.to_string()),
+            _ => {
+                eprintln!("error get notebook {:?}", err);
+                Err(constants::NOTEBOOK_CANT_BE_FETCHED.to_string())
+            }
+        },
+    }
+}
+
+pub async fn get_notebooks_service(
+    pool: &PgPool,
+    user_id: Uuid,
+) -> Result<Vec<NotebookResponse>, String> {
+    match repository::get_notebooks_user(pool, user_id).await {
+        Ok(notebooks) => Ok(build_notebook_tree(notebooks)),
+        Err(err) => {
+            eprintln!("error get notebooks {:?}", err);
+            Err(constants::NOTEBOOK_CANT_BE_FETCHED.to_string())
+        }
+    }
+}
+
+pub async fn get_notebook_service(
+    pool: &PgPool,
+    notebook_id: Uuid,
+    user_id: Uuid,
+) -> Result<NotebookModel, String> {
+    get_owned_notebook(pool, notebook_id, user_id, constants::NOTEBOOK_NOT_FOUND).await
+}
+
+// the ids a notes listing has to match, optionally including every nested notebook
+pub async fn get_notebook_ids_service(
+    pool: &PgPool,
+    notebook_id: Uuid,
+    recursive: bool,
+    user_id: Uuid,
+) -> Result<Vec<Uuid>, String> {
+    get_notebook_service(pool, notebook_id, user_id).await?;
+    if !recursive {
+        return Ok(vec![notebook_id]);
+    }
+
+    match repository::get_notebook_subtree_ids(pool, notebook_id).await {
+        Ok(notebook_ids) => Ok(notebook_ids),
+        Err(err) => {
+            eprintln!("error get notebook subtree {:?}", err);
+            Err(constants::NOTEBOOK_CANT_BE_FETCHED.to_string())
+        }
+    }
+}
+
+pub async fn save_notebook_service(
+    pool: &PgPool,
+    body: &CreateNotebookSchema,
+    user_id: Uuid,
+) -> Result<NotebookModel, String> {
+    if let Some(parent_id) = body.parent_id {
+        get_owned_notebook(
+            pool,
+            parent_id,
+            user_id,
+            constants::PARENT_NOTEBOOK_NOT_FOUND,
+        )
+        .await?;
+    }
+
+    let notebook_save_model = NotebookSaveModel {
+        name: body.name.to_string(),
+        parent_id: body.parent_id,
+        created_by: user_id,
+    };
+
+    match repository::save_notebook(pool, notebook_save_model).await {
+        Ok(notebook) => Ok(notebook),
+        Err(err) => {
+            eprintln!("Error saving notebook: {:?}", err);
+            Err(constants::NOTEBOOK_CANT_BE_SAVED.to_string())
+        }
+    }
+}
+
+pub async fn update_notebook_service(
+    pool: &PgPool,
+    notebook_id: Uuid,
+    body: &UpdateNotebookSchema,
+    user_id: Uuid,
+) -> Result<NotebookModel, String> {
+    get_notebook_service(pool, notebook_id, user_id).await?;
+
+    match repository::update_notebook_name(pool, notebook_id, body.name.to_string(), user_id).await
+    {
+        Ok(notebook) => Ok(notebook),
+        Err(err) => {
+            eprintln!("Error update notebook: {:?}", err);
+            Err(constants::NOTEBOOK_CANT_BE_PATCHED.to_string())
+        }
+    }
+}
+
+pub async fn move_notebook_service(
+    pool: &PgPool,
+    notebook_id: Uuid,
+    body: &MoveNotebookSchema,
+    user_id: Uuid,
+) -> Result<NotebookModel, String> {
+    get_notebook_service(pool, notebook_id, user_id).await?;
+    if let Some(parent_id) = body.parent_id {
+        get_owned_notebook(
+            pool,
+            parent_id,
+            user_id,
+            constants::PARENT_NOTEBOOK_NOT_FOUND,
+        )
+        .await?;
+    }
+
+    match repository::move_notebook(pool, notebook_id, body.parent_id, user_id).await {
+        Ok(Some(notebook)) => Ok(notebook),
+        Ok(None) => Err(constants::NOTEBOOK_MOVE_CYCLE.to_string()),
+        Err(err) => {
+            eprintln!("Error move notebook: {:?}", err);
+            Err(constants::NOTEBOOK_CANT_BE_MOVED.to_string())
+        }
+    }
+}
+
+pub async fn delete_notebook_service(
+    pool: &PgPool,
+    notebook_id: Uuid,
+    options: &DeleteNotebookOptions,
+    user_id: Uuid,
+) -> Result<i32, String> {
+    let mode = parse_delete_mode(options)?;
+    get_notebook_service(pool, notebook_id, user_id).await?;
+
+    let result = match mode {
+        DeleteMode::Root => repository::delete_notebook_to_root(pool, notebook_id, user_id).await,
+        DeleteMode::Trash => repository::delete_notebook_to_trash(pool, notebook_id, user_id).await,
+    };
+
+    match result {
+        Ok(rows) => Ok(rows),
+        Err(err) => {
+            eprintln!("Error delete notebook: {:?}", err);
+            Err(constants::NOTEBOOK_CANT_BE_DELETE.to_string())
+        }
+    }
+}
@@ -1,13 +1,78 @@
 pub const PARAMETER_NOTE_ID_INVALID: &str = "parameter id is invalid";
+pub const PARAMETER_SHARE_LINK_ID_INVALID: &str = "parameter link_id is invalid";
 pub const NOTE_NOT_FOUND: &str = "note not found";
 pub const NOTE_FOUND: &str = "success get data note";
 pub const NOTE_SUCCESS_SAVED: &str = "success saved data note";
 pub const NOTE_SUCCESS_PATCHED: &str = "success patched data note";
+pub const NOTE_SUCCESS_MOVED: &str = "success moved data note";
+pub const NOTE_SUCCESS_PINNED: &str = "success pinned data note";
+pub const NOTE_SUCCESS_UNPINNED: &str = "success unpinned data note";
+pub const NOTE_SUCCESS_ARCHIVED: &str = "success archived data note";
+pub const NOTE_SUCCESS_UNARCHIVED: &str = "success unarchived data note";
+pub const NOTE_SUCCESS_FAVORITED: &str = "success favorited data note";
+pub const NOTE_SUCCESS_UNFAVORITED: &str = "success unfavorited data note";
+pub const NOTE_SUCCESS_DUPLICATED: &str = "success duplicated data note";
+pub const NOTE_SUCCESS_BULK_APPLIED: &str = "success applied bulk action to data note";
 pub const DETAIL_NOTE_CANT_BE_FETCHED: &str = "something went wrong while get detail note";
 pub const NOTE_CANT_BE_FETCHED: &str = "something went wrong while get note";
 pub const EXISTING_NOTE_CANT_BE_FETCHED: &str = "something went wrong while get existing note";
 pub const NOTE_CANT_BE_SAVED: &str = "something went wrong while saving the note";
 pub const NOTE_CANT_BE_PATCHED: &str = "something went wrong while updating the note";
+pub const NOTE_CANT_BE_DUPLICATED: &str = "something went wrong while duplicating the note";
+pub const NOTE_CANT_BE_MOVED: &str = "something went wrong while moving the note";
+pub const NOTE_FAVORITE_CANT_BE_SAVED: &str = "something went wrong while saving the note favorite";
 pub const NOTE_CANT_BE_DELETE: &str = "something went wrong while delete the note";
+pub const NOTE_BULK_CANT_BE_APPLIED: &str = "something went wrong while applying the bulk action";
+pub const NOTE_BULK_ACTION_INVALID: &str =
+    "bulk action is invalid, set_category needs a category and add_tags or remove_tags need 1 to 20 tags of at most 50 characters";
+pub const NOTE_SUCCESS_IMPORTED: &str = "success imported data note";
+pub const NOTE_CANT_BE_EXPORTED: &str = "something went wrong while exporting the notes";
+pub const NOTE_CONTENT_UNREADABLE: &str =
+    "note content can not be read with the configured encryption keys";
+pub const NOTE_ARCHIVE_REQUIRED: &str = "archive file is required in the `file` field";
+pub const NOTE_ARCHIVE_TOO_LARGE: &str = "archive file is too large";
+pub const NOTE_ARCHIVE_INVALID: &str =
+    "archive is invalid, upload a zip of markdown files with front matter or a json export";
+pub const NOTE_ARCHIVE_TOO_MANY_FILES: &str = "archive has too many files";
+pub const NOTE_ARCHIVE_FILE_TOO_LARGE: &str = "archive file entry is too large";
+pub const NOTE_ARCHIVE_FILE_INVALID: &str =
+    "archive file entry is invalid, it needs a title of at most 255 characters, a category of at most 100 characters and content";
+pub const NOTE_SCHEDULE_INVALID: &str = "unpublish_at has to be later than publish_at";
+pub const NOTE_PATCH_INVALID: &str = "title and content cannot be set to null";
+pub const NOTE_VERSION_MISMATCH: &str =
+    "note has been changed by someone else, fetch it again and retry with the new ETag";
+pub const NOTE_IN_TRASH: &str = "note is in the trash";
+pub const NOTE_NOT_IN_TRASH: &str = "note is not in the trash";
 pub const NOTE_SUCCESS_DELETED: &str = "success deleted data note";
 pub const NOTE_TITLE_ALREADY_EXIST: &str = "note with the title already exists";
+pub const PUBLIC_NOTE_CANT_BE_FETCHED: &str = "something went wrong while get public note";
+pub const SHARE_LINK_NOT_FOUND: &str = "share link not found";
+pub const SHARE_LINK_EXPIRED: &str = "share link has expired";
+pub const SHARE_LINK_FOUND: &str = "success get data share link";
+pub const SHARE_LINK_SUCCESS_SAVED: &str = "success saved data share link";
+pub const SHARE_LINK_SUCCESS_DELETED: &str = "success deleted data share link";
+pub const SHARE_LINK_CANT_BE_FETCHED: &str = "something went wrong while get share link";
+pub const SHARE_LINK_CANT_BE_SAVED: &str = "something went wrong while saving the share link";
+pub const SHARE_LINK_CANT_BE_DELETE: &str = "something went wrong while delete the share link";
+pub const PARAMETER_SORT_INVALID: &str =
+    "parameter order_by is invalid, use created_at, updated_at, title, category or published with an optional :asc or :desc";
+pub const PARAMETER_CURSOR_INVALID: &str = "parameter after or before is not a valid cursor";
+pub const PARAMETER_FORMAT_INVALID: &str = "parameter format is invalid, use html or markdown";
+pub const NOTE_SUCCESS_RENDERED: &str = "success rendered note content";
+pub const NOTE_LINKS_FOUND: &str = "success get data note links";
+pub const NOTE_LINKS_CANT_BE_FETCHED: &str = "something went wrong while get note links";
+pub const NOTE_COLLAB_CANT_BE_JOINED: &str =
+    "something went wrong while joining the collaborative session";
+pub const NOTE_EVENTS_CANT_BE_FETCHED: &str = "something went wrong while get note events";
+pub const PARAMETER_LAST_EVENT_ID_INVALID: &str = "header Last-Event-ID is not a valid event id";
+pub const NOTE_STATS_FOUND: &str = "success get data note stats";
+pub const NOTE_STATS_CANT_BE_FETCHED: &str = "something went wrong while get note stats";
+pub const PARAMETER_STATS_RANGE_INVALID: &str =
+    "parameter from and to are invalid, from can not be after to and the range can span at most 366 days";
+pub const PARAMETER_LONGEST_INVALID: &str = "parameter longest is invalid, use 1 to 50";
+pub const PARAMETER_CONTENT_SEARCH_UNAVAILABLE: &str =
+    "parameter content can not be used, note content is stored encrypted";
+pub const NOTE_SLUG_CANT_BE_SAVED: &str = "something went wrong while saving the note slug";
+pub const NOTE_ENCRYPTION_NOT_CONFIGURED: &str =
+    "no encryption keys are configured, set ENCRYPTION_KEYS or ENCRYPTION_KEY_FILE";
+pub const NOTES_CANT_BE_REENCRYPTED: &str = "something went wrong while re-encrypting the notes";
@@ -1,15 +1,32 @@
-use crate::infrastructure::http_lib::{Pagination, Response};
-use crate::infrastructure::pagination::PaginationQuery;
+use crate::infrastructure::conditional;
+use crate::infrastructure::feed::FeedFormat;
+use crate::infrastructure::html;
+use crate::infrastructure::http_lib::{CursorPagination, Pagination, Response};
+use crate::infrastructure::pagination::{self, PaginationQuery};
+use crate::modules::categories::constants as category_constants;
+use crate::modules::notebooks::constants as notebook_constants;
 use crate::modules::notes::constants;
+use crate::modules::users::constants as user_constants;
 use crate::utils::utils;
 use crate::{
-    modules::notes::model::NoteModel,
-    modules::notes::schema::{CreateNoteSchema, FilterOptions, UpdateNoteSchema},
+    modules::notes::model::{NoteModel, NoteShareLinkModel, NoteStatsModel},
+    modules::notes::schema::{
+        BulkNoteResponse, BulkNoteSchema, CreateNoteSchema, CreateShareLinkSchema, ExportOptions,
+        FilterOptions, FormatOptions, ImportOptions, MoveNoteSchema, NoteGraphResponse,
+        NoteImportResult, PatchNoteSchema, PublicNoteResponse, RenderNoteSchema,
+        RenderedNoteResponse, StatsOptions, UpdateNoteSchema,
+    },
     modules::notes::service,
     AppState,
 };
+use actix_multipart::Multipart;
+use actix_web::http::header::{
+    self, ContentDisposition, ContentType, DispositionParam, DispositionType,
+};
 use actix_web::http::StatusCode;
-use actix_web::{delete, get, post, put, web, HttpRequest, HttpResponse, Responder};
+use actix_web::{delete, get, patch, post, put, web, HttpRequest, HttpResponse, Responder};
+use futures::StreamExt;
+use sha2::{Digest, Sha256};
 use validator::Validate;
 
 #[get("/health")]
@@ -25,6 +42,7 @@ pub async fn note_list_handler(
     data: web::Data<AppState>,
     req: HttpRequest,
     paginated: web::Query<PaginationQuery>,
+    format: web::Query<FormatOptions>,
 ) -> impl Responder {
     //get user_id from authorization token
     let user_id = utils::get_current_user_uuid_from_jwt(&data.cfg.clone(), req);
@@ -32,10 +50,66 @@ pub async fn note_list_handler(
         let resp: Response<(), ()> = Response::error(StatusCode::UNAUTHORIZED, err.as_str());
         return HttpResponse::Unauthorized().json(resp);
     }
+
+    let render_html = match service::wants_html(&format) {
+        Ok(render_html) => render_html,
+        Err(err) => {
+            let resp: Response<(), ()> = Response::error(StatusCode::BAD_REQUEST, err.as_str());
+            return HttpResponse::BadRequest().json(resp);
+        }
+    };
+    if pagination::wants_cursor(&paginated) {
+        let page = match service::get_notes_cursor_service(
+            &data.db,
+            &filter,
+            &paginated,
+            user_id.unwrap(),
+        )
+        .await
+        {
+            Ok(page) => page,
+            Err(err) => {
+                let status = if err.contains(notebook_constants::NOTEBOOK_NOT_FOUND)
+                    || err.contains(constants::PARAMETER_SORT_INVALID)
+                    || err.contains(constants::PARAMETER_CURSOR_INVALID)
+                    || err.contains(constants::PARAMETER_CONTENT_SEARCH_UNAVAILABLE)
+                {
+                    StatusCode::BAD_REQUEST
+                } else {
+                    StatusCode::INTERNAL_SERVER_ERROR
+                };
+                let resp: Response<(), ()> = Response::error(status, err.as_str());
+                return HttpResponse::build(status).json(resp);
+            }
+        };
+        let list_notes = if render_html {
+            service::render_notes_html(&data.render_cache, page.notes)
+        } else {
+            page.notes
+        };
+
+        let msg = constants::NOTE_FOUND;
+        let resp: CursorPagination<Vec<NoteModel>> = CursorPagination::success(
+            msg,
+            list_notes,
+            page.size,
+            page.next_cursor,
+            page.prev_cursor,
+        );
+        return HttpResponse::Ok().json(resp);
+    }
+
     //get list note
     let (notes, total_count) =
-        service::get_notes_service(&data.db, &filter.into_inner(), user_id.unwrap()).await;
+        service::get_notes_service(&data.db, &filter, &paginated, user_id.unwrap()).await;
     if let Err(err) = notes {
+        if err.contains(notebook_constants::NOTEBOOK_NOT_FOUND)
+            || err.contains(constants::PARAMETER_SORT_INVALID)
+            || err.contains(constants::PARAMETER_CONTENT_SEARCH_UNAVAILABLE)
+        {
+            let resp: Response<(), ()> = Response::error(StatusCode::BAD_REQUEST, err.as_str());
+            return HttpResponse::BadRequest().json(resp);
+        }
         let resp: Response<(), ()> =
             Response::error(StatusCode::INTERNAL_SERVER_ERROR, err.as_str());
         return HttpResponse::InternalServerError().json(resp);
@@ -50,6 +124,11 @@ pub async fn note_list_handler(
         HttpResponse::InternalServerError().json(resp);
         Vec::new() // Fallback value
     });
+    let list_notes = if render_html {
+        service::render_notes_html(&data.render_cache, list_notes)
+    } else {
+        list_notes
+    };
 
     let msg = constants::NOTE_FOUND;
     let pg: PaginationQuery = paginated.0;
@@ -90,10 +169,14 @@ pub async fn create_note_handler(
         Ok(note) => note,
         Err(err) => {
             return if err.contains(&constants::NOTE_TITLE_ALREADY_EXIST) {
-                let resp: Response<(), ()> = Response::error(
-                    StatusCode::BAD_REQUEST,
-                    &constants::NOTE_TITLE_ALREADY_EXIST,
-                );
+                let resp: Response<(), ()> =
+                    Response::error(StatusCode::CONFLICT, &constants::NOTE_TITLE_ALREADY_EXIST);
+                HttpResponse::Conflict().json(resp)
+            } else if err.contains(notebook_constants::NOTEBOOK_NOT_FOUND)
+                || err.contains(category_constants::CATEGORY_NOT_FOUND)
+                || err.contains(constants::NOTE_SCHEDULE_INVALID)
+            {
+                let resp: Response<(), ()> = Response::error(StatusCode::BAD_REQUEST, err.as_str());
                 HttpResponse::BadRequest().json(resp)
             } else {
                 let resp: Response<(), ()> =
@@ -108,21 +191,330 @@ pub async fn create_note_handler(
     return HttpResponse::Ok().json(resp);
 }
 
+#[post("/render")]
+pub async fn render_note_handler(
+    body: web::Json<RenderNoteSchema>,
+    data: web::Data<AppState>,
+    req: HttpRequest,
+) -> impl Responder {
+    //get user_id from authorization token
+    let user_id = utils::get_current_user_uuid_from_jwt(&data.cfg.clone(), req);
+    if let Err(err) = user_id {
+        let resp: Response<(), ()> = Response::error(StatusCode::UNAUTHORIZED, err.as_str());
+        return HttpResponse::Unauthorized().json(resp);
+    }
+
+    //validate the struct from body
+    if let Err(errors) = body.validate() {
+        let resp = Response::custom(
+            StatusCode::BAD_REQUEST,
+            StatusCode::BAD_REQUEST.as_str(),
+            (),
+            errors,
+        );
+        return HttpResponse::BadRequest().json(resp);
+    }
+
+    let rendered = service::render_preview_service(&body.0);
+    let msg = constants::NOTE_SUCCESS_RENDERED;
+    let resp: Response<RenderedNoteResponse, ()> = Response::success(StatusCode::OK, rendered, msg);
+    return HttpResponse::Ok().json(resp);
+}
+
+#[post("/bulk")]
+pub async fn bulk_note_handler(
+    body: web::Json<BulkNoteSchema>,
+    data: web::Data<AppState>,
+    req: HttpRequest,
+) -> impl Responder {
+    //get user_id from authorization token
+    let user_id = utils::get_current_user_uuid_from_jwt(&data.cfg.clone(), req);
+    if let Err(err) = user_id {
+        let resp: Response<(), ()> = Response::error(StatusCode::UNAUTHORIZED, err.as_str());
+        return HttpResponse::Unauthorized().json(resp);
+    }
+
+    //validate the struct from body
+    if let Err(errors) = body.validate() {
+        let resp = Response::custom(
+            StatusCode::BAD_REQUEST,
+            StatusCode::BAD_REQUEST.as_str(),
+            (),
+            errors,
+        );
+        return HttpResponse::BadRequest().json(resp);
+    }
+
+    let report = match service::bulk_note_service(&data.db, &body.0, user_id.unwrap()).await {
+        Ok(report) => report,
+        Err(err) => {
+            let status = if err.contains(constants::NOTE_BULK_ACTION_INVALID)
+                || err.contains(notebook_constants::NOTEBOOK_NOT_FOUND)
+                || err.contains(category_constants::CATEGORY_NOT_FOUND)
+            {
+                StatusCode::BAD_REQUEST
+            } else {
+                StatusCode::INTERNAL_SERVER_ERROR
+            };
+            let resp: Response<(), ()> = Response::error(status, err.as_str());
+            return HttpResponse::build(status).json(resp);
+        }
+    };
+
+    let msg = constants::NOTE_SUCCESS_BULK_APPLIED;
+    let resp: Response<BulkNoteResponse, ()> = Response::success(StatusCode::OK, report, msg);
+    HttpResponse::Ok().json(resp)
+}
+
+#[get("/export")]
+pub async fn export_notes_handler(
+    filter: web::Query<FilterOptions>,
+    paginated: web::Query<PaginationQuery>,
+    options: web::Query<ExportOptions>,
+    data: web::Data<AppState>,
+    req: HttpRequest,
+) -> impl Responder {
+    //get user_id from authorization token
+    let user_id = utils::get_current_user_uuid_from_jwt(&data.cfg.clone(), req);
+    if let Err(err) = user_id {
+        let resp: Response<(), ()> = Response::error(StatusCode::UNAUTHORIZED, err.as_str());
+        return HttpResponse::Unauthorized().json(resp);
+    }
+
+    let stream = match service::export_notes_service(
+        &data.db,
+        &filter,
+        &paginated,
+        options.format.unwrap_or_default(),
+        user_id.unwrap(),
+    )
+    .await
+    {
+        Ok(stream) => stream,
+        Err(err) => {
+            let status = if err.contains(notebook_constants::NOTEBOOK_NOT_FOUND)
+                || err.contains(constants::PARAMETER_SORT_INVALID)
+                || err.contains(constants::PARAMETER_CONTENT_SEARCH_UNAVAILABLE)
+            {
+                StatusCode::BAD_REQUEST
+            } else if err.contains(constants::NOTE_CONTENT_UNREADABLE) {
+                StatusCode::CONFLICT
+            } else {
+                StatusCode::INTERNAL_SERVER_ERROR
+            };
+            let resp: Response<(), ()> = Response::error(status, err.as_str());
+            return HttpResponse::build(status).json(resp);
+        }
+    };
+
+    HttpResponse::Ok()
+        .content_type("application/zip")
+        .insert_header(ContentDisposition {
+            disposition: DispositionType::Attachment,
+            parameters: vec![DispositionParam::Filename("notes.zip".to_string())],
+        })
+        .streaming(stream)
+}
+
+// reads the `file` field, stopping as soon as it grows past the limit
+async fn read_archive_upload(mut payload: Multipart, max_size: usize) -> Result<Vec<u8>, String> {
+    while let Some(item) = payload.next().await {
+        let mut field = item.map_err(|err| err.to_string())?;
+        if field.content_disposition().get_name() != Some("file") {
+            continue;
+        }
+
+        let mut bytes: Vec<u8> = Vec::new();
+        while let Some(chunk) = field.next().await {
+            let chunk = chunk.map_err(|err| err.to_string())?;
+            if bytes.len() + chunk.len() > max_size {
+                return Err(constants::NOTE_ARCHIVE_TOO_LARGE.to_string());
+            }
+            bytes.extend_from_slice(&chunk);
+        }
+        return Ok(bytes);
+    }
+
+    Err(constants::NOTE_ARCHIVE_REQUIRED.to_string())
+}
+
+#[post("/import")]
+pub async fn import_notes_handler(
+    payload: Multipart,
+    options: web::Query<ImportOptions>,
+    data: web::Data<AppState>,
+    req: HttpRequest,
+) -> impl Responder {
+    //get user_id from authorization token
+    let user_id = utils::get_current_user_uuid_from_jwt(&data.cfg.clone(), req);
+    if let Err(err) = user_id {
+        let resp: Response<(), ()> = Response::error(StatusCode::UNAUTHORIZED, err.as_str());
+        return HttpResponse::Unauthorized().json(resp);
+    }
+
+    let result = match read_archive_upload(payload, service::IMPORT_MAX_SIZE).await {
+        Ok(bytes) => {
+            service::import_notes_service(
+                &data.db,
+                &bytes,
+                options.on_conflict.unwrap_or_default(),
+                user_id.unwrap(),
+            )
+            .await
+        }
+        Err(err) => Err(err),
+    };
+    let results = match result {
+        Ok(results) => results,
+        Err(err) => {
+            let status = if err.contains(constants::NOTE_ARCHIVE_TOO_LARGE) {
+                StatusCode::PAYLOAD_TOO_LARGE
+            } else if err.contains(constants::NOTE_ARCHIVE_REQUIRED)
+                || err.contains(constants::NOTE_ARCHIVE_INVALID)
+                || err.contains(constants::NOTE_ARCHIVE_TOO_MANY_FILES)
+            {
+                StatusCode::BAD_REQUEST
+            } else {
+                eprintln!("error import notes: {:?}", err);
+                StatusCode::INTERNAL_SERVER_ERROR
+            };
+            let resp: Response<(), ()> = Response::error(status, err.as_str());
+            return HttpResponse::build(status).json(resp);
+        }
+    };
+
+    let msg = constants::NOTE_SUCCESS_IMPORTED;
+    let resp: Response<Vec<NoteImportResult>, ()> = Response::success(StatusCode::OK, results, msg);
+    HttpResponse::Ok().json(resp)
+}
+
+#[get("/events")]
+pub async fn note_events_handler(data: web::Data<AppState>, req: HttpRequest) -> impl Responder {
+    // sent back by EventSource when it reconnects
+    let last_event_id = match req.headers().get("Last-Event-ID") {
+        None => None,
+        Some(value) => match value
+            .to_str()
+            .ok()
+            .and_then(|id| id.trim().parse::<i64>().ok())
+        {
+            Some(id) => Some(id),
+            None => {
+                let resp: Response<(), ()> = Response::error(
+                    StatusCode::BAD_REQUEST,
+                    constants::PARAMETER_LAST_EVENT_ID_INVALID,
+                );
+                return HttpResponse::BadRequest().json(resp);
+            }
+        },
+    };
+
+    //get user_id from authorization token
+    let user_id = utils::get_current_user_uuid_from_jwt(&data.cfg.clone(), req);
+    if let Err(err) = user_id {
+        let resp: Response<(), ()> = Response::error(StatusCode::UNAUTHORIZED, err.as_str());
+        return HttpResponse::Unauthorized().json(resp);
+    }
+
+    match service::note_events_service(&data.db, &data.note_events, last_event_id, user_id.unwrap())
+        .await
+    {
+        Ok(events) => HttpResponse::Ok()
+            .content_type("text/event-stream")
+            .insert_header((header::CACHE_CONTROL, "no-cache"))
+            .insert_header(("X-Accel-Buffering", "no"))
+            .streaming(events),
+        Err(err) => {
+            let resp: Response<(), ()> =
+                Response::error(StatusCode::INTERNAL_SERVER_ERROR, err.as_str());
+            HttpResponse::InternalServerError().json(resp)
+        }
+    }
+}
+
+#[get("/graph")]
+pub async fn note_graph_handler(data: web::Data<AppState>, req: HttpRequest) -> impl Responder {
+    //get user_id from authorization token
+    let user_id = utils::get_current_user_uuid_from_jwt(&data.cfg.clone(), req);
+    if let Err(err) = user_id {
+        let resp: Response<(), ()> = Response::error(StatusCode::UNAUTHORIZED, err.as_str());
+        return HttpResponse::Unauthorized().json(resp);
+    }
+
+    match service::get_note_graph_service(&data.db, user_id.unwrap()).await {
+        Ok(graph) => {
+            let resp: Response<NoteGraphResponse, ()> =
+                Response::success(StatusCode::OK, graph, constants::NOTE_LINKS_FOUND);
+            HttpResponse::Ok().json(resp)
+        }
+        Err(err) => {
+            let resp: Response<(), ()> =
+                Response::error(StatusCode::INTERNAL_SERVER_ERROR, err.as_str());
+            HttpResponse::InternalServerError().json(resp)
+        }
+    }
+}
+
+#[get("/stats")]
+pub async fn note_stats_handler(
+    filter: web::Query<FilterOptions>,
+    options: web::Query<StatsOptions>,
+    data: web::Data<AppState>,
+    req: HttpRequest,
+) -> impl Responder {
+    //get user_id from authorization token
+    let user_id = utils::get_current_user_uuid_from_jwt(&data.cfg.clone(), req);
+    if let Err(err) = user_id {
+        let resp: Response<(), ()> = Response::error(StatusCode::UNAUTHORIZED, err.as_str());
+        return HttpResponse::Unauthorized().json(resp);
+    }
+
+    match service::get_note_stats_service(&data.db, &filter, &options, user_id.unwrap()).await {
+        Ok(stats) => {
+            let resp: Response<NoteStatsModel, ()> =
+                Response::success(StatusCode::OK, stats, constants::NOTE_STATS_FOUND);
+            HttpResponse::Ok().json(resp)
+        }
+        Err(err) => {
+            let status = if err.contains(notebook_constants::NOTEBOOK_NOT_FOUND)
+                || err.contains(constants::PARAMETER_STATS_RANGE_INVALID)
+                || err.contains(constants::PARAMETER_LONGEST_INVALID)
+                || err.contains(constants::PARAMETER_CONTENT_SEARCH_UNAVAILABLE)
+            {
+                StatusCode::BAD_REQUEST
+            } else {
+                StatusCode::INTERNAL_SERVER_ERROR
+            };
+            let resp: Response<(), ()> = Response::error(status, err.as_str());
+            HttpResponse::build(status).json(resp)
+        }
+    }
+}
+
 #[get("/{id}")]
 pub async fn get_note_handler(
     path: web::Path<String>,
     data: web::Data<AppState>,
     req: HttpRequest,
+    format: web::Query<FormatOptions>,
 ) -> impl Responder {
     let note_id_str = path.into_inner();
 
     //get user_id from authorization token
-    let user_id = utils::get_current_user_uuid_from_jwt(&data.cfg.clone(), req);
+    let user_id = utils::get_current_user_uuid_from_jwt(&data.cfg.clone(), req.clone());
     if let Err(err) = user_id {
         let resp: Response<(), ()> = Response::error(StatusCode::UNAUTHORIZED, err.as_str());
         return HttpResponse::Unauthorized().json(resp);
     }
 
+    let render_html = match service::wants_html(&format) {
+        Ok(render_html) => render_html,
+        Err(err) => {
+            let resp: Response<(), ()> = Response::error(StatusCode::BAD_REQUEST, err.as_str());
+            return HttpResponse::BadRequest().json(resp);
+        }
+    };
+
     // Attempt to parse the UUID from the path
     let note_id = match uuid::Uuid::parse_str(&note_id_str) {
         Ok(uuid) => uuid,
@@ -134,27 +526,45 @@ pub async fn get_note_handler(
         }
     };
 
-    let note_detail: NoteModel =
-        match service::get_note_detail_service(&data.db, note_id, user_id.unwrap()).await {
-            Ok(note) => note,
-            Err(err) => {
-                return if err.contains(constants::NOTE_NOT_FOUND) {
-                    let resp: Response<(), ()> =
-                        Response::error(StatusCode::NOT_FOUND, constants::NOTE_NOT_FOUND);
-                    HttpResponse::NotFound().json(resp)
-                } else {
-                    let resp: Response<(), ()> = Response::error(
-                        StatusCode::INTERNAL_SERVER_ERROR,
-                        constants::DETAIL_NOTE_CANT_BE_FETCHED,
-                    );
-                    return HttpResponse::InternalServerError().json(resp);
-                }
+    let note_detail: NoteModel = match service::get_note_detail_with_links_service(
+        &data.db,
+        note_id,
+        user_id.unwrap(),
+    )
+    .await
+    {
+        Ok(note) => note,
+        Err(err) => {
+            return if err.contains(constants::NOTE_NOT_FOUND) {
+                let resp: Response<(), ()> =
+                    Response::error(StatusCode::NOT_FOUND, constants::NOTE_NOT_FOUND);
+                HttpResponse::NotFound().json(resp)
+            } else {
+                let resp: Response<(), ()> = Response::error(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    constants::DETAIL_NOTE_CANT_BE_FETCHED,
+                );
+                return HttpResponse::InternalServerError().json(resp);
             }
-        };
+        }
+    };
+    let etag = service::note_etag(&note_detail, render_html);
+    if conditional::none_match(&req, &etag) {
+        return HttpResponse::NotModified()
+            .insert_header((header::ETAG, etag))
+            .finish();
+    }
+    let note_detail = if render_html {
+        service::render_note_html(&data.render_cache, note_detail)
+    } else {
+        note_detail
+    };
 
     let resp: Response<NoteModel, ()> =
         Response::success(StatusCode::OK, note_detail, constants::NOTE_FOUND);
-    return HttpResponse::Ok().json(resp);
+    return HttpResponse::Ok()
+        .insert_header((header::ETAG, etag))
+        .json(resp);
 }
 
 #[put("/{id}")]
@@ -165,6 +575,7 @@ pub async fn edit_note_handler(
     req: HttpRequest,
 ) -> impl Responder {
     let note_id_str = path.into_inner();
+    let if_match = conditional::if_match(&req).map(|tags| service::etag_versions(&tags));
 
     //get user_id from authorization token
     let user_id = utils::get_current_user_uuid_from_jwt(&data.cfg.clone(), req);
@@ -197,16 +608,32 @@ pub async fn edit_note_handler(
 
     let req: &UpdateNoteSchema = &body.0;
     //update the note
-    let note_updated: Result<NoteModel, String> =
-        service::update_note_service(&data.db, note_id, req, user_id.unwrap()).await;
+    let note_updated: Result<NoteModel, String> = service::update_note_service(
+        &data.db,
+        note_id,
+        req,
+        if_match.as_deref(),
+        user_id.unwrap(),
+    )
+    .await;
     let note = match note_updated {
         Ok(note) => note,
         Err(err) => {
             return if err.contains(&constants::NOTE_TITLE_ALREADY_EXIST) {
-                let resp: Response<(), ()> = Response::error(
-                    StatusCode::BAD_REQUEST,
-                    &constants::NOTE_TITLE_ALREADY_EXIST,
-                );
+                let resp: Response<(), ()> =
+                    Response::error(StatusCode::CONFLICT, &constants::NOTE_TITLE_ALREADY_EXIST);
+                HttpResponse::Conflict().json(resp)
+            } else if err.contains(constants::NOTE_VERSION_MISMATCH) {
+                let resp: Response<(), ()> =
+                    Response::error(StatusCode::PRECONDITION_FAILED, err.as_str());
+                HttpResponse::PreconditionFailed().json(resp)
+            } else if err.contains(constants::NOTE_CONTENT_UNREADABLE) {
+                let resp: Response<(), ()> = Response::error(StatusCode::CONFLICT, err.as_str());
+                HttpResponse::Conflict().json(resp)
+            } else if err.contains(constants::NOTE_SCHEDULE_INVALID)
+                || err.contains(category_constants::CATEGORY_NOT_FOUND)
+            {
+                let resp: Response<(), ()> = Response::error(StatusCode::BAD_REQUEST, err.as_str());
                 HttpResponse::BadRequest().json(resp)
             } else if err.contains(&constants::NOTE_NOT_FOUND) {
                 let resp: Response<(), ()> =
@@ -220,9 +647,90 @@ pub async fn edit_note_handler(
         }
     };
 
+    let etag = service::note_etag(&note, false);
     let msg = constants::NOTE_SUCCESS_PATCHED;
     let resp: Response<NoteModel, ()> = Response::success(StatusCode::OK, note, msg);
-    return HttpResponse::Ok().json(resp);
+    return HttpResponse::Ok()
+        .insert_header((header::ETAG, etag))
+        .json(resp);
+}
+
+#[patch("/{id}")]
+pub async fn patch_note_handler(
+    path: web::Path<String>,
+    body: web::Json<PatchNoteSchema>,
+    data: web::Data<AppState>,
+    req: HttpRequest,
+) -> impl Responder {
+    let note_id_str = path.into_inner();
+    let if_match = conditional::if_match(&req).map(|tags| service::etag_versions(&tags));
+
+    //get user_id from authorization token
+    let user_id = utils::get_current_user_uuid_from_jwt(&data.cfg.clone(), req);
+    if let Err(err) = user_id {
+        let resp: Response<(), ()> = Response::error(StatusCode::UNAUTHORIZED, err.as_str());
+        return HttpResponse::Unauthorized().json(resp);
+    }
+
+    // Attempt to parse the UUID from the path
+    let note_id = match uuid::Uuid::parse_str(&note_id_str) {
+        Ok(uuid) => uuid,
+        Err(err) => {
+            eprintln!("match uuid::Uuid::parse_str, got err : {:?}", err);
+            let msg = constants::PARAMETER_NOTE_ID_INVALID;
+            let error_response: Response<(), ()> = Response::error(StatusCode::BAD_REQUEST, msg);
+            return HttpResponse::BadRequest().json(error_response);
+        }
+    };
+
+    //validate the struct from body
+    if let Err(errors) = body.validate() {
+        let resp = Response::custom(
+            StatusCode::BAD_REQUEST,
+            StatusCode::BAD_REQUEST.as_str(),
+            (),
+            errors,
+        );
+        return HttpResponse::BadRequest().json(resp);
+    }
+
+    let note = match service::patch_note_service(
+        &data.db,
+        note_id,
+        &body.0,
+        if_match.as_deref(),
+        user_id.unwrap(),
+    )
+    .await
+    {
+        Ok(note) => note,
+        Err(err) => {
+            let status = if err.contains(constants::NOTE_NOT_FOUND) {
+                StatusCode::NOT_FOUND
+            } else if err.contains(constants::NOTE_VERSION_MISMATCH) {
+                StatusCode::PRECONDITION_FAILED
+            } else if err.contains(constants::NOTE_TITLE_ALREADY_EXIST) {
+                StatusCode::CONFLICT
+            } else if err.contains(constants::NOTE_PATCH_INVALID)
+                || err.contains(constants::NOTE_SCHEDULE_INVALID)
+                || err.contains(notebook_constants::NOTEBOOK_NOT_FOUND)
+                || err.contains(category_constants::CATEGORY_NOT_FOUND)
+            {
+                StatusCode::BAD_REQUEST
+            } else {
+                StatusCode::INTERNAL_SERVER_ERROR
+            };
+            let resp: Response<(), ()> = Response::error(status, err.as_str());
+            return HttpResponse::build(status).json(resp);
+        }
+    };
+
+    let etag = service::note_etag(&note, false);
+    let msg = constants::NOTE_SUCCESS_PATCHED;
+    let resp: Response<NoteModel, ()> = Response::success(StatusCode::OK, note, msg);
+    HttpResponse::Ok()
+        .insert_header((header::ETAG, etag))
+        .json(resp)
 }
 
 #[delete("/{id}")]
@@ -232,6 +740,7 @@ pub async fn delete_note_handler(
     req: HttpRequest,
 ) -> impl Responder {
     let note_id = path.into_inner();
+    let if_match = conditional::if_match(&req).map(|tags| service::etag_versions(&tags));
 
     //get user_id from authorization token
     let user_id = utils::get_current_user_uuid_from_jwt(&data.cfg.clone(), req);
@@ -240,7 +749,9 @@ pub async fn delete_note_handler(
         return HttpResponse::Unauthorized().json(resp);
     }
 
-    let delete_note = service::delete_note_service(&data.db, note_id, user_id.unwrap()).await;
+    let delete_note =
+        service::delete_note_service(&data.db, note_id, if_match.as_deref(), user_id.unwrap())
+            .await;
     match delete_note {
         Ok(_) => {}
         Err(err_delete_note) => {
@@ -248,6 +759,10 @@ pub async fn delete_note_handler(
                 let resp: Response<(), ()> =
                     Response::error(StatusCode::NOT_FOUND, &constants::NOTE_NOT_FOUND);
                 HttpResponse::NotFound().json(resp)
+            } else if err_delete_note.contains(constants::NOTE_VERSION_MISMATCH) {
+                let resp: Response<(), ()> =
+                    Response::error(StatusCode::PRECONDITION_FAILED, err_delete_note.as_str());
+                HttpResponse::PreconditionFailed().json(resp)
             } else {
                 let resp: Response<(), ()> =
                     Response::error(StatusCode::INTERNAL_SERVER_ERROR, err_delete_note.as_str());
@@ -260,3 +775,709 @@ pub async fn delete_note_handler(
     let resp: Response<(), ()> = Response::success(StatusCode::OK, (), msg);
     return HttpResponse::Ok().json(resp);
 }
+
+#[get("/{id}/backlinks")]
+pub async fn note_backlinks_handler(
+    path: web::Path<String>,
+    data: web::Data<AppState>,
+    req: HttpRequest,
+) -> impl Responder {
+    let note_id_str = path.into_inner();
+
+    //get user_id from authorization token
+    let user_id = utils::get_current_user_uuid_from_jwt(&data.cfg.clone(), req);
+    if let Err(err) = user_id {
+        let resp: Response<(), ()> = Response::error(StatusCode::UNAUTHORIZED, err.as_str());
+        return HttpResponse::Unauthorized().json(resp);
+    }
+
+    // Attempt to parse the UUID from the path
+    let note_id = match uuid::Uuid::parse_str(&note_id_str) {
+        Ok(uuid) => uuid,
+        Err(err) => {
+            eprintln!("match uuid::Uuid::parse_str, got err : {:?}", err);
+            let msg = constants::PARAMETER_NOTE_ID_INVALID;
+            let error_response: Response<(), ()> = Response::error(StatusCode::BAD_REQUEST, msg);
+            return HttpResponse::BadRequest().json(error_response);
+        }
+    };
+
+    let notes = match service::get_note_backlinks_service(&data.db, note_id, user_id.unwrap()).await
+    {
+        Ok(notes) => notes,
+        Err(err) => {
+            return if err.contains(constants::NOTE_NOT_FOUND) {
+                let resp: Response<(), ()> =
+                    Response::error(StatusCode::NOT_FOUND, constants::NOTE_NOT_FOUND);
+                HttpResponse::NotFound().json(resp)
+            } else {
+                let resp: Response<(), ()> =
+                    Response::error(StatusCode::INTERNAL_SERVER_ERROR, err.as_str());
+                HttpResponse::InternalServerError().json(resp)
+            };
+        }
+    };
+
+    let resp: Response<Vec<NoteModel>, ()> =
+        Response::success(StatusCode::OK, notes, constants::NOTE_LINKS_FOUND);
+    HttpResponse::Ok().json(resp)
+}
+
+// upgrades to a WebSocket editing the note together with the other connected clients, see
+// CollabHub::serve for the frames exchanged
+#[get("/{id}/ws")]
+pub async fn note_collab_handler(
+    path: web::Path<String>,
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    body: web::Payload,
+) -> impl Responder {
+    let note_id_str = path.into_inner();
+
+    //get user_id from authorization token
+    let user_id = utils::get_current_user_uuid_from_jwt(&data.cfg.clone(), req.clone());
+    if let Err(err) = user_id {
+        let resp: Response<(), ()> = Response::error(StatusCode::UNAUTHORIZED, err.as_str());
+        return HttpResponse::Unauthorized().json(resp);
+    }
+
+    // Attempt to parse the UUID from the path
+    let note_id = match uuid::Uuid::parse_str(&note_id_str) {
+        Ok(uuid) => uuid,
+        Err(err) => {
+            eprintln!("match uuid::Uuid::parse_str, got err : {:?}", err);
+            let msg = constants::PARAMETER_NOTE_ID_INVALID;
+            let error_response: Response<(), ()> = Response::error(StatusCode::BAD_REQUEST, msg);
+            return HttpResponse::BadRequest().json(error_response);
+        }
+    };
+
+    let peer = match service::join_collab_service(&data.db, &data.collab, note_id, user_id.unwrap())
+        .await
+    {
+        Ok(peer) => peer,
+        Err(err) => {
+            return if err.contains(constants::NOTE_NOT_FOUND) {
+                let resp: Response<(), ()> =
+                    Response::error(StatusCode::NOT_FOUND, constants::NOTE_NOT_FOUND);
+                HttpResponse::NotFound().json(resp)
+            } else if err.contains(constants::NOTE_CONTENT_UNREADABLE) {
+                let resp: Response<(), ()> = Response::error(StatusCode::CONFLICT, err.as_str());
+                HttpResponse::Conflict().json(resp)
+            } else {
+                let resp: Response<(), ()> =
+                    Response::error(StatusCode::INTERNAL_SERVER_ERROR, err.as_str());
+                HttpResponse::InternalServerError().json(resp)
+            };
+        }
+    };
+
+    let (response, session, stream) = match actix_ws::handle(&req, body) {
+        Ok(upgrade) => upgrade,
+        Err(err) => {
+            data.collab.leave(peer.note_id, peer.peer_id);
+            return err.error_response();
+        }
+    };
+    actix_web::rt::spawn(service::collab_session_service(
+        data.db.clone(),
+        data.collab.clone(),
+        peer,
+        session,
+        stream,
+    ));
+    response
+}
+
+#[put("/{id}/notebook")]
+pub async fn move_note_handler(
+    path: web::Path<uuid::Uuid>,
+    body: web::Json<MoveNoteSchema>,
+    data: web::Data<AppState>,
+    req: HttpRequest,
+) -> impl Responder {
+    let note_id = path.into_inner();
+
+    //get user_id from authorization token
+    let user_id = utils::get_current_user_uuid_from_jwt(&data.cfg.clone(), req);
+    if let Err(err) = user_id {
+        let resp: Response<(), ()> = Response::error(StatusCode::UNAUTHORIZED, err.as_str());
+        return HttpResponse::Unauthorized().json(resp);
+    }
+
+    let note = match service::move_note_service(&data.db, note_id, &body.0, user_id.unwrap()).await
+    {
+        Ok(note) => note,
+        Err(err) => {
+            return if err.contains(constants::NOTE_NOT_FOUND) {
+                let resp: Response<(), ()> =
+                    Response::error(StatusCode::NOT_FOUND, constants::NOTE_NOT_FOUND);
+                HttpResponse::NotFound().json(resp)
+            } else if err.contains(notebook_constants::NOTEBOOK_NOT_FOUND) {
+                let resp: Response<(), ()> = Response::error(StatusCode::BAD_REQUEST, err.as_str());
+                HttpResponse::BadRequest().json(resp)
+            } else {
+                let resp: Response<(), ()> =
+                    Response::error(StatusCode::INTERNAL_SERVER_ERROR, err.as_str());
+                HttpResponse::InternalServerError().json(resp)
+            };
+        }
+    };
+
+    let msg = constants::NOTE_SUCCESS_MOVED;
+    let resp: Response<NoteModel, ()> = Response::success(StatusCode::OK, note, msg);
+    HttpResponse::Ok().json(resp)
+}
+
+fn note_state_response(result: Result<NoteModel, String>, msg: &str) -> HttpResponse {
+    match result {
+        Ok(note) => {
+            let resp: Response<NoteModel, ()> = Response::success(StatusCode::OK, note, msg);
+            HttpResponse::Ok().json(resp)
+        }
+        Err(err) if err.contains(constants::NOTE_NOT_FOUND) => {
+            let resp: Response<(), ()> =
+                Response::error(StatusCode::NOT_FOUND, constants::NOTE_NOT_FOUND);
+            HttpResponse::NotFound().json(resp)
+        }
+        Err(err) => {
+            let resp: Response<(), ()> =
+                Response::error(StatusCode::INTERNAL_SERVER_ERROR, err.as_str());
+            HttpResponse::InternalServerError().json(resp)
+        }
+    }
+}
+
+#[post("/{id}/duplicate")]
+pub async fn duplicate_note_handler(
+    path: web::Path<String>,
+    data: web::Data<AppState>,
+    req: HttpRequest,
+) -> impl Responder {
+    let note_id_str = path.into_inner();
+
+    //get user_id from authorization token
+    let user_id = utils::get_current_user_uuid_from_jwt(&data.cfg.clone(), req);
+    if let Err(err) = user_id {
+        let resp: Response<(), ()> = Response::error(StatusCode::UNAUTHORIZED, err.as_str());
+        return HttpResponse::Unauthorized().json(resp);
+    }
+
+    // Attempt to parse the UUID from the path
+    let note_id = match uuid::Uuid::parse_str(&note_id_str) {
+        Ok(uuid) => uuid,
+        Err(err) => {
+            eprintln!("match uuid::Uuid::parse_str, got err : {:?}", err);
+            let msg = constants::PARAMETER_NOTE_ID_INVALID;
+            let error_response: Response<(), ()> = Response::error(StatusCode::BAD_REQUEST, msg);
+            return HttpResponse::BadRequest().json(error_response);
+        }
+    };
+
+    match service::duplicate_note_service(&data.db, note_id, user_id.unwrap()).await {
+        Err(err) if err.contains(constants::NOTE_TITLE_ALREADY_EXIST) => {
+            let resp: Response<(), ()> =
+                Response::error(StatusCode::CONFLICT, constants::NOTE_TITLE_ALREADY_EXIST);
+            HttpResponse::Conflict().json(resp)
+        }
+        Err(err) if err.contains(category_constants::CATEGORY_NOT_FOUND) => {
+            let resp: Response<(), ()> = Response::error(StatusCode::BAD_REQUEST, err.as_str());
+            HttpResponse::BadRequest().json(resp)
+        }
+        Err(err) if err.contains(constants::NOTE_CONTENT_UNREADABLE) => {
+            let resp: Response<(), ()> = Response::error(StatusCode::CONFLICT, err.as_str());
+            HttpResponse::Conflict().json(resp)
+        }
+        result => note_state_response(result, constants::NOTE_SUCCESS_DUPLICATED),
+    }
+}
+
+#[put("/{id}/pin")]
+pub async fn pin_note_handler(
+    path: web::Path<String>,
+    data: web::Data<AppState>,
+    req: HttpRequest,
+) -> impl Responder {
+    let note_id_str = path.into_inner();
+
+    //get user_id from authorization token
+    let user_id = utils::get_current_user_uuid_from_jwt(&data.cfg.clone(), req);
+    if let Err(err) = user_id {
+        let resp: Response<(), ()> = Response::error(StatusCode::UNAUTHORIZED, err.as_str());
+        return HttpResponse::Unauthorized().json(resp);
+    }
+
+    // Attempt to parse the UUID from the path
+    let note_id = match uuid::Uuid::parse_str(&note_id_str) {
+        Ok(uuid) => uuid,
+        Err(err) => {
+            eprintln!("match uuid::Uuid::parse_str, got err : {:?}", err);
+            let msg = constants::PARAMETER_NOTE_ID_INVALID;
+            let error_response: Response<(), ()> = Response::error(StatusCode::BAD_REQUEST, msg);
+            return HttpResponse::BadRequest().json(error_response);
+        }
+    };
+
+    let result = service::set_note_pinned_service(&data.db, note_id, true, user_id.unwrap()).await;
+    note_state_response(result, constants::NOTE_SUCCESS_PINNED)
+}
+
+#[delete("/{id}/pin")]
+pub async fn unpin_note_handler(
+    path: web::Path<String>,
+    data: web::Data<AppState>,
+    req: HttpRequest,
+) -> impl Responder {
+    let note_id_str = path.into_inner();
+
+    //get user_id from authorization token
+    let user_id = utils::get_current_user_uuid_from_jwt(&data.cfg.clone(), req);
+    if let Err(err) = user_id {
+        let resp: Response<(), ()> = Response::error(StatusCode::UNAUTHORIZED, err.as_str());
+        return HttpResponse::Unauthorized().json(resp);
+    }
+
+    // Attempt to parse the UUID from the path
+    let note_id = match uuid::Uuid::parse_str(&note_id_str) {
+        Ok(uuid) => uuid,
+        Err(err) => {
+            eprintln!("match uuid::Uuid::parse_str, got err : {:?}", err);
+            let msg = constants::PARAMETER_NOTE_ID_INVALID;
+            let error_response: Response<(), ()> = Response::error(StatusCode::BAD_REQUEST, msg);
+            return HttpResponse::BadRequest().json(error_response);
+        }
+    };
+
+    let result = service::set_note_pinned_service(&data.db, note_id, false, user_id.unwrap()).await;
+    note_state_response(result, constants::NOTE_SUCCESS_UNPINNED)
+}
+
+#[put("/{id}/archive")]
+pub async fn archive_note_handler(
+    path: web::Path<String>,
+    data: web::Data<AppState>,
+    req: HttpRequest,
+) -> impl Responder {
+    let note_id_str = path.into_inner();
+
+    //get user_id from authorization token
+    let user_id = utils::get_current_user_uuid_from_jwt(&data.cfg.clone(), req);
+    if let Err(err) = user_id {
+        let resp: Response<(), ()> = Response::error(StatusCode::UNAUTHORIZED, err.as_str());
+        return HttpResponse::Unauthorized().json(resp);
+    }
+
+    // Attempt to parse the UUID from the path
+    let note_id = match uuid::Uuid::parse_str(&note_id_str) {
+        Ok(uuid) => uuid,
+        Err(err) => {
+            eprintln!("match uuid::Uuid::parse_str, got err : {:?}", err);
+            let msg = constants::PARAMETER_NOTE_ID_INVALID;
+            let error_response: Response<(), ()> = Response::error(StatusCode::BAD_REQUEST, msg);
+            return HttpResponse::BadRequest().json(error_response);
+        }
+    };
+
+    let result =
+        service::set_note_archived_service(&data.db, note_id, true, user_id.unwrap()).await;
+    note_state_response(result, constants::NOTE_SUCCESS_ARCHIVED)
+}
+
+#[delete("/{id}/archive")]
+pub async fn unarchive_note_handler(
+    path: web::Path<String>,
+    data: web::Data<AppState>,
+    req: HttpRequest,
+) -> impl Responder {
+    let note_id_str = path.into_inner();
+
+    //get user_id from authorization token
+    let user_id = utils::get_current_user_uuid_from_jwt(&data.cfg.clone(), req);
+    if let Err(err) = user_id {
+        let resp: Response<(), ()> = Response::error(StatusCode::UNAUTHORIZED, err.as_str());
+        return HttpResponse::Unauthorized().json(resp);
+    }
+
+    // Attempt to parse the UUID from the path
+    let note_id = match uuid::Uuid::parse_str(&note_id_str) {
+        Ok(uuid) => uuid,
+        Err(err) => {
+            eprintln!("match uuid::Uuid::parse_str, got err : {:?}", err);
+            let msg = constants::PARAMETER_NOTE_ID_INVALID;
+            let error_response: Response<(), ()> = Response::error(StatusCode::BAD_REQUEST, msg);
+            return HttpResponse::BadRequest().json(error_response);
+        }
+    };
+
+    let result =
+        service::set_note_archived_service(&data.db, note_id, false, user_id.unwrap()).await;
+    note_state_response(result, constants::NOTE_SUCCESS_UNARCHIVED)
+}
+
+#[put("/{id}/favorite")]
+pub async fn favorite_note_handler(
+    path: web::Path<String>,
+    data: web::Data<AppState>,
+    req: HttpRequest,
+) -> impl Responder {
+    let note_id_str = path.into_inner();
+
+    //get user_id from authorization token
+    let user_id = utils::get_current_user_uuid_from_jwt(&data.cfg.clone(), req);
+    if let Err(err) = user_id {
+        let resp: Response<(), ()> = Response::error(StatusCode::UNAUTHORIZED, err.as_str());
+        return HttpResponse::Unauthorized().json(resp);
+    }
+
+    // Attempt to parse the UUID from the path
+    let note_id = match uuid::Uuid::parse_str(&note_id_str) {
+        Ok(uuid) => uuid,
+        Err(err) => {
+            eprintln!("match uuid::Uuid::parse_str, got err : {:?}", err);
+            let msg = constants::PARAMETER_NOTE_ID_INVALID;
+            let error_response: Response<(), ()> = Response::error(StatusCode::BAD_REQUEST, msg);
+            return HttpResponse::BadRequest().json(error_response);
+        }
+    };
+
+    let result =
+        service::set_note_favorite_service(&data.db, note_id, true, user_id.unwrap()).await;
+    note_state_response(result, constants::NOTE_SUCCESS_FAVORITED)
+}
+
+#[delete("/{id}/favorite")]
+pub async fn unfavorite_note_handler(
+    path: web::Path<String>,
+    data: web::Data<AppState>,
+    req: HttpRequest,
+) -> impl Responder {
+    let note_id_str = path.into_inner();
+
+    //get user_id from authorization token
+    let user_id = utils::get_current_user_uuid_from_jwt(&data.cfg.clone(), req);
+    if let Err(err) = user_id {
+        let resp: Response<(), ()> = Response::error(StatusCode::UNAUTHORIZED, err.as_str());
+        return HttpResponse::Unauthorized().json(resp);
+    }
+
+    // Attempt to parse the UUID from the path
+    let note_id = match uuid::Uuid::parse_str(&note_id_str) {
+        Ok(uuid) => uuid,
+        Err(err) => {
+            eprintln!("match uuid::Uuid::parse_str, got err : {:?}", err);
+            let msg = constants::PARAMETER_NOTE_ID_INVALID;
+            let error_response: Response<(), ()> = Response::error(StatusCode::BAD_REQUEST, msg);
+            return HttpResponse::BadRequest().json(error_response);
+        }
+    };
+
+    let result =
+        service::set_note_favorite_service(&data.db, note_id, false, user_id.unwrap()).await;
+    note_state_response(result, constants::NOTE_SUCCESS_UNFAVORITED)
+}
+
+#[post("/{id}/share-links")]
+pub async fn create_share_link_handler(
+    path: web::Path<String>,
+    body: web::Json<CreateShareLinkSchema>,
+    data: web::Data<AppState>,
+    req: HttpRequest,
+) -> impl Responder {
+    let note_id_str = path.into_inner();
+
+    //get user_id from authorization token
+    let user_id = utils::get_current_user_uuid_from_jwt(&data.cfg.clone(), req);
+    if let Err(err) = user_id {
+        let resp: Response<(), ()> = Response::error(StatusCode::UNAUTHORIZED, err.as_str());
+        return HttpResponse::Unauthorized().json(resp);
+    }
+
+    // Attempt to parse the UUID from the path
+    let note_id = match uuid::Uuid::parse_str(&note_id_str) {
+        Ok(uuid) => uuid,
+        Err(err) => {
+            eprintln!("match uuid::Uuid::parse_str, got err : {:?}", err);
+            let msg = constants::PARAMETER_NOTE_ID_INVALID;
+            let error_response: Response<(), ()> = Response::error(StatusCode::BAD_REQUEST, msg);
+            return HttpResponse::BadRequest().json(error_response);
+        }
+    };
+
+    //validate the struct from body
+    if let Err(errors) = body.validate() {
+        let resp = Response::custom(
+            StatusCode::BAD_REQUEST,
+            StatusCode::BAD_REQUEST.as_str(),
+            (),
+            errors,
+        );
+        return HttpResponse::BadRequest().json(resp);
+    }
+
+    let share_link = match service::create_share_link_service(
+        &data.db,
+        note_id,
+        &body.0,
+        user_id.unwrap(),
+    )
+    .await
+    {
+        Ok(share_link) => share_link,
+        Err(err) => {
+            return if err.contains(constants::NOTE_NOT_FOUND) {
+                let resp: Response<(), ()> =
+                    Response::error(StatusCode::NOT_FOUND, constants::NOTE_NOT_FOUND);
+                HttpResponse::NotFound().json(resp)
+            } else {
+                let resp: Response<(), ()> =
+                    Response::error(StatusCode::INTERNAL_SERVER_ERROR, err.as_str());
+                HttpResponse::InternalServerError().json(resp)
+            };
+        }
+    };
+
+    let msg = constants::SHARE_LINK_SUCCESS_SAVED;
+    let resp: Response<NoteShareLinkModel, ()> = Response::success(StatusCode::OK, share_link, msg);
+    return HttpResponse::Ok().json(resp);
+}
+
+#[get("/{id}/share-links")]
+pub async fn share_link_list_handler(
+    path: web::Path<String>,
+    data: web::Data<AppState>,
+    req: HttpRequest,
+) -> impl Responder {
+    let note_id_str = path.into_inner();
+
+    //get user_id from authorization token
+    let user_id = utils::get_current_user_uuid_from_jwt(&data.cfg.clone(), req);
+    if let Err(err) = user_id {
+        let resp: Response<(), ()> = Response::error(StatusCode::UNAUTHORIZED, err.as_str());
+        return HttpResponse::Unauthorized().json(resp);
+    }
+
+    // Attempt to parse the UUID from the path
+    let note_id = match uuid::Uuid::parse_str(&note_id_str) {
+        Ok(uuid) => uuid,
+        Err(err) => {
+            eprintln!("match uuid::Uuid::parse_str, got err : {:?}", err);
+            let msg = constants::PARAMETER_NOTE_ID_INVALID;
+            let error_response: Response<(), ()> = Response::error(StatusCode::BAD_REQUEST, msg);
+            return HttpResponse::BadRequest().json(error_response);
+        }
+    };
+
+    let share_links =
+        match service::get_share_links_service(&data.db, note_id, user_id.unwrap()).await {
+            Ok(share_links) => share_links,
+            Err(err) => {
+                return if err.contains(constants::NOTE_NOT_FOUND) {
+                    let resp: Response<(), ()> =
+                        Response::error(StatusCode::NOT_FOUND, constants::NOTE_NOT_FOUND);
+                    HttpResponse::NotFound().json(resp)
+                } else {
+                    let resp: Response<(), ()> =
+                        Response::error(StatusCode::INTERNAL_SERVER_ERROR, err.as_str());
+                    HttpResponse::InternalServerError().json(resp)
+                };
+            }
+        };
+
+    let msg = constants::SHARE_LINK_FOUND;
+    let resp: Response<Vec<NoteShareLinkModel>, ()> =
+        Response::success(StatusCode::OK, share_links, msg);
+    return HttpResponse::Ok().json(resp);
+}
+
+#[delete("/{id}/share-links/{link_id}")]
+pub async fn delete_share_link_handler(
+    path: web::Path<(String, String)>,
+    data: web::Data<AppState>,
+    req: HttpRequest,
+) -> impl Responder {
+    let (note_id_str, link_id_str) = path.into_inner();
+
+    //get user_id from authorization token
+    let user_id = utils::get_current_user_uuid_from_jwt(&data.cfg.clone(), req);
+    if let Err(err) = user_id {
+        let resp: Response<(), ()> = Response::error(StatusCode::UNAUTHORIZED, err.as_str());
+        return HttpResponse::Unauthorized().json(resp);
+    }
+
+    // Attempt to parse the UUID from the path
+    let note_id = match uuid::Uuid::parse_str(&note_id_str) {
+        Ok(uuid) => uuid,
+        Err(err) => {
+            eprintln!("match uuid::Uuid::parse_str, got err : {:?}", err);
+            let msg = constants::PARAMETER_NOTE_ID_INVALID;
+            let error_response: Response<(), ()> = Response::error(StatusCode::BAD_REQUEST, msg);
+            return HttpResponse::BadRequest().json(error_response);
+        }
+    };
+
+    let link_id = match uuid::Uuid::parse_str(&link_id_str) {
+        Ok(uuid) => uuid,
+        Err(err) => {
+            eprintln!("match uuid::Uuid::parse_str, got err : {:?}", err);
+            let msg = constants::PARAMETER_SHARE_LINK_ID_INVALID;
+            let error_response: Response<(), ()> = Response::error(StatusCode::BAD_REQUEST, msg);
+            return HttpResponse::BadRequest().json(error_response);
+        }
+    };
+
+    let delete_share_link =
+        service::delete_share_link_service(&data.db, note_id, link_id, user_id.unwrap()).await;
+    if let Err(err) = delete_share_link {
+        return if err.contains(constants::NOTE_NOT_FOUND)
+            || err.contains(constants::SHARE_LINK_NOT_FOUND)
+        {
+            let resp: Response<(), ()> = Response::error(StatusCode::NOT_FOUND, err.as_str());
+            HttpResponse::NotFound().json(resp)
+        } else {
+            let resp: Response<(), ()> =
+                Response::error(StatusCode::INTERNAL_SERVER_ERROR, err.as_str());
+            HttpResponse::InternalServerError().json(resp)
+        };
+    }
+
+    let msg = constants::SHARE_LINK_SUCCESS_DELETED;
+    let resp: Response<(), ()> = Response::success(StatusCode::OK, (), msg);
+    return HttpResponse::Ok().json(resp);
+}
+
+fn public_note_error_status(err: &str) -> StatusCode {
+    if err.contains(constants::NOTE_NOT_FOUND) || err.contains(constants::SHARE_LINK_NOT_FOUND) {
+        StatusCode::NOT_FOUND
+    } else if err.contains(constants::SHARE_LINK_EXPIRED) {
+        StatusCode::GONE
+    } else {
+        StatusCode::INTERNAL_SERVER_ERROR
+    }
+}
+
+fn public_note_json(result: Result<PublicNoteResponse, String>) -> HttpResponse {
+    match result {
+        Ok(note) => {
+            let resp: Response<PublicNoteResponse, ()> =
+                Response::success(StatusCode::OK, note, constants::NOTE_FOUND);
+            HttpResponse::Ok().json(resp)
+        }
+        Err(err) => {
+            let status = public_note_error_status(&err);
+            let resp: Response<(), ()> = Response::error(status, err.as_str());
+            HttpResponse::build(status).json(resp)
+        }
+    }
+}
+
+fn public_note_view(result: Result<PublicNoteResponse, String>) -> HttpResponse {
+    match result {
+        Ok(note) => HttpResponse::Ok()
+            .content_type(ContentType::html())
+            .body(service::render_public_note_html(&note)),
+        Err(err) => {
+            let status = public_note_error_status(&err);
+            let body = format!("<h1>{}</h1>\n<p>{}</p>", status, html::escape(&err));
+            HttpResponse::build(status)
+                .content_type(ContentType::html())
+                .body(html::render_page(&status.to_string(), &body))
+        }
+    }
+}
+
+#[get("/notes/{slug}")]
+pub async fn public_note_handler(
+    path: web::Path<String>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    return public_note_json(service::get_public_note_service(&data.db, &path.into_inner()).await);
+}
+
+#[get("/notes/{slug}/view", name = "public_note_view")]
+pub async fn public_note_view_handler(
+    path: web::Path<String>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    return public_note_view(service::get_public_note_service(&data.db, &path.into_inner()).await);
+}
+
+// timestamps can not tell that a note left the feed, so conditional requests go by the
+// entity tag of the rendered feed alone
+async fn user_feed(
+    req: HttpRequest,
+    data: web::Data<AppState>,
+    username: String,
+    format: FeedFormat,
+) -> HttpResponse {
+    let note_url = |slug: &str| {
+        req.url_for("public_note_view", [slug])
+            .map(|url| url.to_string())
+            .unwrap_or_default()
+    };
+    let feed = match service::get_user_feed_service(
+        &data.db,
+        &data.render_cache,
+        &username,
+        req.full_url().to_string(),
+        note_url,
+    )
+    .await
+    {
+        Ok(feed) => feed,
+        Err(err) => {
+            let status = if err.contains(user_constants::USER_NOT_FOUND) {
+                StatusCode::NOT_FOUND
+            } else {
+                StatusCode::INTERNAL_SERVER_ERROR
+            };
+            let resp: Response<(), ()> = Response::error(status, err.as_str());
+            return HttpResponse::build(status).json(resp);
+        }
+    };
+
+    let body = format.render(&feed);
+    let etag = format!("\"{:x}\"", Sha256::digest(body.as_bytes()));
+    if conditional::none_match(&req, &etag) {
+        return HttpResponse::NotModified()
+            .insert_header((header::ETAG, etag))
+            .finish();
+    }
+    HttpResponse::Ok()
+        .content_type(format.content_type())
+        .insert_header((header::ETAG, etag))
+        .body(body)
+}
+
+#[get("/users/{username}/feed.atom")]
+pub async fn user_atom_feed_handler(
+    path: web::Path<String>,
+    data: web::Data<AppState>,
+    req: HttpRequest,
+) -> impl Responder {
+    user_feed(req, data, path.into_inner(), FeedFormat::Atom).await
+}
+
+#[get("/users/{username}/feed.rss")]
+pub async fn user_rss_feed_handler(
+    path: web::Path<String>,
+    data: web::Data<AppState>,
+    req: HttpRequest,
+) -> impl Responder {
+    user_feed(req, data, path.into_inner(), FeedFormat::Rss).await
+}
+
+#[get("/shared/{token}")]
+pub async fn shared_note_handler(
+    path: web::Path<String>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    return public_note_json(service::get_shared_note_service(&data.db, &path.into_inner()).await);
+}
+
+#[get("/shared/{token}/view")]
+pub async fn shared_note_view_handler(
+    path: web::Path<String>,
+    data: web::Data<AppState>,
+) -> impl Responder {
+    return public_note_view(service::get_shared_note_service(&data.db, &path.into_inner()).await);
+}
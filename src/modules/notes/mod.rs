@@ -1,7 +1,7 @@
-mod constants;
+pub(crate) mod constants;
 mod handler;
-mod model;
+pub(crate) mod model;
 mod repository;
 pub mod routes;
-mod schema;
-mod service;
+pub(crate) mod schema;
+pub(crate) mod service;
@@ -1,5 +1,10 @@
+use crate::infrastructure::crypto;
+use crate::infrastructure::pagination::Cursor;
 use serde::{Deserialize, Serialize};
-use sqlx::FromRow;
+use sqlx::error::BoxDynError;
+use sqlx::postgres::{PgTypeInfo, PgValueRef};
+use sqlx::types::Json;
+use sqlx::{FromRow, Postgres};
 use uuid::Uuid;
 
 #[derive(Debug, FromRow, Deserialize, Serialize, Clone)]
@@ -7,9 +12,37 @@ use uuid::Uuid;
 pub struct NoteModel {
     pub id: Uuid,
     pub title: String,
+    #[sqlx(try_from = "StoredContent")]
     pub content: String,
+    // set when the stored content could not be opened, `content` is empty then
+    #[sqlx(rename = "content", try_from = "StoredContent")]
+    #[serde(
+        rename = "contentUnreadable",
+        default,
+        skip_serializing_if = "std::ops::Not::not"
+    )]
+    pub content_unreadable: bool,
     pub category: Option<String>,
+    pub category_id: Option<Uuid>,
+    pub tags: Option<Vec<String>>,
+    pub notebook_id: Option<Uuid>,
     pub published: Option<bool>,
+    pub slug: Option<String>,
+    pub pinned: Option<bool>,
+    pub version: Option<i32>,
+    #[serde(rename = "archivedAt")]
+    pub archived_at: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(rename = "dueAt")]
+    pub due_at: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(rename = "remindAt")]
+    pub remind_at: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(rename = "remindedAt")]
+    pub reminded_at: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(rename = "publishAt")]
+    pub publish_at: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(rename = "unpublishAt")]
+    pub unpublish_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub scheduled_by: Option<Uuid>,
     pub created_by: Option<Uuid>,
     pub updated_by: Option<Uuid>,
     #[serde(rename = "createdAt")]
@@ -18,6 +51,271 @@ pub struct NoteModel {
     pub updated_at: Option<chrono::DateTime<chrono::Utc>>,
     #[serde(rename = "deletedAt")]
     pub deleted_at: Option<chrono::DateTime<chrono::Utc>>,
+    #[sqlx(default)]
+    #[serde(rename = "contentHtml", skip_serializing_if = "Option::is_none")]
+    pub content_html: Option<String>,
+    #[sqlx(default)]
+    #[serde(rename = "commentCount", skip_serializing_if = "Option::is_none")]
+    pub comment_count: Option<i64>,
+    #[sqlx(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub favorite: Option<bool>,
+    #[sqlx(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub links: Option<Json<Vec<NoteLinkModel>>>,
+    #[sqlx(default)]
+    #[serde(rename = "checklistProgress", skip_serializing_if = "Option::is_none")]
+    pub checklist_progress: Option<String>,
+}
+
+// note content the way it sits in the database, opened while the row is read. content that
+// can not be opened was sealed with a key that is no longer configured, or is plain text from
+// before such text was escaped. one such note must not take down every list it is part of, so
+// it is read as empty content and marked unreadable, and whatever would write the content
+// back refuses the note
+#[derive(Debug)]
+pub struct StoredContent(String);
+
+impl sqlx::Type<Postgres> for StoredContent {
+    fn type_info() -> PgTypeInfo {
+        <String as sqlx::Type<Postgres>>::type_info()
+    }
+
+    fn compatible(ty: &PgTypeInfo) -> bool {
+        <String as sqlx::Type<Postgres>>::compatible(ty)
+    }
+}
+
+impl<'r> sqlx::Decode<'r, Postgres> for StoredContent {
+    fn decode(value: PgValueRef<'r>) -> Result<Self, BoxDynError> {
+        <String as sqlx::Decode<Postgres>>::decode(value).map(StoredContent)
+    }
+}
+
+impl TryFrom<StoredContent> for String {
+    type Error = String;
+
+    fn try_from(stored: StoredContent) -> Result<String, String> {
+        Ok(crypto::open(&stored.0).unwrap_or_else(|err| {
+            eprintln!("error open note content {:?}", err);
+            String::new()
+        }))
+    }
+}
+
+impl TryFrom<StoredContent> for bool {
+    type Error = String;
+
+    fn try_from(stored: StoredContent) -> Result<bool, String> {
+        Ok(crypto::open(&stored.0).is_err())
+    }
+}
+
+// a `[[title]]` link of a note, `id` stays empty while the user has no live note with that title
+#[derive(Debug, FromRow, Deserialize, Serialize, Clone)]
+pub struct NoteLinkModel {
+    pub title: String,
+    pub id: Option<Uuid>,
+}
+
+// one row of the note event log, also the payload of the note_events notification. the
+// owner and visibility only decide who receives it
+#[derive(Debug, FromRow, Deserialize, Serialize, Clone)]
+pub struct NoteEventModel {
+    pub id: i64,
+    pub note_id: Uuid,
+    #[serde(skip_serializing)]
+    pub user_id: Option<Uuid>,
+    #[serde(skip_serializing)]
+    pub public: bool,
+    pub event: String,
+    #[serde(rename = "createdAt", alias = "created_at")]
+    pub created_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl NoteEventModel {
+    pub fn visible_to(&self, user_id: Uuid) -> bool {
+        self.public || self.user_id == Some(user_id)
+    }
+}
+
+// one batch of re-encryption, the next batch starts after `last_id`
+#[derive(Debug, Clone, Default)]
+pub struct NoteReencryptBatch {
+    pub last_id: Option<Uuid>,
+    pub sealed: u64,
+    pub skipped: Vec<Uuid>,
+}
+
+// a reminder that is due, along with the owner it goes to
+#[derive(Debug, FromRow, Deserialize, Serialize, Clone)]
+pub struct NoteReminderModel {
+    pub id: Uuid,
+    pub title: String,
+    pub due_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub remind_at: chrono::DateTime<chrono::Utc>,
+    // counting the attempt being made now
+    pub attempts: i32,
+    pub user_id: Uuid,
+    pub email: Option<String>,
+    pub fullname: Option<String>,
+}
+
+#[derive(Debug, FromRow, Deserialize, Serialize, Clone)]
+pub struct NoteGraphNode {
+    pub id: Uuid,
+    pub title: String,
+}
+
+#[derive(Debug, FromRow, Deserialize, Serialize, Clone)]
+pub struct NoteGraphEdge {
+    pub source: Uuid,
+    pub target: Uuid,
+}
+
+// everything /notes/stats reports, taken from one snapshot of the notes
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct NoteStatsModel {
+    pub totals: NoteTotalsModel,
+    pub categories: Vec<NoteCategoryCountModel>,
+    pub tags: Vec<NoteTagCountModel>,
+    pub activity: Vec<NoteActivityModel>,
+    #[serde(rename = "longestNotes")]
+    pub longest_notes: Vec<NoteLengthModel>,
+}
+
+// `all` and the word count leave the trashed notes out, archived notes are part of `all` and
+// counted on their own in `archived` as well
+#[derive(Debug, FromRow, Deserialize, Serialize, Clone)]
+pub struct NoteTotalsModel {
+    pub all: i64,
+    pub published: i64,
+    pub archived: i64,
+    pub trashed: i64,
+    #[serde(rename = "wordCount")]
+    pub word_count: i64,
+}
+
+#[derive(Debug, FromRow, Deserialize, Serialize, Clone)]
+pub struct NoteCategoryCountModel {
+    pub id: Uuid,
+    pub name: String,
+    pub color: String,
+    pub count: i64,
+}
+
+#[derive(Debug, FromRow, Deserialize, Serialize, Clone)]
+pub struct NoteTagCountModel {
+    pub tag: String,
+    pub count: i64,
+}
+
+// `updated` counts the notes last edited that day, a note only ever written once counts as
+// created alone
+#[derive(Debug, FromRow, Deserialize, Serialize, Clone)]
+pub struct NoteActivityModel {
+    pub date: chrono::NaiveDate,
+    pub created: i64,
+    pub updated: i64,
+}
+
+#[derive(Debug, FromRow, Deserialize, Serialize, Clone)]
+pub struct NoteLengthModel {
+    pub id: Uuid,
+    pub title: String,
+    #[serde(rename = "wordCount")]
+    pub word_count: i64,
+}
+
+// the whitelist of fields the notes list can be sorted on, every column expression is
+// null safe so keyset comparisons never meet a NULL
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NoteSortField {
+    CreatedAt,
+    UpdatedAt,
+    Title,
+    Category,
+    Published,
+}
+
+impl NoteSortField {
+    pub fn parse(value: &str) -> Option<NoteSortField> {
+        match value {
+            "created_at" => Some(NoteSortField::CreatedAt),
+            "updated_at" => Some(NoteSortField::UpdatedAt),
+            "title" => Some(NoteSortField::Title),
+            "category" => Some(NoteSortField::Category),
+            "published" => Some(NoteSortField::Published),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            NoteSortField::CreatedAt => "created_at",
+            NoteSortField::UpdatedAt => "updated_at",
+            NoteSortField::Title => "title",
+            NoteSortField::Category => "category",
+            NoteSortField::Published => "published",
+        }
+    }
+
+    pub fn column(&self) -> &'static str {
+        match self {
+            NoteSortField::CreatedAt => "COALESCE(created_at, 'epoch'::timestamptz)",
+            NoteSortField::UpdatedAt => "COALESCE(updated_at, created_at, 'epoch'::timestamptz)",
+            NoteSortField::Title => "title",
+            NoteSortField::Category => "COALESCE(category, '')",
+            NoteSortField::Published => "COALESCE(published, false)",
+        }
+    }
+
+    pub fn cast(&self) -> &'static str {
+        match self {
+            NoteSortField::CreatedAt | NoteSortField::UpdatedAt => "timestamptz",
+            NoteSortField::Title | NoteSortField::Category => "text",
+            NoteSortField::Published => "boolean",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NoteSortKey {
+    pub field: NoteSortField,
+    pub descending: bool,
+}
+
+// list filters once they are validated, every value ends up as a bound parameter
+#[derive(Debug, Clone, Default)]
+pub struct NoteFilter {
+    pub title: Option<String>,
+    pub content: Option<String>,
+    pub categories: Option<Vec<String>>,
+    pub category_id: Option<Uuid>,
+    pub tags: Option<Vec<String>>,
+    pub published: Option<bool>,
+    pub notebook_ids: Option<Vec<Uuid>>,
+    pub pinned: Option<bool>,
+    // None takes archived and unarchived notes alike
+    pub archived: Option<bool>,
+    pub favorite: Option<bool>,
+    pub open_items: Option<bool>,
+    pub created_after: Option<chrono::DateTime<chrono::Utc>>,
+    pub created_before: Option<chrono::DateTime<chrono::Utc>>,
+    pub updated_after: Option<chrono::DateTime<chrono::Utc>>,
+    pub updated_before: Option<chrono::DateTime<chrono::Utc>>,
+    pub due_after: Option<chrono::DateTime<chrono::Utc>>,
+    pub due_before: Option<chrono::DateTime<chrono::Utc>>,
+    pub scheduled: Option<bool>,
+}
+
+// one cursor page request, `forward` is false when paging back from a `before` cursor
+#[derive(Debug, Clone)]
+pub struct NoteKeysetQuery {
+    pub sort: Vec<NoteSortKey>,
+    pub cursor: Option<Cursor>,
+    pub forward: bool,
+    pub limit: i64,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -26,7 +324,13 @@ pub struct NoteSaveModel {
     pub title: String,
     pub content: String,
     pub category: Option<String>,
+    pub tags: Vec<String>,
+    pub notebook_id: Option<Uuid>,
     pub published: Option<bool>,
+    pub due_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub remind_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub publish_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub unpublish_at: Option<chrono::DateTime<chrono::Utc>>,
     pub created_by: Option<Uuid>,
 }
 
@@ -36,6 +340,91 @@ pub struct NoteUpdateModel {
     pub title: String,
     pub content: String,
     pub category: Option<String>,
+    pub tags: Option<Vec<String>>,
     pub published: Option<bool>,
+    pub due_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub remind_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub publish_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub unpublish_at: Option<chrono::DateTime<chrono::Utc>>,
     pub updated_by: Option<Uuid>,
 }
+
+// only the fields that are Some end up in the UPDATE, `Some(None)` sets the column to NULL
+#[derive(Debug, Clone, Default)]
+pub struct NotePatchModel {
+    pub title: Option<String>,
+    pub content: Option<String>,
+    pub category: Option<Option<String>>,
+    pub tags: Option<Vec<String>>,
+    pub published: Option<bool>,
+    pub notebook_id: Option<Option<Uuid>>,
+    pub due_at: Option<Option<chrono::DateTime<chrono::Utc>>>,
+    pub remind_at: Option<Option<chrono::DateTime<chrono::Utc>>>,
+    pub publish_at: Option<Option<chrono::DateTime<chrono::Utc>>>,
+    pub unpublish_at: Option<Option<chrono::DateTime<chrono::Utc>>>,
+}
+
+impl NotePatchModel {
+    pub fn is_empty(&self) -> bool {
+        self.title.is_none()
+            && self.content.is_none()
+            && self.category.is_none()
+            && self.tags.is_none()
+            && self.published.is_none()
+            && self.notebook_id.is_none()
+            && self.due_at.is_none()
+            && self.remind_at.is_none()
+            && self.publish_at.is_none()
+            && self.unpublish_at.is_none()
+    }
+}
+
+// a note read from an archive, the timestamps are kept when the archive has them
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct NoteImportModel {
+    pub title: String,
+    pub content: String,
+    pub category: Option<String>,
+    pub tags: Vec<String>,
+    pub published: bool,
+    pub created_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub updated_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+// ownership and trash state were already checked by the caller, so every id here is
+// expected to change
+#[derive(Debug, Clone)]
+pub enum NoteBulkUpdate {
+    Delete,
+    Restore,
+    Publish { slugs: Vec<String> },
+    Unpublish,
+    SetCategory { category: String },
+    AddTags { tags: Vec<String> },
+    RemoveTags { tags: Vec<String> },
+    Move { notebook_id: Option<Uuid> },
+}
+
+#[derive(Debug, FromRow, Deserialize, Serialize, Clone)]
+#[allow(non_snake_case)]
+pub struct NoteShareLinkModel {
+    pub id: Uuid,
+    pub note_id: Uuid,
+    pub token: String,
+    #[serde(rename = "expiresAt")]
+    pub expires_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub created_by: Option<Uuid>,
+    #[serde(rename = "createdAt")]
+    pub created_at: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(rename = "deletedAt")]
+    pub deleted_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[allow(non_snake_case)]
+pub struct NoteShareLinkSaveModel {
+    pub note_id: Uuid,
+    pub token: String,
+    pub expires_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub created_by: Option<Uuid>,
+}
@@ -1,9 +1,23 @@
-use crate::modules::notes::model::{NoteModel, NoteSaveModel, NoteUpdateModel};
+use crate::infrastructure::crypto;
+use crate::infrastructure::pagination::Cursor;
+use crate::modules::notes::model::{
+    NoteActivityModel, NoteBulkUpdate, NoteCategoryCountModel, NoteEventModel, NoteFilter,
+    NoteGraphEdge, NoteGraphNode, NoteImportModel, NoteKeysetQuery, NoteLengthModel, NoteLinkModel,
+    NoteModel, NotePatchModel, NoteReencryptBatch, NoteReminderModel, NoteSaveModel,
+    NoteShareLinkModel, NoteShareLinkSaveModel, NoteSortKey, NoteStatsModel, NoteTagCountModel,
+    NoteTotalsModel, NoteUpdateModel,
+};
 use chrono::{DateTime, Utc};
 use sqlx::postgres::PgQueryResult;
-use sqlx::{Error, PgPool};
+use sqlx::{Error, PgPool, Postgres, QueryBuilder, Transaction};
 use uuid::Uuid;
 
+const COMMENT_COUNT_COLUMN: &str = "(SELECT count(c.id) FROM note_comments c WHERE c.note_id = notes.id AND c.deleted_at IS NULL) AS comment_count";
+// the list only ever holds the caller's own notes, so created_by is the caller here
+const FAVORITE_COLUMN: &str = "EXISTS (SELECT 1 FROM note_favorites f WHERE f.note_id = notes.id AND f.user_id = notes.created_by) AS favorite";
+// "3/7 done", NULL for a note without a checklist
+const CHECKLIST_PROGRESS_COLUMN: &str = "(SELECT count(*) FILTER (WHERE i.checked) || '/' || count(*) || ' done' FROM note_checklist_items i WHERE i.note_id = notes.id HAVING count(*) > 0) AS checklist_progress";
+
 #[allow(dead_code)]
 pub async fn get_notes(
     pool: &PgPool,
@@ -27,87 +41,931 @@ pub async fn get_notes(
     query_result
 }
 
+// title and content filters match literally, LIKE wildcards typed by the user are escaped
+fn like_pattern(value: &str) -> String {
+    let escaped = value
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_");
+    format!("%{}%", escaped)
+}
+
+// the WHERE clause shared by the page, count and keyset queries
+fn push_note_filters(builder: &mut QueryBuilder<Postgres>, user_id: Uuid, filter: &NoteFilter) {
+    builder.push(" WHERE deleted_at IS NULL AND");
+    push_note_conditions(builder, user_id, filter);
+}
+
+// the filter conditions without the trash check
+fn push_note_conditions(builder: &mut QueryBuilder<Postgres>, user_id: Uuid, filter: &NoteFilter) {
+    builder.push(" created_by = ");
+    builder.push_bind(user_id);
+
+    if let Some(title) = &filter.title {
+        builder.push(" AND title ILIKE ");
+        builder.push_bind(like_pattern(title));
+    }
+    if let Some(content) = &filter.content {
+        builder.push(" AND content ILIKE ");
+        builder.push_bind(like_pattern(content));
+    }
+    if let Some(categories) = &filter.categories {
+        builder.push(" AND lower(category) = ANY(");
+        builder.push_bind(categories.clone());
+        builder.push(")");
+    }
+    if let Some(category_id) = filter.category_id {
+        builder.push(" AND category_id = ");
+        builder.push_bind(category_id);
+    }
+    if let Some(tags) = &filter.tags {
+        builder.push(" AND tags @> ");
+        builder.push_bind(tags.clone());
+    }
+    if let Some(published) = filter.published {
+        builder.push(" AND COALESCE(published, false) = ");
+        builder.push_bind(published);
+    }
+    if let Some(notebook_ids) = &filter.notebook_ids {
+        builder.push(" AND notebook_id = ANY(");
+        builder.push_bind(notebook_ids.clone());
+        builder.push(")");
+    }
+    if let Some(pinned) = filter.pinned {
+        builder.push(" AND pinned = ");
+        builder.push_bind(pinned);
+    }
+    if let Some(archived) = filter.archived {
+        builder.push(if archived {
+            " AND archived_at IS NOT NULL"
+        } else {
+            " AND archived_at IS NULL"
+        });
+    }
+    if let Some(favorite) = filter.favorite {
+        builder.push(if favorite { " AND " } else { " AND NOT " });
+        builder.push(
+            "EXISTS (SELECT 1 FROM note_favorites f WHERE f.note_id = notes.id AND f.user_id = ",
+        );
+        builder.push_bind(user_id);
+        builder.push(")");
+    }
+    if let Some(open_items) = filter.open_items {
+        builder.push(if open_items { " AND " } else { " AND NOT " });
+        builder.push(
+            "EXISTS (SELECT 1 FROM note_checklist_items i WHERE i.note_id = notes.id AND NOT i.checked)",
+        );
+    }
+    if let Some(created_after) = filter.created_after {
+        builder.push(" AND created_at >= ");
+        builder.push_bind(created_after);
+    }
+    if let Some(created_before) = filter.created_before {
+        builder.push(" AND created_at < ");
+        builder.push_bind(created_before);
+    }
+    if let Some(updated_after) = filter.updated_after {
+        builder.push(" AND updated_at >= ");
+        builder.push_bind(updated_after);
+    }
+    if let Some(updated_before) = filter.updated_before {
+        builder.push(" AND updated_at < ");
+        builder.push_bind(updated_before);
+    }
+    if let Some(due_after) = filter.due_after {
+        builder.push(" AND due_at >= ");
+        builder.push_bind(due_after);
+    }
+    if let Some(due_before) = filter.due_before {
+        builder.push(" AND due_at < ");
+        builder.push_bind(due_before);
+    }
+    if let Some(scheduled) = filter.scheduled {
+        builder.push(if scheduled { " AND " } else { " AND NOT " });
+        builder.push("(publish_at IS NOT NULL OR unpublish_at IS NOT NULL)");
+    }
+}
+
+// pinned notes always lead, the id breaks ties so pages stay stable. `forward` false
+// reverses every key for reading the page before a cursor
+fn push_note_order(builder: &mut QueryBuilder<Postgres>, sort: &[NoteSortKey], forward: bool) {
+    let direction = |descending: bool| {
+        if descending == forward {
+            "DESC"
+        } else {
+            "ASC"
+        }
+    };
+    let mut order_by = format!(" ORDER BY pinned {}", direction(true));
+    for key in sort {
+        order_by.push_str(&format!(
+            ", {} {}",
+            key.field.column(),
+            direction(key.descending)
+        ));
+    }
+    let id_descending = sort.last().is_none_or(|key| key.descending);
+    order_by.push_str(&format!(", id {}", direction(id_descending)));
+    builder.push(order_by);
+}
+
+fn select_notes_user() -> QueryBuilder<'static, Postgres> {
+    QueryBuilder::new(format!(
+        "SELECT notes.*, {}, {}, {} FROM notes",
+        COMMENT_COUNT_COLUMN, FAVORITE_COLUMN, CHECKLIST_PROGRESS_COLUMN
+    ))
+}
+
 pub async fn get_notes_user(
     pool: &PgPool,
-    limit: i32,
-    offset: i32,
-    query: String,
-    query_order: String,
     user_id: Uuid,
+    filter: &NoteFilter,
+    sort: &[NoteSortKey],
+    limit: i64,
+    offset: i64,
 ) -> Result<Vec<NoteModel>, Error> {
-    //build all the query
-    let query_with_order = format!(
-        "SELECT * FROM notes WHERE deleted_at IS NULL AND created_by = $3 {} {} LIMIT $1 OFFSET $2",
-        query, query_order
+    let mut builder = select_notes_user();
+    push_note_filters(&mut builder, user_id, filter);
+    push_note_order(&mut builder, sort, true);
+    builder.push(" LIMIT ");
+    builder.push_bind(limit);
+    builder.push(" OFFSET ");
+    builder.push_bind(offset);
+
+    builder.build_query_as::<NoteModel>().fetch_all(pool).await
+}
+
+// kept up to date by every write, the database can not count the words of encrypted content
+const WORD_COUNT_COLUMN: &str = "word_count::bigint";
+
+// `matched` holds the filtered notes including the trashed ones, `live` the same without them
+fn select_note_stats(user_id: Uuid, filter: &NoteFilter) -> QueryBuilder<'static, Postgres> {
+    let mut builder = QueryBuilder::new("WITH matched AS (SELECT * FROM notes WHERE");
+    push_note_conditions(&mut builder, user_id, filter);
+    builder.push("), live AS (SELECT * FROM matched WHERE deleted_at IS NULL) ");
+    builder
+}
+
+// all parts are read from the same snapshot, so the numbers always add up
+pub async fn get_note_stats(
+    pool: &PgPool,
+    user_id: Uuid,
+    filter: &NoteFilter,
+    from: chrono::NaiveDate,
+    to: chrono::NaiveDate,
+    longest: i64,
+) -> Result<NoteStatsModel, Error> {
+    let mut tx = pool.begin().await?;
+    sqlx::query::<_>("SET TRANSACTION ISOLATION LEVEL REPEATABLE READ, READ ONLY")
+        .execute(&mut tx)
+        .await?;
+
+    let mut builder = select_note_stats(user_id, filter);
+    builder.push(format!(
+        "SELECT count(*) FILTER (WHERE deleted_at IS NULL) AS \"all\", \
+        count(*) FILTER (WHERE deleted_at IS NULL AND COALESCE(published, false)) AS published, \
+        count(*) FILTER (WHERE deleted_at IS NULL AND archived_at IS NOT NULL) AS archived, \
+        count(*) FILTER (WHERE deleted_at IS NOT NULL) AS trashed, \
+        COALESCE(sum({}) FILTER (WHERE deleted_at IS NULL), 0)::bigint AS word_count \
+        FROM matched",
+        WORD_COUNT_COLUMN
+    ));
+    let totals = builder
+        .build_query_as::<NoteTotalsModel>()
+        .fetch_one(&mut tx)
+        .await?;
+
+    let mut builder = select_note_stats(user_id, filter);
+    builder.push(
+        "SELECT c.id, c.name, c.color, count(*) AS count FROM live \
+        JOIN categories c ON c.id = live.category_id \
+        GROUP BY c.id ORDER BY c.sort_order, lower(c.name)",
     );
+    let categories = builder
+        .build_query_as::<NoteCategoryCountModel>()
+        .fetch_all(&mut tx)
+        .await?;
 
-    let query_result = sqlx::query_as::<_, NoteModel>(&query_with_order)
-        .bind(limit)
-        .bind(offset)
-        .bind(user_id)
-        .fetch_all(pool)
-        .await;
+    let mut builder = select_note_stats(user_id, filter);
+    builder.push(
+        "SELECT tag, count(*) AS count FROM live, unnest(live.tags) AS tag \
+        GROUP BY tag ORDER BY count DESC, tag",
+    );
+    let tags = builder
+        .build_query_as::<NoteTagCountModel>()
+        .fetch_all(&mut tx)
+        .await?;
 
-    query_result
+    let mut builder = select_note_stats(user_id, filter);
+    builder.push(
+        ", created AS (SELECT (created_at AT TIME ZONE 'UTC')::date AS date, count(*) AS count FROM live GROUP BY 1), \
+        updated AS (SELECT (updated_at AT TIME ZONE 'UTC')::date AS date, count(*) AS count FROM live WHERE updated_at > created_at GROUP BY 1) \
+        SELECT days.date::date AS date, COALESCE(c.count, 0) AS created, COALESCE(u.count, 0) AS updated \
+        FROM generate_series(",
+    );
+    builder.push_bind(from);
+    builder.push("::date, ");
+    builder.push_bind(to);
+    builder.push(
+        "::date, interval '1 day') AS days(date) \
+        LEFT JOIN created c ON c.date = days.date::date \
+        LEFT JOIN updated u ON u.date = days.date::date \
+        ORDER BY days.date",
+    );
+    let activity = builder
+        .build_query_as::<NoteActivityModel>()
+        .fetch_all(&mut tx)
+        .await?;
+
+    let mut builder = select_note_stats(user_id, filter);
+    builder.push(format!(
+        "SELECT id, title, {} AS word_count FROM live ORDER BY word_count DESC, id LIMIT ",
+        WORD_COUNT_COLUMN
+    ));
+    builder.push_bind(longest);
+    let longest_notes = builder
+        .build_query_as::<NoteLengthModel>()
+        .fetch_all(&mut tx)
+        .await?;
+    tx.commit().await?;
+
+    Ok(NoteStatsModel {
+        totals,
+        categories,
+        tags,
+        activity,
+        longest_notes,
+    })
+}
+
+// key column, its type and whether it is sorted descending
+type KeysetColumn = (&'static str, &'static str, bool);
+
+fn keyset_operator(descending: bool, forward: bool) -> &'static str {
+    if descending == forward {
+        "<"
+    } else {
+        ">"
+    }
+}
+
+// (k1 > v1) OR (k1 = v1 AND ((k2 > v2) OR (k2 = v2 AND id > v3))), with every operator
+// following the direction of its own key so mixed ASC/DESC orders page correctly
+fn push_keyset_condition(
+    builder: &mut QueryBuilder<Postgres>,
+    keys: &[KeysetColumn],
+    values: &[String],
+    cursor: &Cursor,
+    id_descending: bool,
+    forward: bool,
+) {
+    let ((column, cast, descending), (rest, value)) = match (keys.split_first(), values.first()) {
+        (Some((key, rest)), Some(value)) => (key, (rest, value)),
+        _ => {
+            builder.push("id ");
+            builder.push(keyset_operator(id_descending, forward));
+            builder.push(" ");
+            builder.push_bind(cursor.id);
+            return;
+        }
+    };
+
+    builder.push(format!(
+        "({} {} ",
+        column,
+        keyset_operator(*descending, forward)
+    ));
+    builder.push_bind(value.clone());
+    builder.push(format!("::{} OR ({} = ", cast, column));
+    builder.push_bind(value.clone());
+    builder.push(format!("::{} AND ", cast));
+    push_keyset_condition(builder, rest, &values[1..], cursor, id_descending, forward);
+    builder.push("))");
+}
+
+// pages before the cursor are read in reverse order and flipped back by the caller
+pub async fn get_notes_user_keyset(
+    pool: &PgPool,
+    user_id: Uuid,
+    filter: &NoteFilter,
+    keyset: &NoteKeysetQuery,
+) -> Result<Vec<NoteModel>, Error> {
+    let mut keys: Vec<KeysetColumn> = vec![("pinned", "boolean", true)];
+    keys.extend(
+        keyset
+            .sort
+            .iter()
+            .map(|key| (key.field.column(), key.field.cast(), key.descending)),
+    );
+    let id_descending = keyset.sort.last().is_none_or(|key| key.descending);
+
+    let mut builder = select_notes_user();
+    push_note_filters(&mut builder, user_id, filter);
+    if let Some(cursor) = &keyset.cursor {
+        builder.push(" AND ");
+        push_keyset_condition(
+            &mut builder,
+            &keys,
+            &cursor.values,
+            cursor,
+            id_descending,
+            keyset.forward,
+        );
+    }
+    push_note_order(&mut builder, &keyset.sort, keyset.forward);
+    builder.push(" LIMIT ");
+    builder.push_bind(keyset.limit);
+
+    builder.build_query_as::<NoteModel>().fetch_all(pool).await
 }
 
 pub async fn get_notes_count_user(
     pool: &PgPool,
-    query_filter: String,
     user_id: Uuid,
+    filter: &NoteFilter,
 ) -> Result<i64, Error> {
-    // Build the query with placeholders for parameters
-    let final_query = format!(
-        "SELECT count(id) FROM notes WHERE deleted_at IS NULL AND created_by = $1 {}",
-        query_filter
-    );
+    let mut builder: QueryBuilder<Postgres> = QueryBuilder::new("SELECT count(id) FROM notes");
+    push_note_filters(&mut builder, user_id, filter);
 
-    let query_result: Result<i64, Error> = sqlx::query_scalar(&final_query)
-        .bind(user_id)
-        .fetch_one(pool)
-        .await;
+    let (count,): (i64,) = builder.build_query_as().fetch_one(pool).await?;
+    Ok(count)
+}
 
-    query_result
+// notes sealed with a key that is not configured, their content can not be read
+pub async fn get_notes_count_unreadable_user(
+    pool: &PgPool,
+    user_id: Uuid,
+    filter: &NoteFilter,
+) -> Result<i64, Error> {
+    let patterns: Vec<String> = crypto::key_prefixes()
+        .into_iter()
+        .map(|prefix| format!("{}%", prefix))
+        .collect();
+    let mut builder: QueryBuilder<Postgres> = QueryBuilder::new("SELECT count(id) FROM notes");
+    push_note_filters(&mut builder, user_id, filter);
+    builder.push(" AND content LIKE ");
+    builder.push_bind(format!("{}%", crypto::SEALED_PREFIX));
+    builder.push(" AND NOT content LIKE ANY(");
+    builder.push_bind(patterns);
+    builder.push(")");
+
+    let (count,): (i64,) = builder.build_query_as().fetch_one(pool).await?;
+    Ok(count)
 }
 
-pub async fn save_note(pool: &PgPool, body: NoteSaveModel) -> Result<NoteModel, Error> {
-    let query =
-        "INSERT INTO notes (title,content,category,published,created_by) VALUES ($1, $2, $3, $4, $5) RETURNING *";
-    let query_result = sqlx::query_as::<_, NoteModel>(&query)
+const SAVE_NOTE_QUERY: &str = "INSERT INTO notes (title,content,category,tags,notebook_id,published,due_at,remind_at,created_by,publish_at,unpublish_at,scheduled_by,word_count) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, CASE WHEN $10 IS NULL AND $11 IS NULL THEN NULL ELSE $9 END, $12) RETURNING *";
+
+// content is sealed right before it is written. words are counted from the plain text, once
+// sealed the database can not count them anymore
+fn stored_content(content: &str) -> Result<(String, i32), Error> {
+    let sealed = crypto::seal(content).map_err(Error::Protocol)?;
+    Ok((sealed, content.split_whitespace().count() as i32))
+}
+
+// `links` are the titles linked from the content, saved together with the note
+pub async fn save_note(
+    pool: &PgPool,
+    body: NoteSaveModel,
+    links: &[String],
+) -> Result<NoteModel, Error> {
+    let (content, word_count) = stored_content(&body.content)?;
+    let mut tx = pool.begin().await?;
+    let note = sqlx::query_as::<_, NoteModel>(SAVE_NOTE_QUERY)
         .bind(body.title.as_str())
-        .bind(body.content.as_str())
+        .bind(content)
         .bind(body.category.to_owned().unwrap_or("".to_string()))
+        .bind(body.tags)
+        .bind(body.notebook_id)
         .bind(body.published.to_owned().unwrap_or(false))
+        .bind(body.due_at)
+        .bind(body.remind_at)
         .bind(body.created_by.to_owned().unwrap_or(Uuid::default()))
-        .fetch_one(pool)
-        .await;
+        .bind(body.publish_at)
+        .bind(body.unpublish_at)
+        .bind(word_count)
+        .fetch_one(&mut tx)
+        .await?;
+    replace_note_links(&mut tx, note.id, links).await?;
+    tx.commit().await?;
 
-    query_result
+    Ok(note)
+}
+
+// saves the copy together with the checklist of the source note. copied attachments point at
+// the same blob, blobs are never removed so the copies can not lose it
+pub async fn duplicate_note(
+    pool: &PgPool,
+    source_id: Uuid,
+    body: NoteSaveModel,
+    links: &[String],
+    with_attachments: bool,
+) -> Result<NoteModel, Error> {
+    let user_id = body.created_by.unwrap_or_default();
+    let (content, word_count) = stored_content(&body.content)?;
+    let mut tx = pool.begin().await?;
+    let note = sqlx::query_as::<_, NoteModel>(SAVE_NOTE_QUERY)
+        .bind(body.title)
+        .bind(content)
+        .bind(body.category.unwrap_or_default())
+        .bind(body.tags)
+        .bind(body.notebook_id)
+        .bind(body.published.unwrap_or(false))
+        .bind(body.due_at)
+        .bind(body.remind_at)
+        .bind(user_id)
+        .bind(body.publish_at)
+        .bind(body.unpublish_at)
+        .bind(word_count)
+        .fetch_one(&mut tx)
+        .await?;
+    sqlx::query::<_>(
+        "INSERT INTO note_checklist_items (note_id,position,text,checked,created_by) SELECT $1, position, text, checked, $2 FROM note_checklist_items WHERE note_id = $3",
+    )
+    .bind(note.id)
+    .bind(user_id)
+    .bind(source_id)
+    .execute(&mut tx)
+    .await?;
+    if with_attachments {
+        sqlx::query::<_>(
+            "INSERT INTO attachments (id,note_id,owner_id,file_name,content_type,size_bytes,checksum,storage_key) SELECT uuid_generate_v4(), $1, $2, file_name, content_type, size_bytes, checksum, storage_key FROM attachments WHERE note_id = $3 AND deleted_at IS NULL",
+        )
+        .bind(note.id)
+        .bind(user_id)
+        .bind(source_id)
+        .execute(&mut tx)
+        .await?;
+    }
+    replace_note_links(&mut tx, note.id, links).await?;
+    tx.commit().await?;
+
+    Ok(note)
+}
+
+pub async fn save_imported_note(
+    pool: &PgPool,
+    body: NoteImportModel,
+    links: &[String],
+    user_id: Uuid,
+) -> Result<NoteModel, Error> {
+    let (content, word_count) = stored_content(&body.content)?;
+    let query = "INSERT INTO notes (title,content,category,tags,published,created_by,created_at,updated_at,word_count) VALUES ($1, $2, $3, $4, $5, $6, COALESCE($7, NOW()), COALESCE($8, $7, NOW()), $9) RETURNING *";
+    let mut tx = pool.begin().await?;
+    let note = sqlx::query_as::<_, NoteModel>(query)
+        .bind(body.title)
+        .bind(content)
+        .bind(body.category.unwrap_or_default())
+        .bind(body.tags)
+        .bind(body.published)
+        .bind(user_id)
+        .bind(body.created_at)
+        .bind(body.updated_at)
+        .bind(word_count)
+        .fetch_one(&mut tx)
+        .await?;
+    replace_note_links(&mut tx, note.id, links).await?;
+    tx.commit().await?;
+
+    Ok(note)
+}
+
+// an overwrite keeps the note's id, title and slug and replaces what the archive carries
+pub async fn overwrite_imported_note(
+    pool: &PgPool,
+    note_id: Uuid,
+    body: NoteImportModel,
+    links: &[String],
+    user_id: Uuid,
+) -> Result<NoteModel, Error> {
+    let now = Utc::now();
+    let (content, word_count) = stored_content(&body.content)?;
+    let query = "UPDATE notes SET version = version + 1, content = $1, word_count = $8, category = $2, tags = $3, published = $4, updated_by = $5, updated_at = $6 WHERE id = $7 AND created_by = $5 AND deleted_at IS NULL RETURNING *";
+    let mut tx = pool.begin().await?;
+    let note = sqlx::query_as::<_, NoteModel>(query)
+        .bind(content)
+        .bind(body.category.unwrap_or_default())
+        .bind(body.tags)
+        .bind(body.published)
+        .bind(user_id)
+        .bind(now)
+        .bind(note_id)
+        .bind(word_count)
+        .fetch_one(&mut tx)
+        .await?;
+    replace_note_links(&mut tx, note.id, links).await?;
+    tx.commit().await?;
+
+    Ok(note)
 }
 
+// `if_match` holds the versions the caller accepts, a stale one matches no row
 pub async fn update_note(
     pool: &PgPool,
     note_id: Uuid,
     body: NoteUpdateModel,
     note: NoteModel,
+    links: &[String],
+    if_match: Option<&[i32]>,
 ) -> Result<NoteModel, Error> {
     let now = Utc::now();
+    let (content, word_count) = stored_content(&body.content)?;
     let query =
-        "UPDATE notes SET title = $1, content = $2, category = $3, published = $4, updated_by = $5, updated_at = $6 WHERE id = $7 RETURNING *";
-    let query_result = sqlx::query_as::<_, NoteModel>(query)
+        "UPDATE notes SET version = version + 1, title = $1, content = $2, word_count = $14, category = $3, tags = COALESCE($4, tags), published = $5, due_at = COALESCE($10, due_at), remind_at = COALESCE($11, remind_at), reminded_at = CASE WHEN COALESCE($11, remind_at) IS NOT DISTINCT FROM remind_at THEN reminded_at END, reminder_claimed_at = CASE WHEN COALESCE($11, remind_at) IS NOT DISTINCT FROM remind_at THEN reminder_claimed_at END, reminder_retry_at = CASE WHEN COALESCE($11, remind_at) IS NOT DISTINCT FROM remind_at THEN reminder_retry_at END, reminder_attempts = CASE WHEN COALESCE($11, remind_at) IS NOT DISTINCT FROM remind_at THEN reminder_attempts ELSE 0 END, publish_at = COALESCE($12, publish_at), unpublish_at = COALESCE($13, unpublish_at), scheduled_by = CASE WHEN $12 IS NULL AND $13 IS NULL THEN scheduled_by ELSE $6 END, updated_by = $6, updated_at = $7 WHERE id = $8 AND created_by = $6 AND deleted_at IS NULL AND ($9::int[] IS NULL OR version = ANY($9)) RETURNING *";
+    let mut tx = pool.begin().await?;
+    let note = sqlx::query_as::<_, NoteModel>(query)
         .bind(body.title)
-        .bind(body.content)
-        .bind(body.category.unwrap_or(note.category.unwrap()))
-        .bind(body.published.unwrap_or(note.published.unwrap()))
-        .bind(body.updated_by.unwrap_or(note.updated_by.unwrap()))
+        .bind(content)
+        .bind(body.category.or(note.category))
+        .bind(body.tags)
+        .bind(body.published.or(note.published))
+        .bind(body.updated_by.or(note.updated_by))
+        .bind(now)
+        .bind(note_id)
+        .bind(if_match)
+        .bind(body.due_at)
+        .bind(body.remind_at)
+        .bind(body.publish_at)
+        .bind(body.unpublish_at)
+        .bind(word_count)
+        .fetch_one(&mut tx)
+        .await?;
+    replace_note_links(&mut tx, note.id, links).await?;
+    tx.commit().await?;
+
+    Ok(note)
+}
+
+// the SET list is built from the patched fields only, everything else is left untouched.
+// `links` only come along with patched content
+pub async fn patch_note(
+    pool: &PgPool,
+    note_id: Uuid,
+    patch: &NotePatchModel,
+    links: Option<&[String]>,
+    user_id: Uuid,
+    if_match: Option<&[i32]>,
+) -> Result<NoteModel, Error> {
+    let now = Utc::now();
+    let mut builder: QueryBuilder<Postgres> =
+        QueryBuilder::new("UPDATE notes SET version = version + 1, updated_by = ");
+    builder.push_bind(user_id);
+    builder.push(", updated_at = ");
+    builder.push_bind(now);
+    if let Some(title) = &patch.title {
+        builder.push(", title = ");
+        builder.push_bind(title.clone());
+    }
+    if let Some(content) = &patch.content {
+        let (content, word_count) = stored_content(content)?;
+        builder.push(", content = ");
+        builder.push_bind(content);
+        builder.push(", word_count = ");
+        builder.push_bind(word_count);
+    }
+    if let Some(category) = &patch.category {
+        builder.push(", category = ");
+        builder.push_bind(category.clone());
+    }
+    if let Some(tags) = &patch.tags {
+        builder.push(", tags = ");
+        builder.push_bind(tags.clone());
+    }
+    if let Some(published) = patch.published {
+        builder.push(", published = ");
+        builder.push_bind(published);
+    }
+    if let Some(notebook_id) = patch.notebook_id {
+        builder.push(", notebook_id = ");
+        builder.push_bind(notebook_id);
+    }
+    if let Some(due_at) = patch.due_at {
+        builder.push(", due_at = ");
+        builder.push_bind(due_at);
+    }
+    if let Some(remind_at) = patch.remind_at {
+        // a new reminder time starts the reminder over, sent or not
+        for (column, reset) in [
+            ("reminded_at", "NULL"),
+            ("reminder_claimed_at", "NULL"),
+            ("reminder_retry_at", "NULL"),
+            ("reminder_attempts", "0"),
+        ] {
+            builder.push(format!(
+                ", {} = CASE WHEN remind_at IS NOT DISTINCT FROM ",
+                column
+            ));
+            builder.push_bind(remind_at);
+            builder.push(format!(" THEN {} ELSE {} END", column, reset));
+        }
+        builder.push(", remind_at = ");
+        builder.push_bind(remind_at);
+    }
+    if let Some(publish_at) = patch.publish_at {
+        builder.push(", publish_at = ");
+        builder.push_bind(publish_at);
+    }
+    if let Some(unpublish_at) = patch.unpublish_at {
+        builder.push(", unpublish_at = ");
+        builder.push_bind(unpublish_at);
+    }
+    if patch.publish_at.is_some() || patch.unpublish_at.is_some() {
+        builder.push(", scheduled_by = ");
+        builder.push_bind(user_id);
+    }
+    builder.push(" WHERE id = ");
+    builder.push_bind(note_id);
+    builder.push(" AND created_by = ");
+    builder.push_bind(user_id);
+    builder.push(" AND deleted_at IS NULL");
+    if let Some(versions) = if_match {
+        builder.push(" AND version = ANY(");
+        builder.push_bind(versions.to_vec());
+        builder.push(")");
+    }
+    builder.push(" RETURNING *");
+
+    let mut tx = pool.begin().await?;
+    let note = builder
+        .build_query_as::<NoteModel>()
+        .fetch_one(&mut tx)
+        .await?;
+    if let Some(links) = links {
+        replace_note_links(&mut tx, note.id, links).await?;
+    }
+    tx.commit().await?;
+
+    Ok(note)
+}
+
+// the titles out of `titles` that one of the user's live notes already uses
+pub async fn get_live_note_titles_user(
+    tx: &mut Transaction<'_, Postgres>,
+    titles: &[String],
+    user_id: Uuid,
+) -> Result<Vec<String>, Error> {
+    let rows: Vec<(String,)> = sqlx::query_as(
+        "SELECT title FROM notes WHERE title = ANY($1) AND created_by = $2 AND deleted_at IS NULL",
+    )
+    .bind(titles)
+    .bind(user_id)
+    .fetch_all(&mut *tx)
+    .await?;
+    Ok(rows.into_iter().map(|(title,)| title).collect())
+}
+
+// every note whose publish time has come gets published in one statement, a note another
+// instance published in the meantime no longer matches. the change is made in the name of
+// the user who scheduled it
+pub async fn publish_scheduled_notes(pool: &PgPool) -> Result<Vec<NoteModel>, Error> {
+    sqlx::query_as::<_, NoteModel>(
+        "UPDATE notes SET version = version + 1, published = true, publish_at = NULL, updated_by = COALESCE(scheduled_by, created_by), updated_at = NOW() WHERE publish_at <= NOW() AND deleted_at IS NULL RETURNING *",
+    )
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn unpublish_scheduled_notes(pool: &PgPool) -> Result<PgQueryResult, Error> {
+    sqlx::query(
+        "UPDATE notes SET version = version + 1, published = false, unpublish_at = NULL, updated_by = COALESCE(scheduled_by, created_by), updated_at = NOW() WHERE unpublish_at <= NOW() AND deleted_at IS NULL",
+    )
+    .execute(pool)
+    .await
+}
+
+// a claim older than this was left behind by an instance that stopped while sending, it has
+// to outlast a whole batch of sends timing out
+const REMINDER_CLAIM_TIMEOUT: &str = "interval '15 minutes'";
+
+// claims up to `limit` due reminders in one statement, a reminder claimed by another instance
+// is skipped so every reminder goes out once. a reminder waiting for its retry does not hold
+// back the ones after it, one that ran out of attempts is never claimed again
+pub async fn claim_due_reminders(
+    pool: &PgPool,
+    limit: i64,
+    max_attempts: i32,
+) -> Result<Vec<NoteReminderModel>, Error> {
+    let query = format!(
+        "UPDATE notes n SET reminder_claimed_at = NOW(), reminder_attempts = n.reminder_attempts + 1 \
+        FROM users u WHERE u.id = n.created_by AND n.id IN (\
+        SELECT id FROM notes WHERE remind_at <= NOW() AND reminded_at IS NULL AND deleted_at IS NULL \
+        AND reminder_attempts < $2 AND (reminder_retry_at IS NULL OR reminder_retry_at <= NOW()) \
+        AND (reminder_claimed_at IS NULL OR reminder_claimed_at < NOW() - {}) \
+        ORDER BY remind_at LIMIT $1 FOR UPDATE SKIP LOCKED) \
+        RETURNING n.id, n.title, n.due_at, n.remind_at, n.reminder_attempts AS attempts, u.id AS user_id, u.email, u.fullname",
+        REMINDER_CLAIM_TIMEOUT
+    );
+    sqlx::query_as::<_, NoteReminderModel>(&query)
+        .bind(limit)
+        .bind(max_attempts)
+        .fetch_all(pool)
+        .await
+}
+
+// not an edit of the note, so neither the version nor updated_at move. a reminder moved while
+// it was being sent no longer matches and stays pending for its new time
+pub async fn mark_note_reminded(
+    pool: &PgPool,
+    note_id: Uuid,
+    remind_at: DateTime<Utc>,
+) -> Result<PgQueryResult, Error> {
+    sqlx::query(
+        "UPDATE notes SET reminded_at = NOW(), reminder_claimed_at = NULL WHERE id = $1 AND remind_at = $2",
+    )
+    .bind(note_id)
+    .bind(remind_at)
+    .execute(pool)
+    .await
+}
+
+// releases the claim of a reminder that could not be sent, it is tried again once `retry_in`
+// has passed
+pub async fn mark_note_reminder_failed(
+    pool: &PgPool,
+    note_id: Uuid,
+    remind_at: DateTime<Utc>,
+    retry_in: chrono::Duration,
+) -> Result<PgQueryResult, Error> {
+    sqlx::query(
+        "UPDATE notes SET reminder_claimed_at = NULL, reminder_retry_at = NOW() + $3 WHERE id = $1 AND remind_at = $2",
+    )
+    .bind(note_id)
+    .bind(remind_at)
+    .bind(retry_in)
+    .execute(pool)
+    .await
+}
+
+// trashed notes are included so a bulk restore can find them, the rows stay locked until
+// the bulk transaction ends
+pub async fn get_notes_user_by_ids_for_update(
+    tx: &mut Transaction<'_, Postgres>,
+    note_ids: &[Uuid],
+    user_id: Uuid,
+) -> Result<Vec<NoteModel>, Error> {
+    sqlx::query_as::<_, NoteModel>(
+        "SELECT * FROM notes WHERE id = ANY($1) AND created_by = $2 ORDER BY id FOR UPDATE",
+    )
+    .bind(note_ids)
+    .bind(user_id)
+    .fetch_all(&mut *tx)
+    .await
+}
+
+pub async fn bulk_update_notes(
+    tx: &mut Transaction<'_, Postgres>,
+    note_ids: &[Uuid],
+    update: &NoteBulkUpdate,
+    user_id: Uuid,
+) -> Result<i32, Error> {
+    let now: DateTime<Utc> = Utc::now();
+    let query = match update {
+        NoteBulkUpdate::Delete => {
+            // attachments share the note's deleted_at so they can be restored together
+            sqlx::query::<_>(
+                "UPDATE attachments SET deleted_at = $1 WHERE note_id = ANY($2) AND deleted_at IS NULL",
+            )
+            .bind(now)
+            .bind(note_ids)
+            .execute(&mut *tx)
+            .await?;
+            sqlx::query::<_>("UPDATE notes SET version = version + 1, deleted_at = $1 WHERE id = ANY($2)")
+                .bind(now)
+                .bind(note_ids)
+        }
+        NoteBulkUpdate::Restore => {
+            sqlx::query::<_>(
+                "UPDATE attachments a SET deleted_at = NULL FROM notes n WHERE a.note_id = n.id AND a.deleted_at = n.deleted_at AND n.id = ANY($1)",
+            )
+            .bind(note_ids)
+            .execute(&mut *tx)
+            .await?;
+            sqlx::query::<_>("UPDATE notes SET version = version + 1, deleted_at = NULL WHERE id = ANY($1)").bind(note_ids)
+        }
+        // a slug is stable once assigned, so only the notes without one take the new slug
+        NoteBulkUpdate::Publish { slugs } => sqlx::query::<_>(
+            "UPDATE notes SET version = version + 1, published = true, slug = COALESCE(notes.slug, v.slug), updated_by = $3, updated_at = $4 FROM UNNEST($1::uuid[], $2::text[]) AS v(id, slug) WHERE notes.id = v.id",
+        )
+        .bind(note_ids)
+        .bind(slugs)
+        .bind(user_id)
+        .bind(now),
+        NoteBulkUpdate::Unpublish => sqlx::query::<_>(
+            "UPDATE notes SET version = version + 1, published = false, updated_by = $2, updated_at = $3 WHERE id = ANY($1)",
+        )
+        .bind(note_ids)
+        .bind(user_id)
+        .bind(now),
+        NoteBulkUpdate::SetCategory { category } => sqlx::query::<_>(
+            "UPDATE notes SET version = version + 1, category = $2, updated_by = $3, updated_at = $4 WHERE id = ANY($1)",
+        )
+        .bind(note_ids)
+        .bind(category)
+        .bind(user_id)
+        .bind(now),
+        // existing tags keep their position, new ones are appended in the given order
+        NoteBulkUpdate::AddTags { tags } => sqlx::query::<_>(
+            "UPDATE notes SET version = version + 1, tags = ARRAY(SELECT t.tag FROM unnest(notes.tags || $2::text[]) WITH ORDINALITY AS t(tag, position) GROUP BY t.tag ORDER BY min(t.position)), updated_by = $3, updated_at = $4 WHERE id = ANY($1)",
+        )
+        .bind(note_ids)
+        .bind(tags)
+        .bind(user_id)
+        .bind(now),
+        NoteBulkUpdate::RemoveTags { tags } => sqlx::query::<_>(
+            "UPDATE notes SET version = version + 1, tags = ARRAY(SELECT t.tag FROM unnest(notes.tags) WITH ORDINALITY AS t(tag, position) WHERE t.tag <> ALL($2::text[]) ORDER BY t.position), updated_by = $3, updated_at = $4 WHERE id = ANY($1)",
+        )
+        .bind(note_ids)
+        .bind(tags)
+        .bind(user_id)
+        .bind(now),
+        NoteBulkUpdate::Move { notebook_id } => sqlx::query::<_>(
+            "UPDATE notes SET version = version + 1, notebook_id = $2, updated_by = $3, updated_at = $4 WHERE id = ANY($1)",
+        )
+        .bind(note_ids)
+        .bind(notebook_id)
+        .bind(user_id)
+        .bind(now),
+    };
+    let rows_affected: PgQueryResult = query.execute(&mut *tx).await?;
+
+    Ok(rows_affected.rows_affected() as i32)
+}
+
+pub async fn update_note_notebook(
+    pool: &PgPool,
+    note_id: Uuid,
+    notebook_id: Option<Uuid>,
+    user_id: Uuid,
+) -> Result<NoteModel, Error> {
+    let now = Utc::now();
+    let query = "UPDATE notes SET version = version + 1, notebook_id = $1, updated_by = $2, updated_at = $3 WHERE id = $4 AND created_by = $2 RETURNING *";
+    sqlx::query_as::<_, NoteModel>(query)
+        .bind(notebook_id)
+        .bind(user_id)
         .bind(now)
         .bind(note_id)
         .fetch_one(pool)
-        .await;
-    query_result
+        .await
+}
+
+pub async fn update_note_pinned(
+    pool: &PgPool,
+    note_id: Uuid,
+    pinned: bool,
+    user_id: Uuid,
+) -> Result<NoteModel, Error> {
+    let query = "UPDATE notes SET version = version + 1, pinned = $1 WHERE id = $2 AND created_by = $3 RETURNING *";
+    sqlx::query_as::<_, NoteModel>(query)
+        .bind(pinned)
+        .bind(note_id)
+        .bind(user_id)
+        .fetch_one(pool)
+        .await
+}
+
+// archiving again keeps the original archived_at
+pub async fn update_note_archived(
+    pool: &PgPool,
+    note_id: Uuid,
+    archived: bool,
+    user_id: Uuid,
+) -> Result<NoteModel, Error> {
+    let now = Utc::now();
+    let query = "UPDATE notes SET version = version + 1, archived_at = CASE WHEN $1 THEN COALESCE(archived_at, $2) ELSE NULL END WHERE id = $3 AND created_by = $4 RETURNING *";
+    sqlx::query_as::<_, NoteModel>(query)
+        .bind(archived)
+        .bind(now)
+        .bind(note_id)
+        .bind(user_id)
+        .fetch_one(pool)
+        .await
+}
+
+pub async fn save_note_favorite(pool: &PgPool, note_id: Uuid, user_id: Uuid) -> Result<(), Error> {
+    sqlx::query::<_>(
+        "INSERT INTO note_favorites (note_id,user_id) VALUES ($1, $2) ON CONFLICT DO NOTHING",
+    )
+    .bind(note_id)
+    .bind(user_id)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn delete_note_favorite(
+    pool: &PgPool,
+    note_id: Uuid,
+    user_id: Uuid,
+) -> Result<(), Error> {
+    sqlx::query::<_>("DELETE FROM note_favorites WHERE note_id = $1 AND user_id = $2")
+        .bind(note_id)
+        .bind(user_id)
+        .execute(pool)
+        .await?;
+
+    Ok(())
 }
 
-#[allow(dead_code)]
 pub async fn get_note_by_id(pool: &PgPool, note_id: Uuid) -> Result<NoteModel, Error> {
     return sqlx::query_as::<_, NoteModel>(
         "SELECT * FROM notes n where n.deleted_at is null and n.id = $1",
@@ -123,7 +981,7 @@ pub async fn get_note_user_by_id(
     user_id: Uuid,
 ) -> Result<NoteModel, Error> {
     return sqlx::query_as::<_, NoteModel>(
-        "SELECT * FROM notes n where n.deleted_at is null and n.id = $1 and n.created_by = $2",
+        "SELECT n.*, EXISTS (SELECT 1 FROM note_favorites f WHERE f.note_id = n.id AND f.user_id = $2) AS favorite FROM notes n where n.deleted_at is null and n.id = $1 and n.created_by = $2",
     )
     .bind(note_id)
     .bind(user_id)
@@ -131,33 +989,40 @@ pub async fn get_note_user_by_id(
     .await;
 }
 
-pub async fn get_notes_by_title(pool: &PgPool, title: String) -> Result<Vec<NoteModel>, Error> {
-    let query = "SELECT * FROM notes where deleted_at is null and title = $1";
-    return sqlx::query_as::<_, NoteModel>(query)
-        .bind(title)
-        .fetch_all(pool)
-        .await;
+// readable notes are the caller's own notes plus every published note
+pub async fn get_note_readable_by_id(
+    pool: &PgPool,
+    note_id: Uuid,
+    user_id: Uuid,
+) -> Result<NoteModel, Error> {
+    let query = "SELECT * FROM notes n where n.deleted_at is null and n.id = $1 and (n.created_by = $2 or n.published = true)";
+    sqlx::query_as::<_, NoteModel>(query)
+        .bind(note_id)
+        .bind(user_id)
+        .fetch_one(pool)
+        .await
 }
-#[allow(dead_code)]
+
+// titles are unique among the live notes of a single user, trashed notes don't count
 pub async fn get_notes_user_by_title(
     pool: &PgPool,
     title: String,
     user_id: Uuid,
 ) -> Result<Vec<NoteModel>, Error> {
     return sqlx::query_as::<_, NoteModel>(
-        "SELECT * FROM notes n where n.deleted_at is not null and n.title = $1 and n.created_by = $2",
+        "SELECT * FROM notes n where n.deleted_at is null and n.title = $1 and n.created_by = $2",
     )
-        .bind(title)
-        .bind(user_id)
-        .fetch_all(pool)
-        .await;
+    .bind(title)
+    .bind(user_id)
+    .fetch_all(pool)
+    .await;
 }
 
 #[allow(dead_code)]
 pub async fn delete_note_by_id(pool: &PgPool, note_id: Uuid) -> Result<i32, Error> {
     let now: DateTime<Utc> = Utc::now();
     let rows_affected: PgQueryResult =
-        sqlx::query::<_>("UPDATE notes SET deleted_at = $1 WHERE id = $2")
+        sqlx::query::<_>("UPDATE notes SET version = version + 1, deleted_at = $1 WHERE id = $2")
             .bind(now)
             .bind(note_id)
             .execute(pool)
@@ -170,15 +1035,310 @@ pub async fn delete_note_user_by_id(
     pool: &PgPool,
     note_id: Uuid,
     user_id: Uuid,
+    if_match: Option<&[i32]>,
 ) -> Result<i32, Error> {
     let now: DateTime<Utc> = Utc::now();
-    let rows_affected: PgQueryResult =
-        sqlx::query::<_>("UPDATE notes SET deleted_at = $1 WHERE id = $2 AND created_by = $3")
-            .bind(now)
+    let mut tx = pool.begin().await?;
+    let rows_affected: PgQueryResult = sqlx::query::<_>(
+        "UPDATE notes SET version = version + 1, deleted_at = $1 WHERE id = $2 AND created_by = $3 AND ($4::int[] IS NULL OR version = ANY($4))",
+    )
+    .bind(now)
+    .bind(note_id)
+    .bind(user_id)
+    .bind(if_match)
+    .execute(&mut tx)
+    .await?;
+    if rows_affected.rows_affected() == 0 {
+        return Ok(0);
+    }
+
+    // attachments share the note's deleted_at so they can be restored together
+    sqlx::query::<_>(
+        "UPDATE attachments SET deleted_at = $1 WHERE note_id = $2 AND deleted_at IS NULL",
+    )
+    .bind(now)
+    .bind(note_id)
+    .execute(&mut tx)
+    .await?;
+    tx.commit().await?;
+
+    Ok(rows_affected.rows_affected() as i32)
+}
+
+pub async fn update_note_slug(
+    pool: &PgPool,
+    note_id: Uuid,
+    slug: String,
+) -> Result<NoteModel, Error> {
+    // a slug is stable once assigned, so only fill it when it is still empty
+    let query = "UPDATE notes SET slug = COALESCE(slug, $1) WHERE id = $2 RETURNING *";
+    sqlx::query_as::<_, NoteModel>(query)
+        .bind(slug)
+        .bind(note_id)
+        .fetch_one(pool)
+        .await
+}
+
+// seals one batch of notes that are not sealed with the active key yet, trashed ones too.
+// version and updated_at stay as they are and no events are announced, nothing changed for
+// anyone reading the notes
+pub async fn reencrypt_notes(
+    pool: &PgPool,
+    after: Option<Uuid>,
+    limit: i64,
+) -> Result<NoteReencryptBatch, Error> {
+    let mut batch = NoteReencryptBatch::default();
+    let Some(prefix) = crypto::active_prefix() else {
+        return Ok(batch);
+    };
+    let mut tx = pool.begin().await?;
+    sqlx::query::<_>("SET LOCAL notes.skip_events = 'on'")
+        .execute(&mut tx)
+        .await?;
+    let notes: Vec<(Uuid, String)> = sqlx::query_as(
+        "SELECT id, content FROM notes WHERE content NOT LIKE $1 AND ($2::uuid IS NULL OR id > $2) ORDER BY id LIMIT $3 FOR UPDATE SKIP LOCKED",
+    )
+    .bind(format!("{}%", prefix))
+    .bind(after)
+    .bind(limit)
+    .fetch_all(&mut tx)
+    .await?;
+    for (note_id, stored) in &notes {
+        batch.last_id = Some(*note_id);
+        // sealing what can not be opened would lock it away for good, it is left as it is
+        let Ok(content) = crypto::open(stored) else {
+            batch.skipped.push(*note_id);
+            continue;
+        };
+        let (content, word_count) = stored_content(&content)?;
+        sqlx::query::<_>("UPDATE notes SET content = $1, word_count = $2 WHERE id = $3")
+            .bind(content)
+            .bind(word_count)
             .bind(note_id)
-            .bind(user_id)
-            .execute(pool)
+            .execute(&mut tx)
             .await?;
+        batch.sealed += 1;
+    }
+    tx.commit().await?;
+
+    Ok(batch)
+}
+
+// writes back what was edited in a collaborative session, as long as nothing else changed the
+// note since `version`
+pub async fn update_note_content(
+    pool: &PgPool,
+    note_id: Uuid,
+    content: &str,
+    links: &[String],
+    user_id: Uuid,
+    version: Option<i32>,
+) -> Result<NoteModel, Error> {
+    let now: DateTime<Utc> = Utc::now();
+    let (content, word_count) = stored_content(content)?;
+    let query =
+        "UPDATE notes SET content = $1, word_count = $5, updated_at = $2, updated_by = $3, version = version + 1 \
+        WHERE id = $4 AND created_by = $3 AND deleted_at IS NULL AND version IS NOT DISTINCT FROM $6 RETURNING *";
+    let mut tx = pool.begin().await?;
+    let note = sqlx::query_as::<_, NoteModel>(query)
+        .bind(content)
+        .bind(now)
+        .bind(user_id)
+        .bind(note_id)
+        .bind(word_count)
+        .bind(version)
+        .fetch_one(&mut tx)
+        .await?;
+    replace_note_links(&mut tx, note.id, links).await?;
+    tx.commit().await?;
+
+    Ok(note)
+}
+
+pub async fn get_published_note_by_slug(pool: &PgPool, slug: String) -> Result<NoteModel, Error> {
+    let query =
+        "SELECT * FROM notes n where n.deleted_at is null and n.published = true and n.slug = $1";
+    sqlx::query_as::<_, NoteModel>(query)
+        .bind(slug)
+        .fetch_one(pool)
+        .await
+}
+
+// a published note only gets a page once it has a slug
+pub async fn get_published_notes_user(
+    pool: &PgPool,
+    user_id: Uuid,
+    limit: i64,
+) -> Result<Vec<NoteModel>, Error> {
+    let query = "SELECT * FROM notes n WHERE n.deleted_at IS NULL AND n.published = true AND n.slug IS NOT NULL AND n.created_by = $1 ORDER BY n.updated_at DESC, n.id LIMIT $2";
+    sqlx::query_as::<_, NoteModel>(query)
+        .bind(user_id)
+        .bind(limit)
+        .fetch_all(pool)
+        .await
+}
+
+pub async fn save_share_link(
+    pool: &PgPool,
+    body: NoteShareLinkSaveModel,
+) -> Result<NoteShareLinkModel, Error> {
+    let query = "INSERT INTO note_share_links (note_id,token,expires_at,created_by) VALUES ($1, $2, $3, $4) RETURNING *";
+    sqlx::query_as::<_, NoteShareLinkModel>(query)
+        .bind(body.note_id)
+        .bind(body.token)
+        .bind(body.expires_at)
+        .bind(body.created_by)
+        .fetch_one(pool)
+        .await
+}
+
+pub async fn get_share_links_by_note_id(
+    pool: &PgPool,
+    note_id: Uuid,
+) -> Result<Vec<NoteShareLinkModel>, Error> {
+    let query = "SELECT * FROM note_share_links s where s.deleted_at is null and s.note_id = $1 ORDER BY s.created_at DESC";
+    sqlx::query_as::<_, NoteShareLinkModel>(query)
+        .bind(note_id)
+        .fetch_all(pool)
+        .await
+}
+
+pub async fn get_share_link_by_token(
+    pool: &PgPool,
+    token: String,
+) -> Result<NoteShareLinkModel, Error> {
+    let query = "SELECT * FROM note_share_links s where s.deleted_at is null and s.token = $1";
+    sqlx::query_as::<_, NoteShareLinkModel>(query)
+        .bind(token)
+        .fetch_one(pool)
+        .await
+}
+
+pub async fn delete_share_link_by_id(
+    pool: &PgPool,
+    link_id: Uuid,
+    note_id: Uuid,
+) -> Result<i32, Error> {
+    let now: DateTime<Utc> = Utc::now();
+    let rows_affected: PgQueryResult = sqlx::query::<_>(
+        "UPDATE note_share_links SET deleted_at = $1 WHERE id = $2 AND note_id = $3 AND deleted_at IS NULL",
+    )
+    .bind(now)
+    .bind(link_id)
+    .bind(note_id)
+    .execute(pool)
+    .await?;
 
     Ok(rows_affected.rows_affected() as i32)
 }
+
+// runs inside the transaction that writes the content the links were parsed from
+async fn replace_note_links(
+    tx: &mut Transaction<'_, Postgres>,
+    note_id: Uuid,
+    titles: &[String],
+) -> Result<(), Error> {
+    sqlx::query("DELETE FROM note_links WHERE source_id = $1")
+        .bind(note_id)
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query(
+        "INSERT INTO note_links (source_id, target_title) SELECT $1, unnest($2::varchar[])",
+    )
+    .bind(note_id)
+    .bind(titles)
+    .execute(&mut *tx)
+    .await?;
+    Ok(())
+}
+
+// links only resolve to the live notes of the note's author
+pub async fn get_note_links(
+    pool: &PgPool,
+    note_id: Uuid,
+    user_id: Uuid,
+) -> Result<Vec<NoteLinkModel>, Error> {
+    let query = "SELECT l.target_title AS title, t.id FROM note_links l \
+        LEFT JOIN notes t ON t.title = l.target_title AND t.created_by = $2 AND t.deleted_at IS NULL \
+        WHERE l.source_id = $1 ORDER BY l.target_title";
+    sqlx::query_as::<_, NoteLinkModel>(query)
+        .bind(note_id)
+        .bind(user_id)
+        .fetch_all(pool)
+        .await
+}
+
+pub async fn get_note_backlinks(
+    pool: &PgPool,
+    note: &NoteModel,
+    user_id: Uuid,
+) -> Result<Vec<NoteModel>, Error> {
+    let query = "SELECT n.* FROM notes n JOIN note_links l ON l.source_id = n.id \
+        WHERE l.target_title = $1 AND n.created_by = $2 AND n.deleted_at IS NULL AND n.id <> $3 \
+        ORDER BY n.updated_at DESC NULLS LAST, n.id";
+    sqlx::query_as::<_, NoteModel>(query)
+        .bind(&note.title)
+        .bind(user_id)
+        .bind(note.id)
+        .fetch_all(pool)
+        .await
+}
+
+pub async fn get_note_graph_nodes(
+    pool: &PgPool,
+    user_id: Uuid,
+) -> Result<Vec<NoteGraphNode>, Error> {
+    let query =
+        "SELECT id, title FROM notes WHERE created_by = $1 AND deleted_at IS NULL ORDER BY title";
+    sqlx::query_as::<_, NoteGraphNode>(query)
+        .bind(user_id)
+        .fetch_all(pool)
+        .await
+}
+
+pub async fn get_note_graph_edges(
+    pool: &PgPool,
+    user_id: Uuid,
+) -> Result<Vec<NoteGraphEdge>, Error> {
+    let query = "SELECT s.id AS source, t.id AS target FROM note_links l \
+        JOIN notes s ON s.id = l.source_id \
+        JOIN notes t ON t.title = l.target_title AND t.created_by = s.created_by AND t.deleted_at IS NULL \
+        WHERE s.created_by = $1 AND s.deleted_at IS NULL AND t.id <> s.id \
+        ORDER BY s.id, t.id";
+    sqlx::query_as::<_, NoteGraphEdge>(query)
+        .bind(user_id)
+        .fetch_all(pool)
+        .await
+}
+
+// the events of the log after `last_id` the user can see, oldest first
+pub async fn get_note_events_after(
+    pool: &PgPool,
+    last_id: i64,
+    user_id: Uuid,
+) -> Result<Vec<NoteEventModel>, Error> {
+    let query = "SELECT * FROM note_events WHERE id > $1 AND (user_id = $2 OR public) ORDER BY id";
+    sqlx::query_as::<_, NoteEventModel>(query)
+        .bind(last_id)
+        .bind(user_id)
+        .fetch_all(pool)
+        .await
+}
+
+pub async fn get_all_note_events_after(
+    pool: &PgPool,
+    last_id: i64,
+) -> Result<Vec<NoteEventModel>, Error> {
+    let query = "SELECT * FROM note_events WHERE id > $1 ORDER BY id";
+    sqlx::query_as::<_, NoteEventModel>(query)
+        .bind(last_id)
+        .fetch_all(pool)
+        .await
+}
+
+// the oldest and the latest id still in the event log
+pub async fn get_note_event_bounds(pool: &PgPool) -> Result<(Option<i64>, Option<i64>), Error> {
+    sqlx::query_as("SELECT MIN(id), MAX(id) FROM note_events")
+        .fetch_one(pool)
+        .await
+}
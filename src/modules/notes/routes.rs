@@ -6,9 +6,42 @@ pub fn routes(conf: &mut web::ServiceConfig) {
         .service(handler::health_checker_handler)
         .service(handler::note_list_handler)
         .service(handler::create_note_handler)
+        .service(handler::render_note_handler)
+        .service(handler::bulk_note_handler)
+        .service(handler::export_notes_handler)
+        .service(handler::import_notes_handler)
+        .service(handler::note_events_handler)
+        .service(handler::note_graph_handler)
+        .service(handler::note_stats_handler)
         .service(handler::get_note_handler)
         .service(handler::edit_note_handler)
-        .service(handler::delete_note_handler);
+        .service(handler::patch_note_handler)
+        .service(handler::delete_note_handler)
+        .service(handler::note_backlinks_handler)
+        .service(handler::note_collab_handler)
+        .service(handler::move_note_handler)
+        .service(handler::duplicate_note_handler)
+        .service(handler::pin_note_handler)
+        .service(handler::unpin_note_handler)
+        .service(handler::archive_note_handler)
+        .service(handler::unarchive_note_handler)
+        .service(handler::favorite_note_handler)
+        .service(handler::unfavorite_note_handler)
+        .service(handler::create_share_link_handler)
+        .service(handler::share_link_list_handler)
+        .service(handler::delete_share_link_handler);
+
+    conf.service(scope);
+}
+
+pub fn public_routes(conf: &mut web::ServiceConfig) {
+    let scope = web::scope("/public")
+        .service(handler::public_note_handler)
+        .service(handler::public_note_view_handler)
+        .service(handler::shared_note_handler)
+        .service(handler::shared_note_view_handler)
+        .service(handler::user_atom_feed_handler)
+        .service(handler::user_rss_feed_handler);
 
     conf.service(scope);
 }
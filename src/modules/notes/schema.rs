@@ -1,15 +1,140 @@
-use serde::{Deserialize, Serialize};
+use crate::modules::notes::model::{NoteGraphEdge, NoteGraphNode, NoteModel};
+use serde::{Deserialize, Deserializer, Serialize};
+use uuid::Uuid;
 use validator::ValidationError;
 use validator_derive::Validate;
 
+// paging and sorting come in through `PaginationQuery`, this only holds the filters
 #[derive(Deserialize, Debug)]
 pub struct FilterOptions {
-    pub page: Option<usize>,
-    pub limit: Option<usize>,
     pub title: Option<String>,
     pub content: Option<String>,
-    pub sort_by: Option<String>,
-    pub sort_order: Option<String>,
+    pub category: Option<String>,
+    pub category_id: Option<Uuid>,
+    pub tag: Option<String>,
+    pub published: Option<bool>,
+    pub notebook_id: Option<Uuid>,
+    pub recursive: Option<bool>,
+    pub pinned: Option<bool>,
+    pub archived: Option<bool>,
+    pub favorite: Option<bool>,
+    pub has_open_items: Option<bool>,
+    pub created_after: Option<chrono::DateTime<chrono::Utc>>,
+    pub created_before: Option<chrono::DateTime<chrono::Utc>>,
+    pub updated_after: Option<chrono::DateTime<chrono::Utc>>,
+    pub updated_before: Option<chrono::DateTime<chrono::Utc>>,
+    pub due: Option<DueFilter>,
+    pub scheduled: Option<bool>,
+}
+
+// `due=overdue` keeps the notes whose due date has passed, `due=today` the ones due during
+// the current UTC day
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DueFilter {
+    Overdue,
+    Today,
+}
+
+// the activity range is in whole UTC days with both ends included, by default the last 30
+// days. `longest` is how many of the longest notes are listed
+#[derive(Deserialize, Debug)]
+pub struct StatsOptions {
+    pub from: Option<chrono::NaiveDate>,
+    pub to: Option<chrono::NaiveDate>,
+    pub longest: Option<i64>,
+}
+
+#[derive(Debug, Clone)]
+pub struct NoteCursorPage {
+    pub notes: Vec<NoteModel>,
+    pub size: i32,
+    pub next_cursor: Option<String>,
+    pub prev_cursor: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct FormatOptions {
+    pub format: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    #[default]
+    Markdown,
+    Json,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ExportOptions {
+    pub format: Option<ExportFormat>,
+}
+
+// what an import does with a note whose title is already taken
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportConflict {
+    #[default]
+    Skip,
+    Rename,
+    Overwrite,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ImportOptions {
+    pub on_conflict: Option<ImportConflict>,
+}
+
+// the YAML front matter of an exported markdown file
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct NoteFrontMatter {
+    pub title: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub published: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct NoteArchiveEntry {
+    #[serde(flatten)]
+    pub front_matter: NoteFrontMatter,
+    pub content: String,
+}
+
+// the single document written by a json export
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct NoteArchive {
+    pub notes: Vec<NoteArchiveEntry>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum NoteImportStatus {
+    Created,
+    Renamed,
+    Overwritten,
+    Skipped,
+    Failed,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct NoteImportResult {
+    pub file: String,
+    pub status: NoteImportStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<Uuid>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
 }
 
 #[derive(Deserialize, Debug)]
@@ -27,7 +152,22 @@ pub struct CreateNoteSchema {
     #[validate(custom = "validate_category")]
     pub category: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub category_id: Option<Uuid>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[validate(custom = "validate_tags")]
+    pub tags: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notebook_id: Option<Uuid>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub published: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub due_at: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remind_at: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub publish_at: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unpublish_at: Option<chrono::DateTime<chrono::Utc>>,
 }
 
 #[derive(Deserialize, Serialize, Debug, Validate)]
@@ -40,7 +180,140 @@ pub struct UpdateNoteSchema {
     #[validate(custom = "validate_category")]
     pub category: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub category_id: Option<Uuid>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[validate(custom = "validate_tags")]
+    pub tags: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub published: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub due_at: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remind_at: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub publish_at: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unpublish_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+// RFC 7396 merge patch: an absent field is kept, an explicit null clears it. Title and
+// content cannot be cleared, tags and published fall back to their defaults. changing
+// remind_at arms the reminder again
+#[derive(Deserialize, Serialize, Debug, Validate)]
+pub struct PatchNoteSchema {
+    #[serde(default, deserialize_with = "double_option")]
+    #[validate(length(min = 1, max = 255))]
+    pub title: Option<Option<String>>,
+    #[serde(default, deserialize_with = "double_option")]
+    #[validate(length(min = 1))]
+    pub content: Option<Option<String>>,
+    #[serde(default, deserialize_with = "double_option")]
+    #[validate(custom = "validate_category")]
+    pub category: Option<Option<String>>,
+    #[serde(default, deserialize_with = "double_option")]
+    pub category_id: Option<Option<Uuid>>,
+    #[serde(default, deserialize_with = "double_option")]
+    #[validate(custom = "validate_tags")]
+    pub tags: Option<Option<Vec<String>>>,
+    #[serde(default, deserialize_with = "double_option")]
+    pub published: Option<Option<bool>>,
+    #[serde(default, deserialize_with = "double_option")]
+    pub notebook_id: Option<Option<Uuid>>,
+    #[serde(default, deserialize_with = "double_option")]
+    pub due_at: Option<Option<chrono::DateTime<chrono::Utc>>>,
+    #[serde(default, deserialize_with = "double_option")]
+    pub remind_at: Option<Option<chrono::DateTime<chrono::Utc>>>,
+    #[serde(default, deserialize_with = "double_option")]
+    pub publish_at: Option<Option<chrono::DateTime<chrono::Utc>>>,
+    #[serde(default, deserialize_with = "double_option")]
+    pub unpublish_at: Option<Option<chrono::DateTime<chrono::Utc>>>,
+}
+
+// a missing or null notebook_id moves the note back to the root
+#[derive(Deserialize, Serialize, Debug)]
+pub struct MoveNoteSchema {
+    pub notebook_id: Option<Uuid>,
+}
+
+// the action is flattened into the body, e.g. {"ids": [...], "action": "set_category", "category": "work"}
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum BulkNoteAction {
+    Delete,
+    Restore,
+    Publish,
+    Unpublish,
+    SetCategory { category: String },
+    AddTags { tags: Vec<String> },
+    RemoveTags { tags: Vec<String> },
+    Move { notebook_id: Option<Uuid> },
+}
+
+#[derive(Deserialize, Serialize, Debug, Validate)]
+#[validate(schema(function = "validate_bulk_action"))]
+pub struct BulkNoteSchema {
+    #[validate(length(min = 1, max = 100))]
+    pub ids: Vec<Uuid>,
+    #[serde(flatten)]
+    pub action: BulkNoteAction,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct NoteGraphResponse {
+    pub nodes: Vec<NoteGraphNode>,
+    pub edges: Vec<NoteGraphEdge>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct BulkNoteFailure {
+    pub id: Uuid,
+    pub reason: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct BulkNoteResponse {
+    pub succeeded: Vec<Uuid>,
+    pub failed: Vec<BulkNoteFailure>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Validate)]
+pub struct RenderNoteSchema {
+    #[validate(length(max = 1048576))]
+    pub content: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct RenderedNoteResponse {
+    pub html: String,
+}
+
+#[derive(Deserialize, Serialize, Debug, Validate)]
+pub struct CreateShareLinkSchema {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[validate(range(min = 1, max = 8760))]
+    pub expires_in_hours: Option<i64>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[allow(non_snake_case)]
+pub struct PublicNoteResponse {
+    pub title: String,
+    pub content: String,
+    pub category: Option<String>,
+    pub slug: Option<String>,
+    #[serde(rename = "createdAt")]
+    pub created_at: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(rename = "updatedAt")]
+    pub updated_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+// tells an absent field (None) apart from an explicit null (Some(None))
+fn double_option<'de, T, D>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
 }
 
 fn validate_title(title: &str) -> Result<(), ValidationError> {
@@ -62,9 +335,33 @@ fn validate_content(content: &str) -> Result<(), ValidationError> {
 }
 
 fn validate_category(category: &str) -> Result<(), ValidationError> {
-    if category.len() < 1 {
+    if category.trim().is_empty() || category.trim().chars().count() > 100 {
+        return Err(ValidationError::new(
+            "category must have a length between 1 and 100 characters",
+        ));
+    }
+    Ok(())
+}
+
+// a category set in bulk is held to the same rules as one set on a single note
+fn validate_bulk_action(schema: &BulkNoteSchema) -> Result<(), ValidationError> {
+    match &schema.action {
+        BulkNoteAction::SetCategory { category } => validate_category(category),
+        _ => Ok(()),
+    }
+}
+
+// templates hand their tags on to notes, so they are held to the same rules
+pub fn validate_tags(tags: &[String]) -> Result<(), ValidationError> {
+    if tags.len() > 20 {
+        return Err(ValidationError::new("a note can have at most 20 tags"));
+    }
+    if tags
+        .iter()
+        .any(|tag| tag.trim().is_empty() || tag.trim().chars().count() > 50)
+    {
         return Err(ValidationError::new(
-            "category must have a minimum length of 1 characters",
+            "tags must have a length between 1 and 50 characters",
         ));
     }
     Ok(())
@@ -1,64 +1,230 @@
+use crate::infrastructure::archive::{self, ArchiveFile, ZipStream};
+use crate::infrastructure::collab::{CollabHub, CollabPeer, CollabSnapshot};
+use crate::infrastructure::crypto;
+use crate::infrastructure::events::EventHub;
+use crate::infrastructure::feed::{Feed, FeedEntry};
+use crate::infrastructure::html;
+use crate::infrastructure::markdown::{self, RenderCache};
+use crate::infrastructure::notifier::{Notifier, Reminder};
+use crate::infrastructure::pagination::{self, Cursor, PaginationQuery};
+use crate::modules::categories::constants as category_constants;
+use crate::modules::categories::service as category_service;
+use crate::modules::notebooks::service as notebook_service;
 use crate::modules::notes::constants;
-use crate::modules::notes::model::{NoteModel, NoteSaveModel, NoteUpdateModel};
+use crate::modules::notes::model::{
+    NoteBulkUpdate, NoteEventModel, NoteFilter, NoteImportModel, NoteKeysetQuery, NoteModel,
+    NotePatchModel, NoteSaveModel, NoteShareLinkModel, NoteShareLinkSaveModel, NoteSortField,
+    NoteSortKey, NoteStatsModel, NoteUpdateModel,
+};
 use crate::modules::notes::repository;
-use crate::modules::notes::schema::{CreateNoteSchema, FilterOptions, UpdateNoteSchema};
+use crate::modules::notes::schema::{
+    BulkNoteAction, BulkNoteFailure, BulkNoteResponse, BulkNoteSchema, CreateNoteSchema,
+    CreateShareLinkSchema, DueFilter, ExportFormat, FilterOptions, FormatOptions, ImportConflict,
+    MoveNoteSchema, NoteArchive, NoteArchiveEntry, NoteCursorPage, NoteFrontMatter,
+    NoteGraphResponse, NoteImportResult, NoteImportStatus, PatchNoteSchema, PublicNoteResponse,
+    RenderNoteSchema, RenderedNoteResponse, StatsOptions, UpdateNoteSchema,
+};
+use crate::modules::users::service as user_service;
+use crate::utils::utils;
+use actix_web::rt::time::timeout;
+use actix_web::web::Bytes;
+use actix_ws::{MessageStream, Session};
+use chrono::{DateTime, Duration, DurationRound, SecondsFormat, Utc};
+use futures::channel::mpsc;
+use futures::stream::{self, LocalBoxStream};
+use futures::{SinkExt, StreamExt};
+use regex::Regex;
+use sha2::{Digest, Sha256};
+use sqlx::postgres::PgListener;
+use sqlx::types::Json;
 use sqlx::{Error, PgPool};
+use std::io;
+use std::sync::{Arc, LazyLock};
+use std::time::Duration as StdDuration;
 use uuid::Uuid;
 
-pub async fn get_notes_service(
-    pool: &PgPool,
-    filter: &FilterOptions,
-    user_id: Uuid,
-) -> (Result<Vec<NoteModel>, String>, u16) {
-    //unwrap the filter value
-    let filter_option: &FilterOptions = filter;
-    let limit = filter_option.limit.unwrap_or(10);
-    let offset = (filter_option.page.unwrap_or(1) - 1) * limit.clone();
+const MAX_SORT_KEYS: usize = 5;
+const MAX_TAGS: usize = 20;
+const MAX_TAG_LENGTH: usize = 50;
+const MAX_TITLE_LENGTH: usize = 255;
+const MAX_CATEGORY_LENGTH: usize = 100;
+const EXPORT_BATCH_SIZE: i64 = 100;
+pub const IMPORT_MAX_SIZE: usize = 20 * 1024 * 1024;
+const IMPORT_MAX_FILES: usize = 1000;
+const IMPORT_MAX_FILE_SIZE: u64 = 2 * 1024 * 1024;
+// a json export holds every exported note in a single document
+const IMPORT_MAX_DOCUMENT_SIZE: u64 = 64 * 1024 * 1024;
+const JSON_ARCHIVE_FILE: &str = "notes.json";
+const COLLAB_SAVE_INTERVAL: StdDuration = StdDuration::from_secs(10);
+const COLLAB_MAX_FRAME_SIZE: usize = 1024 * 1024;
+const COLLAB_MAX_MESSAGE_SIZE: usize = 8 * 1024 * 1024;
+const NOTE_EVENTS_CHANNEL: &str = "note_events";
+const NOTE_EVENTS_KEEP_ALIVE: StdDuration = StdDuration::from_secs(15);
+const NOTE_EVENTS_RETRY: StdDuration = StdDuration::from_secs(5);
+const REMINDER_INTERVAL: StdDuration = StdDuration::from_secs(30);
+const REMINDER_BATCH_SIZE: i64 = 50;
+const REMINDER_MAX_ATTEMPTS: i32 = 5;
+const REMINDER_FIRST_RETRY_MINUTES: i64 = 1;
+const REENCRYPT_BATCH_SIZE: i64 = 100;
+const PUBLISH_SCHEDULE_INTERVAL: StdDuration = StdDuration::from_secs(30);
+const FEED_SIZE: i64 = 20;
+const STATS_DEFAULT_DAYS: i64 = 30;
+const STATS_MAX_DAYS: i64 = 366;
+const STATS_DEFAULT_LONGEST: i64 = 5;
+const STATS_MAX_LONGEST: i64 = 50;
 
-    // Build the query conditionally
-    let mut query = String::new();
+pub type NoteExportStream = mpsc::Receiver<Result<Bytes, io::Error>>;
+pub type NoteEventStream = LocalBoxStream<'static, Result<Bytes, io::Error>>;
 
-    // Title condition
-    if let Some(title) = &filter_option.title {
-        if !title.is_empty() {
-            let query_with_param = format!("AND title ILIKE '%{}%'", title);
-            query.push_str(&*query_with_param);
+fn non_empty(value: &Option<String>) -> Option<String> {
+    value
+        .as_ref()
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+// tags are compared case-insensitively, so they are stored trimmed, lowercased and once
+pub fn normalize_tags(tags: &[String]) -> Vec<String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for tag in tags {
+        let tag = tag.trim().to_lowercase();
+        if !tag.is_empty() && !normalized.contains(&tag) {
+            normalized.push(tag);
         }
     }
+    normalized
+}
 
-    // Content condition
-    if let Some(content) = &filter_option.content {
-        if !content.is_empty() {
-            let query_with_param = format!("AND content ILIKE '%{}%'", content);
-            query.push_str(&*query_with_param);
-        }
+// turns the query parameters into bound filter values, resolving the notebook tree
+async fn build_notes_filter(
+    pool: &PgPool,
+    filter_option: &FilterOptions,
+    user_id: Uuid,
+) -> Result<NoteFilter, String> {
+    // Content condition, encrypted content can not be matched by the database so the filter
+    // is refused instead of quietly matching nothing
+    if crypto::enabled() && non_empty(&filter_option.content).is_some() {
+        return Err(constants::PARAMETER_CONTENT_SEARCH_UNAVAILABLE.to_string());
     }
 
-    // Sort condition
-    let mut query_order = String::new();
-    if let (Some(sort_by), Some(sort_order)) = (&filter_option.sort_by, &filter_option.sort_order) {
-        if !sort_by.is_empty() && !sort_order.is_empty() {
-            query_order.push_str(" ORDER BY ");
-            query_order.push_str(sort_by);
-            if sort_order.to_uppercase() == "ASC" || sort_order.to_uppercase() == "DESC" {
-                query_order.push(' ');
-                query_order.push_str(sort_order);
-            }
+    // Notebook condition, a recursive filter takes every nested notebook along
+    let notebook_ids = match filter_option.notebook_id {
+        Some(notebook_id) => Some(
+            notebook_service::get_notebook_ids_service(
+                pool,
+                notebook_id,
+                filter_option.recursive.unwrap_or(false),
+                user_id,
+            )
+            .await?,
+        ),
+        None => None,
+    };
+
+    // Category condition, several categories can be given comma separated and the case of
+    // a name does not matter
+    let categories = non_empty(&filter_option.category).map(|category| {
+        category
+            .split(',')
+            .map(|category| category.trim().to_lowercase())
+            .filter(|category| !category.is_empty())
+            .collect()
+    });
+
+    // Tag condition, a note has to carry every comma separated tag
+    let tags = non_empty(&filter_option.tag).map(|tag| {
+        normalize_tags(
+            &tag.split(',')
+                .map(|tag| tag.to_string())
+                .collect::<Vec<String>>(),
+        )
+    });
+
+    // Due condition, today is the current UTC day
+    let now = Utc::now();
+    let (due_after, due_before) = match filter_option.due {
+        Some(DueFilter::Overdue) => (None, Some(now)),
+        Some(DueFilter::Today) => {
+            let today = now.duration_trunc(Duration::days(1)).unwrap_or(now);
+            (Some(today), Some(today + Duration::days(1)))
         }
-    } else {
-        // Default sorting if sort_by is empty
-        query_order.push_str(" ORDER BY created_at DESC");
+        None => (None, None),
+    };
+
+    Ok(NoteFilter {
+        title: non_empty(&filter_option.title),
+        content: non_empty(&filter_option.content),
+        categories,
+        category_id: filter_option.category_id,
+        tags,
+        published: filter_option.published,
+        notebook_ids,
+        pinned: filter_option.pinned,
+        // archived notes are hidden unless they are asked for
+        archived: Some(filter_option.archived.unwrap_or(false)),
+        favorite: filter_option.favorite,
+        open_items: filter_option.has_open_items,
+        created_after: filter_option.created_after,
+        created_before: filter_option.created_before,
+        updated_after: filter_option.updated_after,
+        updated_before: filter_option.updated_before,
+        due_after,
+        due_before,
+        scheduled: filter_option.scheduled,
+    })
+}
+
+// `order_by=title:asc,created_at` sorts on several whitelisted fields, a key without its
+// own direction takes `sort_order`
+fn parse_note_sort(paging: &PaginationQuery) -> Result<Vec<NoteSortKey>, String> {
+    let default_descending = pagination::get_sort_order(paging.clone()) == "desc";
+    let mut sort: Vec<NoteSortKey> = Vec::new();
+    for key in pagination::get_order_by(paging.clone()).split(',') {
+        let (field, direction) = match key.trim().split_once(':') {
+            Some((field, direction)) => (field.trim(), Some(direction.trim())),
+            None => (key.trim(), None),
+        };
+        let descending = match direction.map(|direction| direction.to_lowercase()) {
+            None => default_descending,
+            Some(direction) if direction == "asc" => false,
+            Some(direction) if direction == "desc" => true,
+            Some(_) => return Err(constants::PARAMETER_SORT_INVALID.to_string()),
+        };
+        let field = match NoteSortField::parse(field) {
+            Some(field) => field,
+            None => return Err(constants::PARAMETER_SORT_INVALID.to_string()),
+        };
+        if sort.iter().any(|key| key.field == field) {
+            return Err(constants::PARAMETER_SORT_INVALID.to_string());
+        }
+        sort.push(NoteSortKey { field, descending });
+    }
+    if sort.len() > MAX_SORT_KEYS {
+        return Err(constants::PARAMETER_SORT_INVALID.to_string());
     }
+    Ok(sort)
+}
 
-    let result_note: Result<Vec<NoteModel>, Error> = repository::get_notes_user(
-        pool,
-        limit as i32,
-        offset as i32,
-        query.clone(),
-        query_order,
-        user_id,
-    )
-    .await;
+pub async fn get_notes_service(
+    pool: &PgPool,
+    filter: &FilterOptions,
+    paging: &PaginationQuery,
+    user_id: Uuid,
+) -> (Result<Vec<NoteModel>, String>, i64) {
+    let sort = match parse_note_sort(paging) {
+        Ok(sort) => sort,
+        Err(err) => return (Err(err), 0),
+    };
+    let note_filter = match build_notes_filter(pool, filter, user_id).await {
+        Ok(note_filter) => note_filter,
+        Err(err) => return (Err(err), 0),
+    };
+
+    let limit = pagination::get_size(paging.clone());
+    let offset = pagination::get_offset(paging.clone());
+    let result_note: Result<Vec<NoteModel>, Error> =
+        repository::get_notes_user(pool, user_id, &note_filter, &sort, i64::from(limit), offset)
+            .await;
 
     let notes = match result_note {
         Ok(notes) => Ok(notes),
@@ -71,14 +237,125 @@ pub async fn get_notes_service(
     };
 
     let result_note_total_count =
-        repository::get_notes_count_user(pool, query.clone(), user_id).await;
+        repository::get_notes_count_user(pool, user_id, &note_filter).await;
 
     let total_count_note = match result_note_total_count {
         Ok(total_count_note) => total_count_note,
         _ => 0,
     };
 
-    (notes, total_count_note as u16)
+    (notes, total_count_note)
+}
+
+fn note_sort_value(note: &NoteModel, field: NoteSortField) -> String {
+    let timestamp = |value: Option<DateTime<Utc>>| {
+        value
+            .unwrap_or_default()
+            .to_rfc3339_opts(SecondsFormat::Micros, true)
+    };
+    match field {
+        NoteSortField::CreatedAt => timestamp(note.created_at),
+        NoteSortField::UpdatedAt => timestamp(note.updated_at.or(note.created_at)),
+        NoteSortField::Title => note.title.clone(),
+        NoteSortField::Category => note.category.clone().unwrap_or_default(),
+        NoteSortField::Published => note.published.unwrap_or(false).to_string(),
+    }
+}
+
+// the canonical form of the sort a cursor was handed out with
+fn note_sort_spec(sort: &[NoteSortKey]) -> String {
+    sort.iter()
+        .map(|key| {
+            let direction = if key.descending { "desc" } else { "asc" };
+            format!("{}:{}", key.field.name(), direction)
+        })
+        .collect::<Vec<String>>()
+        .join(",")
+}
+
+fn note_cursor_value(note: &NoteModel, sort: &[NoteSortKey]) -> Cursor {
+    let mut values = vec![note.pinned.unwrap_or(false).to_string()];
+    values.extend(sort.iter().map(|key| note_sort_value(note, key.field)));
+    Cursor {
+        sort: note_sort_spec(sort),
+        values,
+        id: note.id,
+    }
+}
+
+fn note_cursor(note: &NoteModel, sort: &[NoteSortKey]) -> String {
+    pagination::encode_cursor(&note_cursor_value(note, sort))
+}
+
+// a cursor only makes sense for the sort it was handed out with
+fn decode_note_cursor(value: &str, sort: &[NoteSortKey]) -> Result<Cursor, String> {
+    match pagination::decode_cursor(value) {
+        Some(cursor)
+            if cursor.sort == note_sort_spec(sort) && cursor.values.len() == sort.len() + 1 =>
+        {
+            Ok(cursor)
+        }
+        _ => Err(constants::PARAMETER_CURSOR_INVALID.to_string()),
+    }
+}
+
+pub async fn get_notes_cursor_service(
+    pool: &PgPool,
+    filter: &FilterOptions,
+    paging: &PaginationQuery,
+    user_id: Uuid,
+) -> Result<NoteCursorPage, String> {
+    let limit = pagination::get_size(paging.clone()) as usize;
+    let sort = parse_note_sort(paging)?;
+
+    let (cursor, forward) = match (&paging.after, &paging.before) {
+        (Some(_), Some(_)) => return Err(constants::PARAMETER_CURSOR_INVALID.to_string()),
+        (Some(after), None) => (Some(decode_note_cursor(after, &sort)?), true),
+        (None, Some(before)) => (Some(decode_note_cursor(before, &sort)?), false),
+        (None, None) => (None, true),
+    };
+    let has_cursor = cursor.is_some();
+    // one extra row tells whether there is anything beyond this page
+    let keyset = NoteKeysetQuery {
+        sort,
+        cursor,
+        forward,
+        limit: limit as i64 + 1,
+    };
+
+    let note_filter = build_notes_filter(pool, filter, user_id).await?;
+
+    let mut notes =
+        match repository::get_notes_user_keyset(pool, user_id, &note_filter, &keyset).await {
+            Ok(notes) => notes,
+            Err(err) => {
+                eprintln!("error get list notes by cursor: {:?}", err);
+                return Err(constants::NOTE_CANT_BE_FETCHED.to_string());
+            }
+        };
+    let has_more = notes.len() > limit;
+    notes.truncate(limit);
+    if !forward {
+        notes.reverse();
+    }
+
+    let first_cursor = notes.first().map(|note| note_cursor(note, &keyset.sort));
+    let last_cursor = notes.last().map(|note| note_cursor(note, &keyset.sort));
+    let (next_cursor, prev_cursor) = if forward {
+        (
+            last_cursor.filter(|_| has_more),
+            first_cursor.filter(|_| has_cursor),
+        )
+    } else {
+        (last_cursor, first_cursor.filter(|_| has_more))
+    };
+
+    Ok(NoteCursorPage {
+        notes,
+        size: limit as i32,
+        next_cursor,
+        prev_cursor,
+    })
 }
 
 pub async fn get_note_detail_service(
@@ -107,51 +384,609 @@ pub async fn get_note_detail_service(
     }
 }
 
+// the detail of a note with its `[[title]]` links resolved to the ids of the user's notes
+pub async fn get_note_detail_with_links_service(
+    pool: &PgPool,
+    note_id: Uuid,
+    user_id: Uuid,
+) -> Result<NoteModel, String> {
+    let mut note = get_note_detail_service(pool, note_id, user_id).await?;
+    match repository::get_note_links(pool, note_id, user_id).await {
+        Ok(links) => {
+            note.links = Some(Json(links));
+            Ok(note)
+        }
+        Err(err) => {
+            eprintln!("error get note links {:?}", err);
+            Err(constants::NOTE_LINKS_CANT_BE_FETCHED.to_string())
+        }
+    }
+}
+
+// the user's notes linking to this one
+pub async fn get_note_backlinks_service(
+    pool: &PgPool,
+    note_id: Uuid,
+    user_id: Uuid,
+) -> Result<Vec<NoteModel>, String> {
+    let note = get_owned_note(pool, note_id, user_id).await?;
+    match repository::get_note_backlinks(pool, &note, user_id).await {
+        Ok(notes) => Ok(notes),
+        Err(err) => {
+            eprintln!("error get note backlinks {:?}", err);
+            Err(constants::NOTE_LINKS_CANT_BE_FETCHED.to_string())
+        }
+    }
+}
+
+// every live note of the user as a node and every resolved link between them as an edge,
+// links to titles without a note are left out
+pub async fn get_note_graph_service(
+    pool: &PgPool,
+    user_id: Uuid,
+) -> Result<NoteGraphResponse, String> {
+    let graph_error = |err: Error| {
+        eprintln!("error get note graph {:?}", err);
+        constants::NOTE_LINKS_CANT_BE_FETCHED.to_string()
+    };
+    let nodes = repository::get_note_graph_nodes(pool, user_id)
+        .await
+        .map_err(graph_error)?;
+    let edges = repository::get_note_graph_edges(pool, user_id)
+        .await
+        .map_err(graph_error)?;
+    Ok(NoteGraphResponse { nodes, edges })
+}
+
+// the stats take the same filters as the notes list, except that archived notes are counted
+// unless `archived` is given. how many of them there are is in the totals either way
+pub async fn get_note_stats_service(
+    pool: &PgPool,
+    filter_option: &FilterOptions,
+    options: &StatsOptions,
+    user_id: Uuid,
+) -> Result<NoteStatsModel, String> {
+    let to = options.to.unwrap_or_else(|| Utc::now().date_naive());
+    let from = options
+        .from
+        .unwrap_or(to - Duration::days(STATS_DEFAULT_DAYS - 1));
+    if from > to || (to - from).num_days() >= STATS_MAX_DAYS {
+        return Err(constants::PARAMETER_STATS_RANGE_INVALID.to_string());
+    }
+    let longest = options.longest.unwrap_or(STATS_DEFAULT_LONGEST);
+    if !(1..=STATS_MAX_LONGEST).contains(&longest) {
+        return Err(constants::PARAMETER_LONGEST_INVALID.to_string());
+    }
+    let mut filter = build_notes_filter(pool, filter_option, user_id).await?;
+    filter.archived = filter_option.archived;
+
+    match repository::get_note_stats(pool, user_id, &filter, from, to, longest).await {
+        Ok(stats) => Ok(stats),
+        Err(err) => {
+            eprintln!("error get note stats {:?}", err);
+            Err(constants::NOTE_STATS_CANT_BE_FETCHED.to_string())
+        }
+    }
+}
+
+// opens the collaborative document of a note, seeded from the saved content when nobody is
+// editing it yet. only the owner can join, the same as for editing the note over REST
+pub async fn join_collab_service(
+    pool: &PgPool,
+    hub: &CollabHub,
+    note_id: Uuid,
+    user_id: Uuid,
+) -> Result<CollabPeer, String> {
+    let _saves = hub.lock_saves().await;
+    let note = get_owned_note(pool, note_id, user_id).await?;
+    ensure_content_readable(&note)?;
+    match hub.join(note.id, &note.content, note.version, user_id) {
+        Ok(peer) => Ok(peer),
+        Err(err) => {
+            eprintln!("Error join collaborative session: {:?}", err);
+            Err(constants::NOTE_COLLAB_CANT_BE_JOINED.to_string())
+        }
+    }
+}
+
+pub async fn collab_session_service(
+    pool: PgPool,
+    hub: Arc<CollabHub>,
+    peer: CollabPeer,
+    session: Session,
+    stream: MessageStream,
+) {
+    let note_id = peer.note_id;
+    let stream = stream
+        .max_frame_size(COLLAB_MAX_FRAME_SIZE)
+        .aggregate_continuations()
+        .max_continuation_size(COLLAB_MAX_MESSAGE_SIZE);
+    if hub.serve(peer, session, stream).await {
+        // the last client left, save what it typed and close the room
+        let _saves = hub.lock_saves().await;
+        if let Some(snapshot) = hub.take_dirty(note_id) {
+            save_collab_snapshot(&pool, &hub, snapshot).await;
+        }
+        hub.close_idle();
+    }
+}
+
+// runs for the lifetime of the server, saving the rooms edited since the previous round
+pub async fn collab_autosave_service(pool: PgPool, hub: Arc<CollabHub>) {
+    let mut interval = actix_web::rt::time::interval(COLLAB_SAVE_INTERVAL);
+    loop {
+        interval.tick().await;
+        let _saves = hub.lock_saves().await;
+        for snapshot in hub.take_all_dirty() {
+            save_collab_snapshot(&pool, &hub, snapshot).await;
+        }
+        hub.close_idle();
+    }
+}
+
+async fn save_collab_snapshot(pool: &PgPool, hub: &CollabHub, snapshot: CollabSnapshot) {
+    let note = match repository::update_note_content(
+        pool,
+        snapshot.note_id,
+        &snapshot.content,
+        &parse_note_links(&snapshot.content),
+        snapshot.editor,
+        snapshot.version,
+    )
+    .await
+    {
+        Ok(note) => note,
+        // either the note was deleted while it was being edited and there is nothing left to
+        // save, or it was changed over REST since the room was seeded. that change is kept and
+        // the room starts over from it, the same as a REST write with a stale If-Match
+        Err(Error::RowNotFound) => {
+            if let Ok(note) = get_owned_note(pool, snapshot.note_id, snapshot.editor).await {
+                // a room seeded from unreadable content would save it back over the note
+                if note.content_unreadable {
+                    return;
+                }
+                if let Err(err) = hub.reseed(note.id, &note.content, note.version) {
+                    eprintln!("Error reseed collaborative note: {:?}", err);
+                }
+            }
+            return;
+        }
+        Err(err) => {
+            eprintln!("Error save collaborative note: {:?}", err);
+            hub.mark_dirty(snapshot.note_id);
+            return;
+        }
+    };
+    hub.mark_saved(note.id, note.version);
+}
+
+fn note_event_frame(event: &NoteEventModel) -> Bytes {
+    let data = serde_json::to_string(event).unwrap_or_default();
+    Bytes::from(format!(
+        "id: {}\nevent: {}\ndata: {}\n\n",
+        event.id, event.event, data
+    ))
+}
+
+// the server-sent events of the notes the user can see. with a Last-Event-ID the events logged
+// since then are replayed first, and a `reset` event tells the client that some of them were
+// already dropped from the log so it has to fetch its notes again
+pub async fn note_events_service(
+    pool: &PgPool,
+    hub: &EventHub<NoteEventModel>,
+    last_event_id: Option<i64>,
+    user_id: Uuid,
+) -> Result<NoteEventStream, String> {
+    let events_error = |err: Error| {
+        eprintln!("error get note events {:?}", err);
+        constants::NOTE_EVENTS_CANT_BE_FETCHED.to_string()
+    };
+    // subscribe before reading the log so nothing published in between is missed
+    let live = hub.subscribe(user_id);
+
+    let mut backlog: Vec<Result<Bytes, io::Error>> = Vec::new();
+    let mut last_sent = 0;
+    if let Some(last_event_id) = last_event_id {
+        let (oldest, _) = repository::get_note_event_bounds(pool)
+            .await
+            .map_err(events_error)?;
+        if oldest.is_some_and(|oldest| last_event_id + 1 < oldest) {
+            backlog.push(Ok(Bytes::from_static(b"event: reset\ndata: {}\n\n")));
+        }
+        let events = repository::get_note_events_after(pool, last_event_id, user_id)
+            .await
+            .map_err(events_error)?;
+        last_sent = events.last().map_or(last_event_id, |event| event.id);
+        backlog.extend(events.iter().map(|event| Ok(note_event_frame(event))));
+    }
+
+    // a comment line now and then keeps proxies from closing an idle stream
+    let live = stream::unfold(live, move |mut live| async move {
+        loop {
+            return match timeout(NOTE_EVENTS_KEEP_ALIVE, live.next()).await {
+                Ok(Some(event)) if event.id <= last_sent => continue,
+                Ok(Some(event)) => Some((Ok(note_event_frame(&event)), live)),
+                Ok(None) => None,
+                Err(_) => Some((Ok(Bytes::from_static(b": keep-alive\n\n")), live)),
+            };
+        }
+    });
+    Ok(stream::iter(backlog).chain(live).boxed_local())
+}
+
+// runs for the lifetime of the server, handing the events announced by any instance to the
+// clients connected to this one
+pub async fn note_events_listener_service(pool: PgPool, hub: Arc<EventHub<NoteEventModel>>) {
+    let mut last_id: Option<i64> = None;
+    loop {
+        if let Err(err) = listen_note_events(&pool, &hub, &mut last_id).await {
+            eprintln!("Error listen note events: {:?}", err);
+            actix_web::rt::time::sleep(NOTE_EVENTS_RETRY).await;
+        }
+    }
+}
+
+async fn listen_note_events(
+    pool: &PgPool,
+    hub: &EventHub<NoteEventModel>,
+    last_id: &mut Option<i64>,
+) -> Result<(), Error> {
+    let mut listener = PgListener::connect_with(pool).await?;
+    loop {
+        // LISTEN first, then read back what was announced while nobody was listening
+        listener.listen(NOTE_EVENTS_CHANNEL).await?;
+        match *last_id {
+            Some(id) => {
+                for event in repository::get_all_note_events_after(pool, id).await? {
+                    *last_id = Some(event.id);
+                    hub.publish(&event, |user_id| event.visible_to(user_id));
+                }
+            }
+            None => {
+                *last_id = Some(
+                    repository::get_note_event_bounds(pool)
+                        .await?
+                        .1
+                        .unwrap_or(0),
+                )
+            }
+        }
+
+        while let Some(notification) = listener.try_recv().await? {
+            match serde_json::from_str::<NoteEventModel>(notification.payload()) {
+                Ok(event) => {
+                    *last_id = (*last_id).max(Some(event.id));
+                    hub.publish(&event, |user_id| event.visible_to(user_id));
+                }
+                Err(err) => eprintln!("Error parse note event: {:?}", err),
+            }
+        }
+    }
+}
+
+// runs for the lifetime of the server on every instance. publishing goes first, so a note
+// whose whole publish window has already passed ends up unpublished
+pub async fn publish_scheduler_service(pool: PgPool) {
+    let mut interval = actix_web::rt::time::interval(PUBLISH_SCHEDULE_INTERVAL);
+    loop {
+        interval.tick().await;
+        match repository::publish_scheduled_notes(&pool).await {
+            Ok(notes) => {
+                for note in notes {
+                    if let Err(err) = ensure_note_slug(&pool, note).await {
+                        eprintln!("Error save scheduled note slug: {:?}", err);
+                    }
+                }
+            }
+            Err(err) => eprintln!("Error publish scheduled notes: {:?}", err),
+        }
+        if let Err(err) = repository::unpublish_scheduled_notes(&pool).await {
+            eprintln!("Error unpublish scheduled notes: {:?}", err);
+        }
+    }
+}
+
+// run by hand after the active encryption key changed, batches are committed one by one so
+// it can be stopped and started again at any point. returns how many notes got sealed and the
+// ones that could not be opened, those need their key configured and another run
+pub async fn reencrypt_notes_service(pool: &PgPool) -> Result<(u64, Vec<Uuid>), String> {
+    if !crypto::enabled() {
+        return Err(constants::NOTE_ENCRYPTION_NOT_CONFIGURED.to_string());
+    }
+    let mut sealed = 0;
+    let mut skipped = Vec::new();
+    let mut after = None;
+    loop {
+        match repository::reencrypt_notes(pool, after, REENCRYPT_BATCH_SIZE).await {
+            Ok(batch) if batch.last_id.is_none() => return Ok((sealed, skipped)),
+            Ok(batch) => {
+                sealed += batch.sealed;
+                skipped.extend(batch.skipped);
+                after = batch.last_id;
+            }
+            Err(err) => {
+                eprintln!("error reencrypt notes {:?}", err);
+                return Err(constants::NOTES_CANT_BE_REENCRYPTED.to_string());
+            }
+        }
+    }
+}
+
+// runs for the lifetime of the server on every instance. a reminder is claimed before it is
+// sent, so two instances polling at the same time never both send it
+pub async fn reminder_scheduler_service(pool: PgPool, notifier: Arc<dyn Notifier>) {
+    let mut interval = actix_web::rt::time::interval(REMINDER_INTERVAL);
+    loop {
+        interval.tick().await;
+        // a batch sent in full may have left more reminders behind
+        loop {
+            match send_due_reminders(&pool, notifier.as_ref()).await {
+                Ok(claimed) if claimed == REMINDER_BATCH_SIZE => continue,
+                Ok(_) => break,
+                Err(err) => {
+                    eprintln!("Error send note reminders: {:?}", err);
+                    break;
+                }
+            }
+        }
+    }
+}
+
+// sending happens outside of any transaction, the note can be edited meanwhile. a reminder
+// that could not be sent is tried again after a wait that doubles with every attempt, up to
+// REMINDER_MAX_ATTEMPTS. returns how many reminders were claimed
+async fn send_due_reminders(pool: &PgPool, notifier: &dyn Notifier) -> Result<i64, Error> {
+    let claimed =
+        repository::claim_due_reminders(pool, REMINDER_BATCH_SIZE, REMINDER_MAX_ATTEMPTS).await?;
+    let count = claimed.len() as i64;
+    for due in claimed {
+        let attempts = due.attempts;
+        let reminder = Reminder {
+            note_id: due.id,
+            title: due.title,
+            due_at: due.due_at,
+            remind_at: due.remind_at,
+            user_id: due.user_id,
+            email: due.email,
+            fullname: due.fullname,
+        };
+        if let Err(err) = notifier.notify(&reminder).await {
+            eprintln!(
+                "Error send reminder of note {} (attempt {} of {}): {:?}",
+                reminder.note_id, attempts, REMINDER_MAX_ATTEMPTS, err
+            );
+            let retry_in =
+                Duration::minutes(REMINDER_FIRST_RETRY_MINUTES << (attempts - 1).clamp(0, 10));
+            repository::mark_note_reminder_failed(
+                pool,
+                reminder.note_id,
+                reminder.remind_at,
+                retry_in,
+            )
+            .await?;
+            continue;
+        }
+        repository::mark_note_reminded(pool, reminder.note_id, reminder.remind_at).await?;
+    }
+    Ok(count)
+}
+
+pub async fn get_readable_note_service(
+    pool: &PgPool,
+    note_id: Uuid,
+    user_id: Uuid,
+) -> Result<NoteModel, String> {
+    match repository::get_note_readable_by_id(pool, note_id, user_id).await {
+        Ok(note) => Ok(note),
+        Err(err) => match err {
+            Error::RowNotFound => Err(constants::NOTE_NOT_FOUND.to_string()),
+            _ => {
+                eprintln!("error get readable note {:?}", err);
+                Err(constants::DETAIL_NOTE_CANT_BE_FETCHED.to_string())
+            }
+        },
+    }
+}
+
+// an unpublish time before the publish time would take the note down before it went up
+fn check_publish_schedule(
+    publish_at: Option<DateTime<Utc>>,
+    unpublish_at: Option<DateTime<Utc>>,
+) -> Result<(), String> {
+    match (publish_at, unpublish_at) {
+        (Some(publish_at), Some(unpublish_at)) if unpublish_at <= publish_at => {
+            Err(constants::NOTE_SCHEDULE_INVALID.to_string())
+        }
+        _ => Ok(()),
+    }
+}
+
+// a category_id wins over a category name. a name has to be one of the owner's categories,
+// matched without regard to case, so a typo does not end up as a category of its own. an
+// empty name takes the note out of its category. only the name is written, the database links
+// the note to the category of that name
+async fn note_category(
+    pool: &PgPool,
+    category: &Option<String>,
+    category_id: Option<Uuid>,
+    user_id: Uuid,
+) -> Result<Option<String>, String> {
+    let category = match category_id {
+        Some(category_id) => {
+            category_service::get_category_service(pool, category_id, user_id).await?
+        }
+        None => match category.as_deref().map(str::trim) {
+            Some(name) if !name.is_empty() => {
+                category_service::get_category_by_name_service(pool, name, user_id).await?
+            }
+            name => return Ok(name.map(str::to_string)),
+        },
+    };
+    Ok(Some(category.name))
+}
+
+// a note copied from someone else keeps its category only when the caller has one by that name
+async fn own_category(
+    pool: &PgPool,
+    category: &Option<String>,
+    user_id: Uuid,
+) -> Result<Option<String>, String> {
+    match note_category(pool, category, None, user_id).await {
+        Err(err) if err == category_constants::CATEGORY_NOT_FOUND => Ok(None),
+        result => result,
+    }
+}
+
+// the category trigger refuses a name the owner has no category for. unlike a foreign key it
+// names no constraint
+fn is_missing_category(err: &Error) -> bool {
+    match err {
+        Error::Database(err) => {
+            err.code().as_deref() == Some("23503") && err.constraint().is_none()
+        }
+        _ => false,
+    }
+}
+
 pub async fn save_note_service(
     pool: &PgPool,
     body: &CreateNoteSchema,
     user_id: Uuid,
 ) -> Result<NoteModel, String> {
-    let existing_notes: Result<Vec<NoteModel>, Error> =
-        repository::get_notes_by_title(pool, body.title.to_string()).await;
-    if let Err(err) = existing_notes {
-        // Handle the error
-        eprintln!("Error getting existing notes: {:?}", err);
-        let error_message = constants::EXISTING_NOTE_CANT_BE_FETCHED;
-        return Err(error_message.parse().unwrap());
-    }
-    if let Ok(notes) = existing_notes {
-        if !notes.is_empty() {
-            // Title already exists, handle the error
-            eprintln!("Note with title {:?} already exists", body.title);
-            let error_message = constants::NOTE_TITLE_ALREADY_EXIST;
-            return Err(error_message.parse().unwrap());
-        }
+    check_publish_schedule(body.publish_at, body.unpublish_at)?;
+    ensure_note_title_free(pool, &body.title, None, user_id).await?;
+    let category = note_category(pool, &body.category, body.category_id, user_id).await?;
+
+    if let Some(notebook_id) = body.notebook_id {
+        notebook_service::get_notebook_service(pool, notebook_id, user_id).await?;
     }
 
     let note_save_model = NoteSaveModel {
         title: body.title.to_string(),
         content: body.content.to_string(),
-        category: body.category.as_ref().map(|c| c.to_string()),
+        category,
+        tags: body.tags.as_deref().map(normalize_tags).unwrap_or_default(),
+        notebook_id: body.notebook_id,
         published: body.published.map(|c| c),
+        due_at: body.due_at,
+        remind_at: body.remind_at,
+        publish_at: body.publish_at,
+        unpublish_at: body.unpublish_at,
         created_by: Option::from(user_id),
     };
 
-    match repository::save_note(pool, note_save_model.clone()).await {
-        Ok(note) => Ok(note),
+    let links = parse_note_links(&note_save_model.content);
+    let note = match repository::save_note(pool, note_save_model, &links).await {
+        Ok(note) => note,
+        Err(err) if is_missing_category(&err) => {
+            return Err(category_constants::CATEGORY_NOT_FOUND.to_string());
+        }
+        // another request took the title after the check above
+        Err(Error::Database(err)) if err.code().as_deref() == Some("23505") => {
+            return Err(constants::NOTE_TITLE_ALREADY_EXIST.to_string());
+        }
         Err(err) => {
             // Handle the error
             eprintln!("Error saving note: {:?}", err);
             let error_message = constants::NOTE_CANT_BE_SAVED;
-            Err(error_message.parse().unwrap())
+            return Err(error_message.parse().unwrap());
+        }
+    };
+
+    ensure_note_slug(pool, note).await
+}
+
+// copies a note the caller can read into a new unpublished note of their own. the notebook
+// and the attachments only come along when the caller owns the source note
+pub async fn duplicate_note_service(
+    pool: &PgPool,
+    note_id: Uuid,
+    user_id: Uuid,
+) -> Result<NoteModel, String> {
+    let source = get_readable_note_service(pool, note_id, user_id).await?;
+    ensure_content_readable(&source)?;
+    let owned = source.created_by == Some(user_id);
+    let category = if owned {
+        source.category
+    } else {
+        own_category(pool, &source.category, user_id).await?
+    };
+
+    let title = match free_note_title(pool, &source.title, duplicate_title_suffix, user_id).await {
+        Ok(title) => title,
+        Err(err) => {
+            eprintln!("Error getting existing notes: {:?}", err);
+            return Err(constants::EXISTING_NOTE_CANT_BE_FETCHED.to_string());
+        }
+    };
+    let note_save_model = NoteSaveModel {
+        title,
+        content: source.content,
+        category,
+        tags: source.tags.unwrap_or_default(),
+        notebook_id: source.notebook_id.filter(|_| owned),
+        published: Some(false),
+        due_at: source.due_at,
+        // the copy would otherwise remind about the same thing a second time
+        remind_at: None,
+        publish_at: None,
+        unpublish_at: None,
+        created_by: Some(user_id),
+    };
+
+    let links = parse_note_links(&note_save_model.content);
+    let note = match repository::duplicate_note(pool, note_id, note_save_model, &links, owned).await
+    {
+        Ok(note) => note,
+        Err(err) if is_missing_category(&err) => {
+            return Err(category_constants::CATEGORY_NOT_FOUND.to_string());
+        }
+        // another request took the title after it was picked
+        Err(Error::Database(err)) if err.code().as_deref() == Some("23505") => {
+            return Err(constants::NOTE_TITLE_ALREADY_EXIST.to_string());
+        }
+        Err(err) => {
+            eprintln!("Error duplicating note: {:?}", err);
+            return Err(constants::NOTE_CANT_BE_DUPLICATED.to_string());
+        }
+    };
+
+    ensure_note_slug(pool, note).await
+}
+
+// content that could not be opened is read as empty, writing the note back would lose it
+fn ensure_content_readable(note: &NoteModel) -> Result<(), String> {
+    if note.content_unreadable {
+        return Err(constants::NOTE_CONTENT_UNREADABLE.to_string());
+    }
+    Ok(())
+}
+
+// titles are unique per user among live notes, `note_id` is the note being renamed
+async fn ensure_note_title_free(
+    pool: &PgPool,
+    title: &str,
+    note_id: Option<Uuid>,
+    user_id: Uuid,
+) -> Result<(), String> {
+    let notes = match repository::get_notes_user_by_title(pool, title.to_string(), user_id).await {
+        Ok(notes) => notes,
+        Err(err) => {
+            eprintln!("Error getting existing notes: {:?}", err);
+            return Err(constants::EXISTING_NOTE_CANT_BE_FETCHED.to_string());
         }
+    };
+    if notes.iter().any(|note| Some(note.id) != note_id) {
+        return Err(constants::NOTE_TITLE_ALREADY_EXIST.to_string());
     }
+    Ok(())
 }
 
 pub async fn update_note_service(
     pool: &PgPool,
     note_id: Uuid,
     body: &UpdateNoteSchema,
+    if_match: Option<&[i32]>,
     user_id: Uuid,
 ) -> Result<NoteModel, String> {
     let note_exist: Result<NoteModel, Error> =
@@ -174,28 +1009,145 @@ pub async fn update_note_service(
         }
     };
 
+    if body.content.is_none() {
+        ensure_content_readable(&existing_note)?;
+    }
+
+    check_publish_schedule(
+        body.publish_at.or(existing_note.publish_at),
+        body.unpublish_at.or(existing_note.unpublish_at),
+    )?;
+    if let Some(title) = &body.title {
+        ensure_note_title_free(pool, title, Some(note_id), user_id).await?;
+    }
+    let category = note_category(pool, &body.category, body.category_id, user_id).await?;
+
+    // a field left out of the body keeps its current value
     let note_update_model = NoteUpdateModel {
-        title: body.title.as_ref().unwrap().to_string(),
-        content: body.content.as_ref().unwrap().to_string(),
-        category: body.category.as_ref().map(|c| c.to_string()),
+        title: body
+            .title
+            .clone()
+            .unwrap_or_else(|| existing_note.title.clone()),
+        content: body
+            .content
+            .clone()
+            .unwrap_or_else(|| existing_note.content.clone()),
+        category,
+        tags: body.tags.as_deref().map(normalize_tags),
         published: body.published.map(|c| c),
+        due_at: body.due_at,
+        remind_at: body.remind_at,
+        publish_at: body.publish_at,
+        unpublish_at: body.unpublish_at,
         updated_by: Option::from(user_id),
     };
 
     let note = existing_note;
-    match repository::update_note(pool, note_id, note_update_model, note).await {
-        Ok(note) => Ok(note),
-        Err(err) => {
-            // Handle the error
-            eprintln!("Error update or patch note: {:?}", err);
-            Err(constants::NOTE_CANT_BE_PATCHED.to_string())
+    let links = parse_note_links(&note_update_model.content);
+    let note =
+        match repository::update_note(pool, note_id, note_update_model, note, &links, if_match)
+            .await
+        {
+            Ok(note) => note,
+            // the note was deleted since it was read above, or its version did not match
+            Err(Error::RowNotFound) if if_match.is_none() => {
+                return Err(constants::NOTE_NOT_FOUND.to_string());
+            }
+            Err(Error::RowNotFound) => return Err(constants::NOTE_VERSION_MISMATCH.to_string()),
+            // the category the note was filed under is gone
+            Err(err) if is_missing_category(&err) => {
+                return Err(category_constants::CATEGORY_NOT_FOUND.to_string());
+            }
+            Err(Error::Database(err)) if err.code().as_deref() == Some("23505") => {
+                return Err(constants::NOTE_TITLE_ALREADY_EXIST.to_string());
+            }
+            Err(err) => {
+                // Handle the error
+                eprintln!("Error update or patch note: {:?}", err);
+                return Err(constants::NOTE_CANT_BE_PATCHED.to_string());
+            }
+        };
+
+    ensure_note_slug(pool, note).await
+}
+
+pub async fn patch_note_service(
+    pool: &PgPool,
+    note_id: Uuid,
+    body: &PatchNoteSchema,
+    if_match: Option<&[i32]>,
+    user_id: Uuid,
+) -> Result<NoteModel, String> {
+    let note = get_owned_note(pool, note_id, user_id).await?;
+    if matches!(body.title, Some(None)) || matches!(body.content, Some(None)) {
+        return Err(constants::NOTE_PATCH_INVALID.to_string());
+    }
+    check_publish_schedule(
+        body.publish_at.unwrap_or(note.publish_at),
+        body.unpublish_at.unwrap_or(note.unpublish_at),
+    )?;
+    if let Some(Some(title)) = &body.title {
+        ensure_note_title_free(pool, title, Some(note_id), user_id).await?;
+    }
+    if let Some(Some(notebook_id)) = body.notebook_id {
+        notebook_service::get_notebook_service(pool, notebook_id, user_id).await?;
+    }
+    let category = match (body.category_id, &body.category) {
+        (Some(category_id), _) => Some(note_category(pool, &None, category_id, user_id).await?),
+        (None, Some(category)) => Some(note_category(pool, category, None, user_id).await?),
+        (None, None) => None,
+    };
+
+    let patch = NotePatchModel {
+        title: body.title.clone().flatten(),
+        content: body.content.clone().flatten(),
+        category,
+        tags: body
+            .tags
+            .as_ref()
+            .map(|tags| normalize_tags(tags.as_deref().unwrap_or_default())),
+        published: body.published.map(|published| published.unwrap_or(false)),
+        notebook_id: body.notebook_id,
+        due_at: body.due_at,
+        remind_at: body.remind_at,
+        publish_at: body.publish_at,
+        unpublish_at: body.unpublish_at,
+    };
+    // an empty patch changes nothing, but a stale If-Match still has to fail
+    if patch.is_empty() {
+        let version = note.version.unwrap_or_default();
+        if if_match.is_some_and(|versions| !versions.contains(&version)) {
+            return Err(constants::NOTE_VERSION_MISMATCH.to_string());
         }
+        return Ok(note);
     }
+
+    let links = patch.content.as_deref().map(parse_note_links);
+    let note =
+        match repository::patch_note(pool, note_id, &patch, links.as_deref(), user_id, if_match)
+            .await
+        {
+            Ok(note) => note,
+            Err(Error::RowNotFound) => return Err(constants::NOTE_VERSION_MISMATCH.to_string()),
+            Err(err) if is_missing_category(&err) => {
+                return Err(category_constants::CATEGORY_NOT_FOUND.to_string());
+            }
+            Err(Error::Database(err)) if err.code().as_deref() == Some("23505") => {
+                return Err(constants::NOTE_TITLE_ALREADY_EXIST.to_string());
+            }
+            Err(err) => {
+                eprintln!("Error patch note: {:?}", err);
+                return Err(constants::NOTE_CANT_BE_PATCHED.to_string());
+            }
+        };
+
+    ensure_note_slug(pool, note).await
 }
 
 pub async fn delete_note_service(
     pool: &PgPool,
     note_id: Uuid,
+    if_match: Option<&[i32]>,
     user_id: Uuid,
 ) -> Result<i32, String> {
     let note_exist: Result<NoteModel, Error> =
@@ -218,7 +1170,10 @@ pub async fn delete_note_service(
         }
     };
 
-    match repository::delete_note_user_by_id(pool, note_id, user_id).await {
+    // without If-Match nothing deleted means the note went away since it was read
+    match repository::delete_note_user_by_id(pool, note_id, user_id, if_match).await {
+        Ok(0) if if_match.is_none() => Err(constants::NOTE_NOT_FOUND.to_string()),
+        Ok(0) => Err(constants::NOTE_VERSION_MISMATCH.to_string()),
         Ok(note) => Ok(note),
         Err(err) => {
             // Handle the error
@@ -227,3 +1182,1049 @@ pub async fn delete_note_service(
         }
     }
 }
+
+pub async fn move_note_service(
+    pool: &PgPool,
+    note_id: Uuid,
+    body: &MoveNoteSchema,
+    user_id: Uuid,
+) -> Result<NoteModel, String> {
+    get_owned_note(pool, note_id, user_id).await?;
+    if let Some(notebook_id) = body.notebook_id {
+        notebook_service::get_notebook_service(pool, notebook_id, user_id).await?;
+    }
+
+    match repository::update_note_notebook(pool, note_id, body.notebook_id, user_id).await {
+        Ok(note) => Ok(note),
+        Err(err) => {
+            eprintln!("Error move note: {:?}", err);
+            Err(constants::NOTE_CANT_BE_MOVED.to_string())
+        }
+    }
+}
+
+pub async fn set_note_pinned_service(
+    pool: &PgPool,
+    note_id: Uuid,
+    pinned: bool,
+    user_id: Uuid,
+) -> Result<NoteModel, String> {
+    get_owned_note(pool, note_id, user_id).await?;
+
+    match repository::update_note_pinned(pool, note_id, pinned, user_id).await {
+        Ok(note) => Ok(note),
+        Err(err) => {
+            eprintln!("Error pin note: {:?}", err);
+            Err(constants::NOTE_CANT_BE_PATCHED.to_string())
+        }
+    }
+}
+
+pub async fn set_note_archived_service(
+    pool: &PgPool,
+    note_id: Uuid,
+    archived: bool,
+    user_id: Uuid,
+) -> Result<NoteModel, String> {
+    get_owned_note(pool, note_id, user_id).await?;
+
+    match repository::update_note_archived(pool, note_id, archived, user_id).await {
+        Ok(note) => Ok(note),
+        Err(err) => {
+            eprintln!("Error archive note: {:?}", err);
+            Err(constants::NOTE_CANT_BE_PATCHED.to_string())
+        }
+    }
+}
+
+// favorites are listed with the user's own notes, so only those may be favorited
+pub async fn set_note_favorite_service(
+    pool: &PgPool,
+    note_id: Uuid,
+    favorite: bool,
+    user_id: Uuid,
+) -> Result<NoteModel, String> {
+    let mut note = get_owned_note(pool, note_id, user_id).await?;
+
+    let result = if favorite {
+        repository::save_note_favorite(pool, note_id, user_id).await
+    } else {
+        repository::delete_note_favorite(pool, note_id, user_id).await
+    };
+    match result {
+        Ok(_) => {
+            note.favorite = Some(favorite);
+            Ok(note)
+        }
+        Err(err) => {
+            eprintln!("Error favorite note: {:?}", err);
+            Err(constants::NOTE_FAVORITE_CANT_BE_SAVED.to_string())
+        }
+    }
+}
+
+fn bulk_tags(tags: &[String]) -> Result<Vec<String>, String> {
+    let tags = normalize_tags(tags);
+    if tags.is_empty()
+        || tags.len() > MAX_TAGS
+        || tags.iter().any(|tag| tag.chars().count() > MAX_TAG_LENGTH)
+    {
+        return Err(constants::NOTE_BULK_ACTION_INVALID.to_string());
+    }
+    Ok(tags)
+}
+
+// every id is reported on its own, a note the caller does not own fails as not found and
+// the ones that can be changed are updated together in a single transaction
+pub async fn bulk_note_service(
+    pool: &PgPool,
+    body: &BulkNoteSchema,
+    user_id: Uuid,
+) -> Result<BulkNoteResponse, String> {
+    let mut note_ids: Vec<Uuid> = Vec::new();
+    for id in &body.ids {
+        if !note_ids.contains(id) {
+            note_ids.push(*id);
+        }
+    }
+
+    let restore = matches!(body.action, BulkNoteAction::Restore);
+    let mut update = match &body.action {
+        BulkNoteAction::Delete => NoteBulkUpdate::Delete,
+        BulkNoteAction::Restore => NoteBulkUpdate::Restore,
+        BulkNoteAction::Publish => NoteBulkUpdate::Publish { slugs: Vec::new() },
+        BulkNoteAction::Unpublish => NoteBulkUpdate::Unpublish,
+        BulkNoteAction::SetCategory { category } => match non_empty(&Some(category.clone())) {
+            Some(category) => NoteBulkUpdate::SetCategory {
+                category: category_service::get_category_by_name_service(pool, &category, user_id)
+                    .await?
+                    .name,
+            },
+            None => return Err(constants::NOTE_BULK_ACTION_INVALID.to_string()),
+        },
+        BulkNoteAction::AddTags { tags } => NoteBulkUpdate::AddTags {
+            tags: bulk_tags(tags)?,
+        },
+        BulkNoteAction::RemoveTags { tags } => NoteBulkUpdate::RemoveTags {
+            tags: bulk_tags(tags)?,
+        },
+        BulkNoteAction::Move { notebook_id } => {
+            if let Some(notebook_id) = notebook_id {
+                notebook_service::get_notebook_service(pool, *notebook_id, user_id).await?;
+            }
+            NoteBulkUpdate::Move {
+                notebook_id: *notebook_id,
+            }
+        }
+    };
+
+    let bulk_error = |err: Error| {
+        eprintln!("Error apply bulk note action: {:?}", err);
+        constants::NOTE_BULK_CANT_BE_APPLIED.to_string()
+    };
+    let mut tx = pool.begin().await.map_err(bulk_error)?;
+    let notes = repository::get_notes_user_by_ids_for_update(&mut tx, &note_ids, user_id)
+        .await
+        .map_err(bulk_error)?;
+
+    // a note can't come back from the trash while a live note of the user has its title,
+    // and of two trashed notes sharing a title only the first one is restored
+    let mut taken_titles: Vec<String> = Vec::new();
+    if restore {
+        let titles: Vec<String> = notes.iter().map(|note| note.title.clone()).collect();
+        taken_titles = repository::get_live_note_titles_user(&mut tx, &titles, user_id)
+            .await
+            .map_err(bulk_error)?;
+    }
+
+    let mut succeeded: Vec<Uuid> = Vec::new();
+    let mut failed: Vec<BulkNoteFailure> = Vec::new();
+    let mut slugs: Vec<String> = Vec::new();
+    for id in note_ids {
+        let reason = match notes.iter().find(|note| note.id == id) {
+            None => Some(constants::NOTE_NOT_FOUND),
+            Some(note) if restore && note.deleted_at.is_none() => {
+                Some(constants::NOTE_NOT_IN_TRASH)
+            }
+            Some(note) if !restore && note.deleted_at.is_some() => Some(constants::NOTE_IN_TRASH),
+            Some(note) if restore && taken_titles.contains(&note.title) => {
+                Some(constants::NOTE_TITLE_ALREADY_EXIST)
+            }
+            Some(note) => {
+                if restore {
+                    taken_titles.push(note.title.clone());
+                }
+                slugs.push(build_note_slug(note));
+                None
+            }
+        };
+        match reason {
+            Some(reason) => failed.push(BulkNoteFailure {
+                id,
+                reason: reason.to_string(),
+            }),
+            None => succeeded.push(id),
+        }
+    }
+    if let NoteBulkUpdate::Publish {
+        slugs: publish_slugs,
+    } = &mut update
+    {
+        *publish_slugs = slugs;
+    }
+
+    if !succeeded.is_empty() {
+        repository::bulk_update_notes(&mut tx, &succeeded, &update, user_id)
+            .await
+            .map_err(bulk_error)?;
+    }
+    tx.commit().await.map_err(bulk_error)?;
+
+    Ok(BulkNoteResponse { succeeded, failed })
+}
+
+fn to_front_matter(note: &NoteModel) -> NoteFrontMatter {
+    NoteFrontMatter {
+        title: note.title.clone(),
+        category: note
+            .category
+            .clone()
+            .filter(|category| !category.is_empty()),
+        tags: note.tags.clone().unwrap_or_default(),
+        published: note.published.unwrap_or(false),
+        created_at: note.created_at,
+        updated_at: note.updated_at,
+    }
+}
+
+fn note_markdown_file(note: &NoteModel) -> io::Result<String> {
+    let front_matter = serde_yaml::to_string(&to_front_matter(note)).map_err(io::Error::other)?;
+    Ok(format!("---\n{}---\n\n{}", front_matter, note.content))
+}
+
+// validates the filter and sort up front so those errors still get a proper status, the
+// archive itself is written while it is being sent
+pub async fn export_notes_service(
+    pool: &PgPool,
+    filter: &FilterOptions,
+    paging: &PaginationQuery,
+    format: ExportFormat,
+    user_id: Uuid,
+) -> Result<NoteExportStream, String> {
+    let sort = parse_note_sort(paging)?;
+    let note_filter = build_notes_filter(pool, filter, user_id).await?;
+    match repository::get_notes_count_unreadable_user(pool, user_id, &note_filter).await {
+        Ok(0) => {}
+        Ok(_) => return Err(constants::NOTE_CONTENT_UNREADABLE.to_string()),
+        Err(err) => {
+            eprintln!("error count unreadable notes: {:?}", err);
+            return Err(constants::NOTE_CANT_BE_EXPORTED.to_string());
+        }
+    }
+
+    let (sender, receiver) = mpsc::channel(4);
+    actix_web::rt::spawn(write_notes_archive(
+        pool.clone(),
+        note_filter,
+        sort,
+        format,
+        user_id,
+        sender,
+    ));
+    Ok(receiver)
+}
+
+// once the first chunk is out the status can no longer change, so a failure is logged and
+// ends the stream with an error
+async fn write_notes_archive(
+    pool: PgPool,
+    filter: NoteFilter,
+    sort: Vec<NoteSortKey>,
+    format: ExportFormat,
+    user_id: Uuid,
+    mut sender: mpsc::Sender<Result<Bytes, io::Error>>,
+) {
+    let keyset = NoteKeysetQuery {
+        sort,
+        cursor: None,
+        forward: true,
+        limit: EXPORT_BATCH_SIZE,
+    };
+    if let Err(err) =
+        stream_notes_archive(&pool, &filter, keyset, format, user_id, &mut sender).await
+    {
+        eprintln!("error export notes: {:?}", err);
+        let _ = sender
+            .send(Err(io::Error::other(constants::NOTE_CANT_BE_EXPORTED)))
+            .await;
+    }
+}
+
+async fn send_chunk(
+    sender: &mut mpsc::Sender<Result<Bytes, io::Error>>,
+    chunk: Bytes,
+) -> io::Result<()> {
+    if chunk.is_empty() {
+        return Ok(());
+    }
+    sender
+        .send(Ok(chunk))
+        .await
+        .map_err(|err| io::Error::new(io::ErrorKind::BrokenPipe, err))
+}
+
+// the notes are read in keyset batches, so memory stays flat however many notes there are
+async fn stream_notes_archive(
+    pool: &PgPool,
+    filter: &NoteFilter,
+    mut keyset: NoteKeysetQuery,
+    format: ExportFormat,
+    user_id: Uuid,
+    sender: &mut mpsc::Sender<Result<Bytes, io::Error>>,
+) -> io::Result<()> {
+    let mut zip = ZipStream::new();
+    if format == ExportFormat::Json {
+        start_json_archive(&mut zip)?;
+    }
+
+    let mut written = 0;
+    loop {
+        let notes = repository::get_notes_user_keyset(pool, user_id, filter, &keyset)
+            .await
+            .map_err(io::Error::other)?;
+        for note in &notes {
+            if note.content_unreadable {
+                return Err(io::Error::other(constants::NOTE_CONTENT_UNREADABLE));
+            }
+            match format {
+                ExportFormat::Markdown => {
+                    zip.start_file(&format!("{}.md", build_note_slug(note)))?;
+                    zip.write_all(note_markdown_file(note)?.as_bytes())?;
+                }
+                ExportFormat::Json => {
+                    let entry = NoteArchiveEntry {
+                        front_matter: to_front_matter(note),
+                        content: note.content.clone(),
+                    };
+                    write_json_archive_entry(&mut zip, &entry, written == 0)?;
+                }
+            }
+            written += 1;
+        }
+        send_chunk(sender, zip.take_chunk()).await?;
+
+        if (notes.len() as i64) < keyset.limit {
+            break;
+        }
+        keyset.cursor = notes
+            .last()
+            .map(|note| note_cursor_value(note, &keyset.sort));
+    }
+
+    if format == ExportFormat::Json {
+        finish_json_archive(&mut zip)?;
+    }
+    send_chunk(sender, zip.finish()?).await
+}
+
+// the json export is one document that is written a note at a time
+fn start_json_archive(zip: &mut ZipStream) -> io::Result<()> {
+    zip.start_file(JSON_ARCHIVE_FILE)?;
+    zip.write_all(b"{\"notes\":[")
+}
+
+fn write_json_archive_entry(
+    zip: &mut ZipStream,
+    entry: &NoteArchiveEntry,
+    first: bool,
+) -> io::Result<()> {
+    if !first {
+        zip.write_all(b",")?;
+    }
+    zip.write_all(&serde_json::to_vec(entry)?)
+}
+
+fn finish_json_archive(zip: &mut ZipStream) -> io::Result<()> {
+    zip.write_all(b"]}")
+}
+
+// a markdown file holds one note, a json document as many as were exported
+fn import_file_size_limit(name: &str) -> u64 {
+    if name.to_lowercase().ends_with(".json") {
+        IMPORT_MAX_DOCUMENT_SIZE
+    } else {
+        IMPORT_MAX_FILE_SIZE
+    }
+}
+
+// None for a file that is not an archived note, Some(Err) for one that can not be read
+fn read_archive_entries(file: &ArchiveFile) -> Option<Result<Vec<NoteArchiveEntry>, String>> {
+    let name = file.name.to_lowercase();
+    let is_markdown = name.ends_with(".md") || name.ends_with(".markdown");
+    if name.starts_with("__macosx/") || !(is_markdown || name.ends_with(".json")) {
+        return None;
+    }
+    if file.truncated {
+        return Some(Err(constants::NOTE_ARCHIVE_FILE_TOO_LARGE.to_string()));
+    }
+
+    let text = String::from_utf8_lossy(&file.bytes);
+    let entries = if is_markdown {
+        parse_markdown_note(&text).map(|entry| vec![entry])
+    } else {
+        serde_json::from_str::<NoteArchive>(&text)
+            .ok()
+            .map(|archive| archive.notes)
+    };
+    Some(entries.ok_or_else(|| constants::NOTE_ARCHIVE_FILE_INVALID.to_string()))
+}
+
+fn strip_line_break(value: &str) -> &str {
+    value
+        .strip_prefix("\r\n")
+        .or_else(|| value.strip_prefix('\n'))
+        .unwrap_or(value)
+}
+
+// `---` fenced YAML front matter followed by the markdown content
+fn parse_markdown_note(text: &str) -> Option<NoteArchiveEntry> {
+    let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+    let rest = text
+        .strip_prefix("---\n")
+        .or_else(|| text.strip_prefix("---\r\n"))?;
+    let (index, after) = rest.match_indices("\n---").find_map(|(index, _)| {
+        let after = &rest[index + 4..];
+        let closes = after.is_empty() || after.starts_with('\n') || after.starts_with("\r\n");
+        closes.then_some((index, after))
+    })?;
+
+    let front_matter: NoteFrontMatter = serde_yaml::from_str(&rest[..index + 1]).ok()?;
+    let content = strip_line_break(strip_line_break(after)).to_string();
+    Some(NoteArchiveEntry {
+        front_matter,
+        content,
+    })
+}
+
+fn to_import_model(entry: NoteArchiveEntry) -> Result<NoteImportModel, String> {
+    let title = entry.front_matter.title.trim().to_string();
+    let category = non_empty(&entry.front_matter.category);
+    let tags = normalize_tags(&entry.front_matter.tags);
+    // a note in a json document is held to the size of a single markdown file
+    let valid = !title.is_empty()
+        && title.chars().count() <= MAX_TITLE_LENGTH
+        && !entry.content.trim().is_empty()
+        && entry.content.len() as u64 <= IMPORT_MAX_FILE_SIZE
+        && category
+            .as_ref()
+            .is_none_or(|category| category.chars().count() <= MAX_CATEGORY_LENGTH)
+        && tags.len() <= MAX_TAGS
+        && tags.iter().all(|tag| tag.chars().count() <= MAX_TAG_LENGTH);
+    if !valid {
+        return Err(constants::NOTE_ARCHIVE_FILE_INVALID.to_string());
+    }
+
+    Ok(NoteImportModel {
+        title,
+        content: entry.content,
+        category,
+        tags,
+        published: entry.front_matter.published,
+        created_at: entry.front_matter.created_at,
+        updated_at: entry.front_matter.updated_at,
+    })
+}
+
+// "title (2)", "title (3)" and so on
+fn import_title_suffix(copy: u32) -> String {
+    format!(" ({})", copy + 1)
+}
+
+// "title (copy)", "title (copy 2)" and so on
+fn duplicate_title_suffix(copy: u32) -> String {
+    match copy {
+        1 => " (copy)".to_string(),
+        _ => format!(" (copy {})", copy),
+    }
+}
+
+// the first title with a suffix that none of the user's live notes uses, cut short so the
+// suffix always fits the column
+async fn free_note_title(
+    pool: &PgPool,
+    title: &str,
+    suffix: fn(u32) -> String,
+    user_id: Uuid,
+) -> Result<String, Error> {
+    let mut copy = 1;
+    loop {
+        let suffix = suffix(copy);
+        let base: String = title
+            .chars()
+            .take(MAX_TITLE_LENGTH - suffix.len())
+            .collect();
+        let candidate = format!("{}{}", base.trim_end(), suffix);
+        if repository::get_notes_user_by_title(pool, candidate.clone(), user_id)
+            .await?
+            .is_empty()
+        {
+            return Ok(candidate);
+        }
+        copy += 1;
+    }
+}
+
+fn import_result(
+    file: &str,
+    status: NoteImportStatus,
+    note: Option<&NoteModel>,
+    reason: Option<&str>,
+) -> NoteImportResult {
+    NoteImportResult {
+        file: file.to_string(),
+        status,
+        id: note.map(|note| note.id),
+        title: note.map(|note| note.title.clone()),
+        reason: reason.map(|reason| reason.to_string()),
+    }
+}
+
+async fn import_note_entry(
+    pool: &PgPool,
+    file: &str,
+    mut model: NoteImportModel,
+    conflict: ImportConflict,
+    user_id: Uuid,
+) -> NoteImportResult {
+    let failed = |reason: &str| import_result(file, NoteImportStatus::Failed, None, Some(reason));
+    if let Some(name) = &model.category {
+        match category_service::get_or_save_category_service(pool, name, user_id).await {
+            Ok(category) => model.category = Some(category.name),
+            Err(err) => return failed(&err),
+        }
+    }
+    let existing =
+        match repository::get_notes_user_by_title(pool, model.title.clone(), user_id).await {
+            Ok(existing) => existing.into_iter().next(),
+            Err(err) => {
+                eprintln!("Error getting existing notes: {:?}", err);
+                return failed(constants::EXISTING_NOTE_CANT_BE_FETCHED);
+            }
+        };
+
+    let mut status = NoteImportStatus::Created;
+    if let Some(existing) = existing {
+        match conflict {
+            ImportConflict::Skip => {
+                return import_result(
+                    file,
+                    NoteImportStatus::Skipped,
+                    None,
+                    Some(constants::NOTE_TITLE_ALREADY_EXIST),
+                );
+            }
+            ImportConflict::Rename => {
+                model.title =
+                    match free_note_title(pool, &model.title, import_title_suffix, user_id).await {
+                        Ok(title) => title,
+                        Err(err) => {
+                            eprintln!("Error getting existing notes: {:?}", err);
+                            return failed(constants::EXISTING_NOTE_CANT_BE_FETCHED);
+                        }
+                    };
+                status = NoteImportStatus::Renamed;
+            }
+            ImportConflict::Overwrite => {
+                let links = parse_note_links(&model.content);
+                let note = match repository::overwrite_imported_note(
+                    pool,
+                    existing.id,
+                    model,
+                    &links,
+                    user_id,
+                )
+                .await
+                {
+                    Ok(note) => note,
+                    Err(err) => {
+                        eprintln!("Error overwrite imported note: {:?}", err);
+                        return failed(constants::NOTE_CANT_BE_PATCHED);
+                    }
+                };
+                return match ensure_note_slug(pool, note).await {
+                    Ok(note) => {
+                        import_result(file, NoteImportStatus::Overwritten, Some(&note), None)
+                    }
+                    Err(err) => failed(&err),
+                };
+            }
+        }
+    }
+
+    let links = parse_note_links(&model.content);
+    let note = match repository::save_imported_note(pool, model, &links, user_id).await {
+        Ok(note) => note,
+        Err(Error::Database(err)) if err.code().as_deref() == Some("23505") => {
+            return failed(constants::NOTE_TITLE_ALREADY_EXIST);
+        }
+        Err(err) => {
+            eprintln!("Error saving imported note: {:?}", err);
+            return failed(constants::NOTE_CANT_BE_SAVED);
+        }
+    };
+    match ensure_note_slug(pool, note).await {
+        Ok(note) => import_result(file, status, Some(&note), None),
+        Err(err) => failed(&err),
+    }
+}
+
+// takes the zip written by the export, markdown files and json documents may be mixed and
+// anything else in the archive is ignored
+pub async fn import_notes_service(
+    pool: &PgPool,
+    bytes: &[u8],
+    conflict: ImportConflict,
+    user_id: Uuid,
+) -> Result<Vec<NoteImportResult>, String> {
+    let files = match archive::read_zip(bytes, IMPORT_MAX_FILES, import_file_size_limit) {
+        Ok(files) => files,
+        Err(err) if err.kind() == io::ErrorKind::InvalidData => {
+            return Err(constants::NOTE_ARCHIVE_TOO_MANY_FILES.to_string());
+        }
+        Err(err) => {
+            eprintln!("error read note archive: {:?}", err);
+            return Err(constants::NOTE_ARCHIVE_INVALID.to_string());
+        }
+    };
+
+    let mut results: Vec<NoteImportResult> = Vec::new();
+    for file in files {
+        let failed =
+            |reason: &str| import_result(&file.name, NoteImportStatus::Failed, None, Some(reason));
+        let entries = match read_archive_entries(&file) {
+            Some(Ok(entries)) => entries,
+            Some(Err(err)) => {
+                results.push(failed(&err));
+                continue;
+            }
+            None => continue,
+        };
+
+        for entry in entries {
+            let result = match to_import_model(entry) {
+                Ok(model) => import_note_entry(pool, &file.name, model, conflict, user_id).await,
+                Err(err) => failed(&err),
+            };
+            results.push(result);
+        }
+    }
+
+    if results.is_empty() {
+        return Err(constants::NOTE_ARCHIVE_INVALID.to_string());
+    }
+    Ok(results)
+}
+
+// notes are stored as markdown, `format=html` additionally returns the sanitized rendering
+pub fn wants_html(options: &FormatOptions) -> Result<bool, String> {
+    match options.format.as_deref() {
+        None | Some("") | Some("markdown") => Ok(false),
+        Some("html") => Ok(true),
+        Some(_) => Err(constants::PARAMETER_FORMAT_INVALID.to_string()),
+    }
+}
+
+fn note_revision(note: &NoteModel) -> String {
+    note.version.unwrap_or_default().to_string()
+}
+
+// the ETag of a note is its version, the html rendering being a representation of its own.
+// resolved links change when other notes are renamed or deleted, so a note carrying them is
+// tagged with a digest of them too
+pub fn note_etag(note: &NoteModel, html: bool) -> String {
+    let mut etag = note.version.unwrap_or_default().to_string();
+    if let Some(links) = &note.links {
+        let mut digest = Sha256::new();
+        for link in links.iter() {
+            let id = link.id.map(|id| id.to_string()).unwrap_or_default();
+            digest.update(format!("{}\0{}\0", link.title, id));
+        }
+        etag.push_str(&format!(".{:x}", digest.finalize())[..17]);
+    }
+    if html {
+        etag.push_str("-html");
+    }
+    format!("\"{}\"", etag)
+}
+
+// turns If-Match tags back into versions, a tag that was not handed out here never matches
+pub fn etag_versions(tags: &[String]) -> Vec<i32> {
+    tags.iter()
+        .filter_map(|tag| {
+            tag.strip_prefix('"')?
+                .strip_suffix('"')?
+                .split(['.', '-'])
+                .next()?
+                .parse()
+                .ok()
+        })
+        .collect()
+}
+
+pub fn render_note_html(cache: &RenderCache, mut note: NoteModel) -> NoteModel {
+    note.content_html = Some(cache.get_or_render(note.id, &note_revision(&note), &note.content));
+    note
+}
+
+pub fn render_notes_html(cache: &RenderCache, notes: Vec<NoteModel>) -> Vec<NoteModel> {
+    notes
+        .into_iter()
+        .map(|note| render_note_html(cache, note))
+        .collect()
+}
+
+pub fn render_preview_service(body: &RenderNoteSchema) -> RenderedNoteResponse {
+    RenderedNoteResponse {
+        html: markdown::render(&body.content),
+    }
+}
+
+fn build_note_slug(note: &NoteModel) -> String {
+    let suffix = &note.id.simple().to_string()[..12];
+    let slug = utils::slugify(&note.title);
+    if slug.is_empty() {
+        return suffix.to_string();
+    }
+    format!("{}-{}", slug, suffix)
+}
+
+static NOTE_LINK_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\[\[([^\[\]\n]+)\]\]").unwrap());
+
+// the titles of the `[[Other Note Title]]` links in the content, in order and without
+// duplicates. the migration backfilling note_links uses the same pattern
+fn parse_note_links(content: &str) -> Vec<String> {
+    let mut titles: Vec<String> = Vec::new();
+    for capture in NOTE_LINK_REGEX.captures_iter(content) {
+        let title = capture[1].trim();
+        if !title.is_empty()
+            && title.chars().count() <= MAX_TITLE_LENGTH
+            && !titles.iter().any(|t| t == title)
+        {
+            titles.push(title.to_string());
+        }
+    }
+    titles
+}
+
+// published notes get a slug the first time they are published, it never changes afterwards
+async fn ensure_note_slug(pool: &PgPool, note: NoteModel) -> Result<NoteModel, String> {
+    if !note.published.unwrap_or(false) || note.slug.is_some() {
+        return Ok(note);
+    }
+
+    match repository::update_note_slug(pool, note.id, build_note_slug(&note)).await {
+        Ok(note) => Ok(note),
+        Err(err) => {
+            eprintln!("Error saving note slug: {:?}", err);
+            Err(constants::NOTE_SLUG_CANT_BE_SAVED.to_string())
+        }
+    }
+}
+
+fn to_public_note(note: NoteModel) -> PublicNoteResponse {
+    PublicNoteResponse {
+        title: note.title,
+        content: note.content,
+        category: note.category,
+        slug: note.slug,
+        created_at: note.created_at,
+        updated_at: note.updated_at,
+    }
+}
+
+pub fn render_public_note_html(note: &PublicNoteResponse) -> String {
+    let mut meta = String::new();
+    if let Some(category) = note.category.as_ref().filter(|c| !c.is_empty()) {
+        meta.push_str(&html::escape(category));
+    }
+    if let Some(updated_at) = note.updated_at {
+        if !meta.is_empty() {
+            meta.push_str(" &middot; ");
+        }
+        meta.push_str(&updated_at.format("%Y-%m-%d %H:%M UTC").to_string());
+    }
+
+    let body = format!(
+        "<article>\n<h1>{}</h1>\n<p class=\"meta\">{}</p>\n<div class=\"content\">{}</div>\n</article>",
+        html::escape(&note.title),
+        meta,
+        markdown::render(&note.content)
+    );
+    html::render_page(&note.title, &body)
+}
+
+pub async fn get_public_note_service(
+    pool: &PgPool,
+    slug: &str,
+) -> Result<PublicNoteResponse, String> {
+    match repository::get_published_note_by_slug(pool, slug.to_string()).await {
+        Ok(note) => Ok(to_public_note(note)),
+        Err(err) => match err {
+            Error::RowNotFound => Err(constants::NOTE_NOT_FOUND.to_string()),
+            _ => {
+                eprintln!("error get public note {:?}", err);
+                Err(constants::PUBLIC_NOTE_CANT_BE_FETCHED.to_string())
+            }
+        },
+    }
+}
+
+// the newest published notes of a user. `note_url` turns a slug into the link of the public
+// note page, `feed_url` is where the feed was requested
+pub async fn get_user_feed_service(
+    pool: &PgPool,
+    cache: &RenderCache,
+    username: &str,
+    feed_url: String,
+    note_url: impl Fn(&str) -> String,
+) -> Result<Feed, String> {
+    let user = user_service::get_user_by_username_service(pool, username).await?;
+    let notes = match repository::get_published_notes_user(pool, user.id, FEED_SIZE).await {
+        Ok(notes) => notes,
+        Err(err) => {
+            eprintln!("error get published notes {:?}", err);
+            return Err(constants::PUBLIC_NOTE_CANT_BE_FETCHED.to_string());
+        }
+    };
+
+    let entries: Vec<FeedEntry> = notes
+        .into_iter()
+        .map(|note| {
+            let published = note.created_at.unwrap_or_default();
+            FeedEntry {
+                id: format!("urn:uuid:{}", note.id),
+                link: note_url(note.slug.as_deref().unwrap_or_default()),
+                category: note.category.clone().filter(|c| !c.is_empty()),
+                published,
+                updated: note.updated_at.unwrap_or(published),
+                content_html: cache.get_or_render(note.id, &note_revision(&note), &note.content),
+                title: note.title,
+            }
+        })
+        .collect();
+    let author = user
+        .fullname
+        .filter(|fullname| !fullname.trim().is_empty())
+        .unwrap_or(user.username);
+    Ok(Feed {
+        id: format!("urn:uuid:{}", user.id),
+        title: format!("Notes by {}", author),
+        author,
+        url: feed_url,
+        updated: entries
+            .iter()
+            .map(|entry| entry.updated)
+            .max()
+            .or(user.created_at)
+            .unwrap_or_default(),
+        entries,
+    })
+}
+
+pub async fn get_shared_note_service(
+    pool: &PgPool,
+    token: &str,
+) -> Result<PublicNoteResponse, String> {
+    let share_link = match repository::get_share_link_by_token(pool, token.to_string()).await {
+        Ok(share_link) => share_link,
+        Err(err) => {
+            return match err {
+                Error::RowNotFound => Err(constants::SHARE_LINK_NOT_FOUND.to_string()),
+                _ => {
+                    eprintln!("error get share link {:?}", err);
+                    Err(constants::SHARE_LINK_CANT_BE_FETCHED.to_string())
+                }
+            };
+        }
+    };
+
+    if let Some(expires_at) = share_link.expires_at {
+        if expires_at <= Utc::now() {
+            return Err(constants::SHARE_LINK_EXPIRED.to_string());
+        }
+    }
+
+    match repository::get_note_by_id(pool, share_link.note_id).await {
+        Ok(note) => Ok(to_public_note(note)),
+        Err(err) => match err {
+            Error::RowNotFound => Err(constants::NOTE_NOT_FOUND.to_string()),
+            _ => {
+                eprintln!("error get shared note {:?}", err);
+                Err(constants::PUBLIC_NOTE_CANT_BE_FETCHED.to_string())
+            }
+        },
+    }
+}
+
+async fn get_owned_note(pool: &PgPool, note_id: Uuid, user_id: Uuid) -> Result<NoteModel, String> {
+    match repository::get_note_user_by_id(pool, note_id, user_id).await {
+        Ok(note) => Ok(note),
+        Err(err) => match err {
+            Error::RowNotFound => Err(constants::NOTE_NOT_FOUND.to_string()),
+            _ => {
+                eprintln!("error get detail notes {:?}", err);
+                Err(constants::DETAIL_NOTE_CANT_BE_FETCHED.to_string())
+            }
+        },
+    }
+}
+
+pub async fn create_share_link_service(
+    pool: &PgPool,
+    note_id: Uuid,
+    body: &CreateShareLinkSchema,
+    user_id: Uuid,
+) -> Result<NoteShareLinkModel, String> {
+    get_owned_note(pool, note_id, user_id).await?;
+
+    let share_link_save_model = NoteShareLinkSaveModel {
+        note_id,
+        token: utils::generate_token(),
+        expires_at: body
+            .expires_in_hours
+            .map(|hours| Utc::now() + Duration::hours(hours)),
+        created_by: Option::from(user_id),
+    };
+
+    match repository::save_share_link(pool, share_link_save_model).await {
+        Ok(share_link) => Ok(share_link),
+        Err(err) => {
+            eprintln!("Error saving share link: {:?}", err);
+            Err(constants::SHARE_LINK_CANT_BE_SAVED.to_string())
+        }
+    }
+}
+
+pub async fn get_share_links_service(
+    pool: &PgPool,
+    note_id: Uuid,
+    user_id: Uuid,
+) -> Result<Vec<NoteShareLinkModel>, String> {
+    get_owned_note(pool, note_id, user_id).await?;
+
+    match repository::get_share_links_by_note_id(pool, note_id).await {
+        Ok(share_links) => Ok(share_links),
+        Err(err) => {
+            eprintln!("error get share links {:?}", err);
+            Err(constants::SHARE_LINK_CANT_BE_FETCHED.to_string())
+        }
+    }
+}
+
+pub async fn delete_share_link_service(
+    pool: &PgPool,
+    note_id: Uuid,
+    link_id: Uuid,
+    user_id: Uuid,
+) -> Result<i32, String> {
+    get_owned_note(pool, note_id, user_id).await?;
+
+    match repository::delete_share_link_by_id(pool, link_id, note_id).await {
+        Ok(0) => Err(constants::SHARE_LINK_NOT_FOUND.to_string()),
+        Ok(rows) => Ok(rows),
+        Err(err) => {
+            eprintln!("Error delete share link: {:?}", err);
+            Err(constants::SHARE_LINK_CANT_BE_DELETE.to_string())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::categories::schema::CategorySchema;
+
+    fn archive_entry(index: usize) -> NoteArchiveEntry {
+        NoteArchiveEntry {
+            front_matter: NoteFrontMatter {
+                title: format!("note {}", index),
+                category: None,
+                tags: vec!["export".to_string()],
+                published: index.is_multiple_of(2),
+                created_at: None,
+                updated_at: None,
+            },
+            content: format!("{} {}\n", index, "lorem ipsum ".repeat(16 * 1024)),
+        }
+    }
+
+    #[test]
+    fn json_export_larger_than_a_single_file_imports() {
+        let notes: Vec<NoteArchiveEntry> = (0..20).map(archive_entry).collect();
+
+        let mut zip = ZipStream::new();
+        let mut bytes = Vec::new();
+        start_json_archive(&mut zip).unwrap();
+        for (index, entry) in notes.iter().enumerate() {
+            write_json_archive_entry(&mut zip, entry, index == 0).unwrap();
+            bytes.extend_from_slice(&zip.take_chunk());
+        }
+        finish_json_archive(&mut zip).unwrap();
+        bytes.extend_from_slice(&zip.finish().unwrap());
+
+        let files = archive::read_zip(&bytes, IMPORT_MAX_FILES, import_file_size_limit).unwrap();
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].name, JSON_ARCHIVE_FILE);
+        assert!(files[0].bytes.len() as u64 > IMPORT_MAX_FILE_SIZE);
+
+        let entries = read_archive_entries(&files[0]).unwrap().unwrap();
+        assert_eq!(entries.len(), notes.len());
+        for (entry, note) in entries.into_iter().zip(&notes) {
+            let model = to_import_model(entry).unwrap();
+            assert_eq!(model.title, note.front_matter.title);
+            assert_eq!(model.content, note.content);
+            assert_eq!(model.tags, note.front_matter.tags);
+            assert_eq!(model.published, note.front_matter.published);
+        }
+    }
+
+    #[actix_web::test]
+    #[ignore = "needs a migrated database, run with DATABASE_URL set and --ignored"]
+    async fn duplicate_of_another_users_note_keeps_only_an_own_category() {
+        let url = std::env::var("DATABASE_URL").unwrap();
+        let pool = PgPool::connect(&url).await.unwrap();
+        let (owner, caller) = (Uuid::new_v4(), Uuid::new_v4());
+        let category = CategorySchema {
+            name: format!("work {}", owner.simple()),
+            color: None,
+            sort_order: None,
+        };
+        category_service::save_category_service(&pool, &category, owner)
+            .await
+            .unwrap();
+        let body: CreateNoteSchema = serde_json::from_value(serde_json::json!({
+            "title": format!("shared {}", owner.simple()),
+            "content": "content",
+            "category": category.name,
+            "published": true,
+        }))
+        .unwrap();
+        let source = save_note_service(&pool, &body, owner).await.unwrap();
+
+        // the caller has no category by that name, the copy is left without one
+        let copy = duplicate_note_service(&pool, source.id, caller)
+            .await
+            .unwrap();
+        assert_eq!(copy.created_by, Some(caller));
+        assert_eq!(non_empty(&copy.category), None);
+
+        category_service::save_category_service(&pool, &category, caller)
+            .await
+            .unwrap();
+        let copy = duplicate_note_service(&pool, source.id, caller)
+            .await
+            .unwrap();
+        assert_eq!(copy.category, Some(category.name));
+    }
+}
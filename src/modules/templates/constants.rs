@@ -0,0 +1,12 @@
+pub const TEMPLATE_NOT_FOUND: &str = "template not found";
+pub const TEMPLATE_RENDERED_NOTE_INVALID: &str =
+    "the rendered title has to be 1 to 255 characters and the rendered content can not be empty";
+pub const PARAMETER_TEMPLATE_ID_INVALID: &str = "parameter id is invalid";
+pub const TEMPLATE_FOUND: &str = "success get data template";
+pub const TEMPLATE_SUCCESS_SAVED: &str = "success saved data template";
+pub const TEMPLATE_SUCCESS_PATCHED: &str = "success patched data template";
+pub const TEMPLATE_SUCCESS_DELETED: &str = "success deleted data template";
+pub const TEMPLATE_CANT_BE_FETCHED: &str = "something went wrong while get template";
+pub const TEMPLATE_CANT_BE_SAVED: &str = "something went wrong while saving the template";
+pub const TEMPLATE_CANT_BE_PATCHED: &str = "something went wrong while updating the template";
+pub const TEMPLATE_CANT_BE_DELETE: &str = "something went wrong while delete the template";
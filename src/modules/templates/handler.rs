@@ -0,0 +1,253 @@
+use crate::infrastructure::http_lib::Response;
+use crate::modules::categories::constants as category_constants;
+use crate::modules::notebooks::constants as notebook_constants;
+use crate::modules::notes::constants as note_constants;
+use crate::modules::notes::model::NoteModel;
+use crate::modules::templates::constants;
+use crate::modules::templates::model::TemplateModel;
+use crate::modules::templates::schema::{InstantiateTemplateSchema, TemplateSchema};
+use crate::modules::templates::service;
+use crate::utils::utils;
+use crate::AppState;
+use actix_web::http::StatusCode;
+use actix_web::{delete, get, post, put, web, HttpRequest, HttpResponse, Responder};
+use validator::Validate;
+
+fn error_response(err: &str) -> HttpResponse {
+    let status = if err.contains(constants::TEMPLATE_NOT_FOUND) {
+        StatusCode::NOT_FOUND
+    } else if err.contains(constants::TEMPLATE_RENDERED_NOTE_INVALID)
+        || err.contains(notebook_constants::NOTEBOOK_NOT_FOUND)
+        || err.contains(category_constants::CATEGORY_NOT_FOUND)
+    {
+        StatusCode::BAD_REQUEST
+    } else if err.contains(note_constants::NOTE_TITLE_ALREADY_EXIST) {
+        StatusCode::CONFLICT
+    } else {
+        StatusCode::INTERNAL_SERVER_ERROR
+    };
+    let resp: Response<(), ()> = Response::error(status, err);
+    HttpResponse::build(status).json(resp)
+}
+
+#[get("")]
+pub async fn template_list_handler(data: web::Data<AppState>, req: HttpRequest) -> impl Responder {
+    //get user_id from authorization token
+    let user_id = utils::get_current_user_uuid_from_jwt(&data.cfg.clone(), req);
+    if let Err(err) = user_id {
+        let resp: Response<(), ()> = Response::error(StatusCode::UNAUTHORIZED, err.as_str());
+        return HttpResponse::Unauthorized().json(resp);
+    }
+
+    let templates = match service::get_templates_service(&data.db, user_id.unwrap()).await {
+        Ok(templates) => templates,
+        Err(err) => return error_response(&err),
+    };
+
+    let msg = constants::TEMPLATE_FOUND;
+    let resp: Response<Vec<TemplateModel>, ()> = Response::success(StatusCode::OK, templates, msg);
+    return HttpResponse::Ok().json(resp);
+}
+
+#[post("")]
+pub async fn create_template_handler(
+    body: web::Json<TemplateSchema>,
+    data: web::Data<AppState>,
+    req: HttpRequest,
+) -> impl Responder {
+    //get user_id from authorization token
+    let user_id = utils::get_current_user_uuid_from_jwt(&data.cfg.clone(), req);
+    if let Err(err) = user_id {
+        let resp: Response<(), ()> = Response::error(StatusCode::UNAUTHORIZED, err.as_str());
+        return HttpResponse::Unauthorized().json(resp);
+    }
+
+    //validate the struct from body
+    if let Err(errors) = body.validate() {
+        let resp = Response::custom(
+            StatusCode::BAD_REQUEST,
+            StatusCode::BAD_REQUEST.as_str(),
+            (),
+            errors,
+        );
+        return HttpResponse::BadRequest().json(resp);
+    }
+
+    let template = match service::save_template_service(&data.db, &body.0, user_id.unwrap()).await {
+        Ok(template) => template,
+        Err(err) => return error_response(&err),
+    };
+
+    let msg = constants::TEMPLATE_SUCCESS_SAVED;
+    let resp: Response<TemplateModel, ()> = Response::success(StatusCode::OK, template, msg);
+    return HttpResponse::Ok().json(resp);
+}
+
+#[get("/{id}")]
+pub async fn get_template_handler(
+    path: web::Path<String>,
+    data: web::Data<AppState>,
+    req: HttpRequest,
+) -> impl Responder {
+    let template_id_str = path.into_inner();
+
+    //get user_id from authorization token
+    let user_id = utils::get_current_user_uuid_from_jwt(&data.cfg.clone(), req);
+    if let Err(err) = user_id {
+        let resp: Response<(), ()> = Response::error(StatusCode::UNAUTHORIZED, err.as_str());
+        return HttpResponse::Unauthorized().json(resp);
+    }
+
+    // Attempt to parse the UUID from the path
+    let template_id = match uuid::Uuid::parse_str(&template_id_str) {
+        Ok(uuid) => uuid,
+        Err(err) => {
+            eprintln!("match uuid::Uuid::parse_str, got err : {:?}", err);
+            let msg = constants::PARAMETER_TEMPLATE_ID_INVALID;
+            let error_response: Response<(), ()> = Response::error(StatusCode::BAD_REQUEST, msg);
+            return HttpResponse::BadRequest().json(error_response);
+        }
+    };
+
+    let template =
+        match service::get_template_service(&data.db, template_id, user_id.unwrap()).await {
+            Ok(template) => template,
+            Err(err) => return error_response(&err),
+        };
+
+    let msg = constants::TEMPLATE_FOUND;
+    let resp: Response<TemplateModel, ()> = Response::success(StatusCode::OK, template, msg);
+    return HttpResponse::Ok().json(resp);
+}
+
+#[put("/{id}")]
+pub async fn edit_template_handler(
+    path: web::Path<String>,
+    body: web::Json<TemplateSchema>,
+    data: web::Data<AppState>,
+    req: HttpRequest,
+) -> impl Responder {
+    let template_id_str = path.into_inner();
+
+    //get user_id from authorization token
+    let user_id = utils::get_current_user_uuid_from_jwt(&data.cfg.clone(), req);
+    if let Err(err) = user_id {
+        let resp: Response<(), ()> = Response::error(StatusCode::UNAUTHORIZED, err.as_str());
+        return HttpResponse::Unauthorized().json(resp);
+    }
+
+    // Attempt to parse the UUID from the path
+    let template_id = match uuid::Uuid::parse_str(&template_id_str) {
+        Ok(uuid) => uuid,
+        Err(err) => {
+            eprintln!("match uuid::Uuid::parse_str, got err : {:?}", err);
+            let msg = constants::PARAMETER_TEMPLATE_ID_INVALID;
+            let error_response: Response<(), ()> = Response::error(StatusCode::BAD_REQUEST, msg);
+            return HttpResponse::BadRequest().json(error_response);
+        }
+    };
+
+    //validate the struct from body
+    if let Err(errors) = body.validate() {
+        let resp = Response::custom(
+            StatusCode::BAD_REQUEST,
+            StatusCode::BAD_REQUEST.as_str(),
+            (),
+            errors,
+        );
+        return HttpResponse::BadRequest().json(resp);
+    }
+
+    let template =
+        match service::update_template_service(&data.db, template_id, &body.0, user_id.unwrap())
+            .await
+        {
+            Ok(template) => template,
+            Err(err) => return error_response(&err),
+        };
+
+    let msg = constants::TEMPLATE_SUCCESS_PATCHED;
+    let resp: Response<TemplateModel, ()> = Response::success(StatusCode::OK, template, msg);
+    return HttpResponse::Ok().json(resp);
+}
+
+#[delete("/{id}")]
+pub async fn delete_template_handler(
+    path: web::Path<String>,
+    data: web::Data<AppState>,
+    req: HttpRequest,
+) -> impl Responder {
+    let template_id_str = path.into_inner();
+
+    //get user_id from authorization token
+    let user_id = utils::get_current_user_uuid_from_jwt(&data.cfg.clone(), req);
+    if let Err(err) = user_id {
+        let resp: Response<(), ()> = Response::error(StatusCode::UNAUTHORIZED, err.as_str());
+        return HttpResponse::Unauthorized().json(resp);
+    }
+
+    // Attempt to parse the UUID from the path
+    let template_id = match uuid::Uuid::parse_str(&template_id_str) {
+        Ok(uuid) => uuid,
+        Err(err) => {
+            eprintln!("match uuid::Uuid::parse_str, got err : {:?}", err);
+            let msg = constants::PARAMETER_TEMPLATE_ID_INVALID;
+            let error_response: Response<(), ()> = Response::error(StatusCode::BAD_REQUEST, msg);
+            return HttpResponse::BadRequest().json(error_response);
+        }
+    };
+
+    if let Err(err) =
+        service::delete_template_service(&data.db, template_id, user_id.unwrap()).await
+    {
+        return error_response(&err);
+    }
+
+    let msg = constants::TEMPLATE_SUCCESS_DELETED;
+    let resp: Response<(), ()> = Response::success(StatusCode::OK, (), msg);
+    return HttpResponse::Ok().json(resp);
+}
+
+#[post("/{id}")]
+pub async fn create_note_from_template_handler(
+    path: web::Path<String>,
+    body: web::Json<InstantiateTemplateSchema>,
+    data: web::Data<AppState>,
+    req: HttpRequest,
+) -> impl Responder {
+    let template_id_str = path.into_inner();
+
+    //get user_id from authorization token
+    let user_id = utils::get_current_user_uuid_from_jwt(&data.cfg.clone(), req);
+    if let Err(err) = user_id {
+        let resp: Response<(), ()> = Response::error(StatusCode::UNAUTHORIZED, err.as_str());
+        return HttpResponse::Unauthorized().json(resp);
+    }
+
+    // Attempt to parse the UUID from the path
+    let template_id = match uuid::Uuid::parse_str(&template_id_str) {
+        Ok(uuid) => uuid,
+        Err(err) => {
+            eprintln!("match uuid::Uuid::parse_str, got err : {:?}", err);
+            let msg = constants::PARAMETER_TEMPLATE_ID_INVALID;
+            let error_response: Response<(), ()> = Response::error(StatusCode::BAD_REQUEST, msg);
+            return HttpResponse::BadRequest().json(error_response);
+        }
+    };
+
+    let note = match service::create_note_from_template_service(
+        &data.db,
+        template_id,
+        &body.0,
+        user_id.unwrap(),
+    )
+    .await
+    {
+        Ok(note) => note,
+        Err(err) => return error_response(&err),
+    };
+
+    let msg = note_constants::NOTE_SUCCESS_SAVED;
+    let resp: Response<NoteModel, ()> = Response::success(StatusCode::OK, note, msg);
+    return HttpResponse::Ok().json(resp);
+}
@@ -0,0 +1,32 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+#[derive(Debug, FromRow, Deserialize, Serialize, Clone)]
+#[allow(non_snake_case)]
+pub struct TemplateModel {
+    pub id: Uuid,
+    pub name: String,
+    pub title: String,
+    pub content: String,
+    pub category: Option<String>,
+    pub tags: Vec<String>,
+    pub created_by: Uuid,
+    pub updated_by: Option<Uuid>,
+    #[serde(rename = "createdAt")]
+    pub created_at: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(rename = "updatedAt")]
+    pub updated_at: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(rename = "deletedAt")]
+    pub deleted_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[allow(non_snake_case)]
+pub struct TemplateSaveModel {
+    pub name: String,
+    pub title: String,
+    pub content: String,
+    pub category: Option<String>,
+    pub tags: Vec<String>,
+}
@@ -0,0 +1,83 @@
+use crate::modules::templates::model::{TemplateModel, TemplateSaveModel};
+use chrono::{DateTime, Utc};
+use sqlx::postgres::PgQueryResult;
+use sqlx::{Error, PgPool};
+use uuid::Uuid;
+
+pub async fn save_template(
+    pool: &PgPool,
+    body: TemplateSaveModel,
+    user_id: Uuid,
+) -> Result<TemplateModel, Error> {
+    let query = "INSERT INTO note_templates (name,title,content,category,tags,created_by) VALUES ($1, $2, $3, $4, $5, $6) RETURNING *";
+    sqlx::query_as::<_, TemplateModel>(query)
+        .bind(body.name)
+        .bind(body.title)
+        .bind(body.content)
+        .bind(body.category)
+        .bind(body.tags)
+        .bind(user_id)
+        .fetch_one(pool)
+        .await
+}
+
+pub async fn get_templates_user(pool: &PgPool, user_id: Uuid) -> Result<Vec<TemplateModel>, Error> {
+    let query = "SELECT * FROM note_templates WHERE deleted_at IS NULL AND created_by = $1 ORDER BY name, created_at";
+    sqlx::query_as::<_, TemplateModel>(query)
+        .bind(user_id)
+        .fetch_all(pool)
+        .await
+}
+
+pub async fn get_template_user_by_id(
+    pool: &PgPool,
+    template_id: Uuid,
+    user_id: Uuid,
+) -> Result<TemplateModel, Error> {
+    let query =
+        "SELECT * FROM note_templates WHERE deleted_at IS NULL AND id = $1 AND created_by = $2";
+    sqlx::query_as::<_, TemplateModel>(query)
+        .bind(template_id)
+        .bind(user_id)
+        .fetch_one(pool)
+        .await
+}
+
+pub async fn update_template(
+    pool: &PgPool,
+    template_id: Uuid,
+    body: TemplateSaveModel,
+    user_id: Uuid,
+) -> Result<TemplateModel, Error> {
+    let now = Utc::now();
+    let query = "UPDATE note_templates SET name = $1, title = $2, content = $3, category = $4, tags = $5, updated_by = $6, updated_at = $7 WHERE id = $8 AND created_by = $6 AND deleted_at IS NULL RETURNING *";
+    sqlx::query_as::<_, TemplateModel>(query)
+        .bind(body.name)
+        .bind(body.title)
+        .bind(body.content)
+        .bind(body.category)
+        .bind(body.tags)
+        .bind(user_id)
+        .bind(now)
+        .bind(template_id)
+        .fetch_one(pool)
+        .await
+}
+
+pub async fn delete_template_by_id(
+    pool: &PgPool,
+    template_id: Uuid,
+    user_id: Uuid,
+) -> Result<i32, Error> {
+    let now: DateTime<Utc> = Utc::now();
+    let rows_affected: PgQueryResult = sqlx::query::<_>(
+        "UPDATE note_templates SET deleted_at = $1 WHERE id = $2 AND created_by = $3 AND deleted_at IS NULL",
+    )
+    .bind(now)
+    .bind(template_id)
+    .bind(user_id)
+    .execute(pool)
+    .await?;
+
+    Ok(rows_affected.rows_affected() as i32)
+}
@@ -0,0 +1,21 @@
+use crate::modules::templates::handler;
+use actix_web::web;
+
+pub fn routes(conf: &mut web::ServiceConfig) {
+    let scope = web::scope("/templates")
+        .service(handler::template_list_handler)
+        .service(handler::create_template_handler)
+        .service(handler::get_template_handler)
+        .service(handler::edit_template_handler)
+        .service(handler::delete_template_handler);
+
+    conf.service(scope);
+}
+
+// creating a note from a template lives next to the other note routes
+pub fn note_routes(conf: &mut web::ServiceConfig) {
+    let scope =
+        web::scope("/notes/from-template").service(handler::create_note_from_template_handler);
+
+    conf.service(scope);
+}
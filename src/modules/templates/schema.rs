@@ -0,0 +1,34 @@
+use crate::modules::notes::schema::validate_tags;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use uuid::Uuid;
+use validator_derive::Validate;
+
+// used for both creating and replacing a template
+#[derive(Deserialize, Serialize, Debug, Validate)]
+pub struct TemplateSchema {
+    #[validate(length(min = 1, max = 255))]
+    pub name: String,
+    #[validate(length(min = 1, max = 255))]
+    pub title: String,
+    #[validate(length(min = 1))]
+    pub content: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[validate(length(min = 1, max = 100))]
+    pub category: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[validate(custom = "validate_tags")]
+    pub tags: Option<Vec<String>>,
+}
+
+// `variables` fill the custom placeholders and win over the built in ones, a placeholder
+// nobody fills in is left in the note as it is
+#[derive(Deserialize, Serialize, Debug)]
+pub struct InstantiateTemplateSchema {
+    #[serde(default)]
+    pub variables: HashMap<String, String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notebook_id: Option<Uuid>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub published: Option<bool>,
+}
@@ -0,0 +1,181 @@
+use crate::modules::notes::model::NoteModel;
+use crate::modules::notes::schema::CreateNoteSchema;
+use crate::modules::notes::service as note_service;
+use crate::modules::templates::constants;
+use crate::modules::templates::model::{TemplateModel, TemplateSaveModel};
+use crate::modules::templates::repository;
+use crate::modules::templates::schema::{InstantiateTemplateSchema, TemplateSchema};
+use crate::modules::users::service as user_service;
+use chrono::{SecondsFormat, Utc};
+use regex::{Captures, Regex};
+use sqlx::{Error, PgPool};
+use std::collections::HashMap;
+use std::sync::LazyLock;
+use uuid::Uuid;
+
+const MAX_TITLE_LENGTH: usize = 255;
+
+static PLACEHOLDER_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\{\{\s*([A-Za-z0-9_.\-]+)\s*\}\}").unwrap());
+
+fn to_template_save_model(body: &TemplateSchema) -> TemplateSaveModel {
+    TemplateSaveModel {
+        name: body.name.trim().to_string(),
+        title: body.title.to_string(),
+        content: body.content.to_string(),
+        category: body.category.as_ref().map(|c| c.to_string()),
+        tags: body
+            .tags
+            .as_deref()
+            .map(note_service::normalize_tags)
+            .unwrap_or_default(),
+    }
+}
+
+// replaces every `{{name}}` that has a value, anything else is kept as it was written
+fn render_template(text: &str, variables: &HashMap<String, String>) -> String {
+    PLACEHOLDER_REGEX
+        .replace_all(text, |capture: &Captures| {
+            variables
+                .get(&capture[1])
+                .cloned()
+                .unwrap_or_else(|| capture[0].to_string())
+        })
+        .into_owned()
+}
+
+// the built in placeholders, dates and times are in UTC
+async fn builtin_variables(
+    pool: &PgPool,
+    user_id: Uuid,
+) -> Result<HashMap<String, String>, String> {
+    let user = user_service::get_user_by_id_service(pool, user_id).await?;
+    let now = Utc::now();
+    Ok(HashMap::from([
+        ("date".to_string(), now.format("%Y-%m-%d").to_string()),
+        ("time".to_string(), now.format("%H:%M").to_string()),
+        (
+            "datetime".to_string(),
+            now.to_rfc3339_opts(SecondsFormat::Secs, true),
+        ),
+        ("weekday".to_string(), now.format("%A").to_string()),
+        ("user.username".to_string(), user.username),
+        (
+            "user.fullname".to_string(),
+            user.fullname.unwrap_or_default(),
+        ),
+        ("user.email".to_string(), user.email.unwrap_or_default()),
+    ]))
+}
+
+pub async fn get_templates_service(
+    pool: &PgPool,
+    user_id: Uuid,
+) -> Result<Vec<TemplateModel>, String> {
+    match repository::get_templates_user(pool, user_id).await {
+        Ok(templates) => Ok(templates),
+        Err(err) => {
+            eprintln!("error get templates {:?}", err);
+            Err(constants::TEMPLATE_CANT_BE_FETCHED.to_string())
+        }
+    }
+}
+
+pub async fn get_template_service(
+    pool: &PgPool,
+    template_id: Uuid,
+    user_id: Uuid,
+) -> Result<TemplateModel, String> {
+    match repository::get_template_user_by_id(pool, template_id, user_id).await {
+        Ok(template) => Ok(template),
+        Err(err) => match err {
+            Error::RowNotFound => Err(constants::TEMPLATE_NOT_FOUND.to_string()),
+            _ => {
+                eprintln!("error get template {:?}", err);
+                Err(constants::TEMPLATE_CANT_BE_FETCHED.to_string())
+            }
+        },
+    }
+}
+
+pub async fn save_template_service(
+    pool: &PgPool,
+    body: &TemplateSchema,
+    user_id: Uuid,
+) -> Result<TemplateModel, String> {
+    match repository::save_template(pool, to_template_save_model(body), user_id).await {
+        Ok(template) => Ok(template),
+        Err(err) => {
+            eprintln!("Error saving template: {:?}", err);
+            Err(constants::TEMPLATE_CANT_BE_SAVED.to_string())
+        }
+    }
+}
+
+pub async fn update_template_service(
+    pool: &PgPool,
+    template_id: Uuid,
+    body: &TemplateSchema,
+    user_id: Uuid,
+) -> Result<TemplateModel, String> {
+    match repository::update_template(pool, template_id, to_template_save_model(body), user_id)
+        .await
+    {
+        Ok(template) => Ok(template),
+        Err(Error::RowNotFound) => Err(constants::TEMPLATE_NOT_FOUND.to_string()),
+        Err(err) => {
+            eprintln!("Error update template: {:?}", err);
+            Err(constants::TEMPLATE_CANT_BE_PATCHED.to_string())
+        }
+    }
+}
+
+pub async fn delete_template_service(
+    pool: &PgPool,
+    template_id: Uuid,
+    user_id: Uuid,
+) -> Result<i32, String> {
+    match repository::delete_template_by_id(pool, template_id, user_id).await {
+        Ok(0) => Err(constants::TEMPLATE_NOT_FOUND.to_string()),
+        Ok(rows) => Ok(rows),
+        Err(err) => {
+            eprintln!("Error delete template: {:?}", err);
+            Err(constants::TEMPLATE_CANT_BE_DELETE.to_string())
+        }
+    }
+}
+
+// renders the template and saves the result like any other new note
+pub async fn create_note_from_template_service(
+    pool: &PgPool,
+    template_id: Uuid,
+    body: &InstantiateTemplateSchema,
+    user_id: Uuid,
+) -> Result<NoteModel, String> {
+    let template = get_template_service(pool, template_id, user_id).await?;
+
+    let mut variables = builtin_variables(pool, user_id).await?;
+    variables.extend(body.variables.clone());
+    let title = render_template(&template.title, &variables)
+        .trim()
+        .to_string();
+    let content = render_template(&template.content, &variables);
+    if title.is_empty() || title.chars().count() > MAX_TITLE_LENGTH || content.trim().is_empty() {
+        return Err(constants::TEMPLATE_RENDERED_NOTE_INVALID.to_string());
+    }
+
+    let note = CreateNoteSchema {
+        title,
+        content,
+        category: template.category,
+        category_id: None,
+        tags: Some(template.tags),
+        notebook_id: body.notebook_id,
+        published: body.published,
+        due_at: None,
+        remind_at: None,
+        publish_at: None,
+        unpublish_at: None,
+    };
+    note_service::save_note_service(pool, &note, user_id).await
+}
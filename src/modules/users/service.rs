@@ -7,7 +7,6 @@ use crate::modules::users::schema::{CreateUserRequest, UpdateUserRequest, UserRe
 use sqlx::{Error, PgPool};
 use uuid::Uuid;
 
-#[allow(dead_code)]
 pub async fn get_user_by_id_service(pool: &PgPool, user_id: Uuid) -> Result<UserModel, String> {
     match repository::get_user_by_id(pool, user_id).await {
         Ok(note) => Ok(note),
@@ -1,5 +1,11 @@
+use crate::modules::attachments::routes as attachment_routes;
 use crate::modules::auth::routes as auth_routes;
+use crate::modules::categories::routes as category_routes;
+use crate::modules::checklists::routes as checklist_routes;
+use crate::modules::comments::routes as comment_routes;
+use crate::modules::notebooks::routes as notebook_routes;
 use crate::modules::notes::routes as note_routes;
+use crate::modules::templates::routes as template_routes;
 use crate::modules::users::routes as user_routes;
 use actix_web::web;
 
@@ -8,7 +14,16 @@ pub fn initiate_routes(conf: &mut web::ServiceConfig) {
     let scope = web::scope("/api/v1")
         .configure(auth_routes::routes)
         .configure(user_routes::routes)
-        .configure(note_routes::routes);
+        .configure(notebook_routes::routes)
+        .configure(category_routes::routes)
+        .configure(template_routes::routes)
+        //nested note resources have to be registered before the notes scope
+        .configure(attachment_routes::routes)
+        .configure(checklist_routes::routes)
+        .configure(comment_routes::routes)
+        .configure(template_routes::note_routes)
+        .configure(note_routes::routes)
+        .configure(note_routes::public_routes);
 
     conf.service(scope);
 }
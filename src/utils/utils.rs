@@ -60,3 +60,21 @@ pub fn get_current_user_uuid_from_jwt(cfg: &Config, req: HttpRequest) -> Result<
 
     Ok(uuid_zero)
 }
+
+pub fn slugify(value: &str) -> String {
+    let mut slug = String::new();
+    for c in value.to_lowercase().chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c);
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    slug.truncate(80);
+    slug.trim_matches('-').to_string()
+}
+
+// two random v4 uuids give 244 bits of entropy, enough for an unguessable token
+pub fn generate_token() -> String {
+    format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple())
+}